@@ -0,0 +1,134 @@
+// src/analytics/equity.rs
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::sync::Mutex;
+
+use crate::portfolio::Portfolio;
+use crate::types::EquityPoint;
+
+/// 实盘运行中的权益曲线，按固定间隔记录快照并提示何时推送绩效报告
+pub struct EquityTracker {
+    /// 相邻两个快照的最小间隔
+    spacing: Duration,
+    /// 推送绩效报告的间隔，从第一个快照起算
+    report_every: Duration,
+    state: Mutex<TrackerState>,
+}
+
+#[derive(Default)]
+struct TrackerState {
+    curve: Vec<EquityPoint>,
+    last_report: Option<DateTime<Utc>>,
+}
+
+impl EquityTracker {
+    pub fn new(spacing: Duration, report_every: Duration) -> Self {
+        Self {
+            spacing,
+            report_every,
+            state: Mutex::new(TrackerState::default()),
+        }
+    }
+
+    /// 记录一个快照，距上一个快照不足间隔时忽略，返回是否已记录
+    pub fn record(&self, point: EquityPoint) -> bool {
+        let mut state = self.state.lock().expect("equity tracker lock poisoned");
+        if let Some(last) = state.curve.last() {
+            if point.timestamp - last.timestamp < self.spacing {
+                return false;
+            }
+        }
+        state.curve.push(point);
+        true
+    }
+
+    /// 距上次报告（或第一个快照）已满报告间隔时返回 true，并以 `now` 作为本次报告时间
+    pub fn take_report_due(&self, now: DateTime<Utc>) -> bool {
+        let mut state = self.state.lock().expect("equity tracker lock poisoned");
+        let since = match (state.last_report, state.curve.first()) {
+            (Some(last), _) => last,
+            (None, Some(first)) => first.timestamp,
+            (None, None) => return false,
+        };
+        if now - since < self.report_every {
+            return false;
+        }
+        state.last_report = Some(now);
+        true
+    }
+
+    pub fn curve(&self) -> Vec<EquityPoint> {
+        let state = self.state.lock().expect("equity tracker lock poisoned");
+        state.curve.clone()
+    }
+}
+
+/// 按持仓账本估算的权益：启动时的权益加上此后的已实现和未实现盈亏
+///
+/// 持仓市值按最新标记价计，空头为负数，其余部分计为现金。
+pub fn ledger_equity(portfolio: &Portfolio, base: Decimal, now: DateTime<Utc>) -> EquityPoint {
+    let equity = base + portfolio.realized_pnl() + portfolio.unrealized_pnl();
+    let position_value: Decimal = portfolio
+        .positions()
+        .iter()
+        .map(|p| p.size * p.current_price)
+        .sum();
+
+    EquityPoint {
+        timestamp: now,
+        equity,
+        cash: equity - position_value,
+        position_value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portfolio::CostBasis;
+    use crate::types::{test_symbol, FeeAsset, Fill, PositionSide, Side};
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_ledger_equity_and_report_schedule() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let portfolio = Portfolio::new(CostBasis::AverageCost);
+        portfolio.record_fill(&Fill {
+            order_id: "1".to_string(),
+            symbol: test_symbol("BTCUSDT"),
+            side: Side::Buy,
+            price: dec!(100),
+            size: dec!(2),
+            fee: dec!(1),
+            fee_asset: FeeAsset::Quote,
+            position_side: PositionSide::Both,
+            timestamp: start,
+        });
+        portfolio.mark(&test_symbol("BTCUSDT"), dec!(110));
+
+        // 启动权益 1000，浮盈 19
+        let point = ledger_equity(&portfolio, dec!(1000), start);
+        assert_eq!(point.equity, dec!(1019));
+        assert_eq!(point.position_value, dec!(220));
+        assert_eq!(point.cash, dec!(799));
+
+        let tracker = EquityTracker::new(Duration::minutes(1), Duration::days(1));
+        assert!(!tracker.take_report_due(start));
+        assert!(tracker.record(point.clone()));
+        // 间隔不足一分钟的快照不记录
+        let mut early = point.clone();
+        early.timestamp = start + Duration::seconds(30);
+        assert!(!tracker.record(early));
+        let mut later = point;
+        later.timestamp = start + Duration::minutes(1);
+        assert!(tracker.record(later));
+        assert_eq!(tracker.curve().len(), 2);
+
+        // 首个快照满一天后推送，之后再隔一天
+        assert!(!tracker.take_report_due(start + Duration::hours(23)));
+        assert!(tracker.take_report_due(start + Duration::days(1)));
+        assert!(!tracker.take_report_due(start + Duration::hours(30)));
+        assert!(tracker.take_report_due(start + Duration::days(2)));
+    }
+}
@@ -0,0 +1,325 @@
+// src/analytics/mod.rs
+mod equity;
+pub use equity::{ledger_equity, EquityTracker};
+
+mod report;
+pub use report::PerformanceReport;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::portfolio::{CostBasis, Portfolio};
+use crate::types::{EquityPoint, Fill};
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+
+/// 回测或实盘的绩效指标
+///
+/// 收益率、回撤等比例均以小数表示（0.05 即 5%）。
+#[derive(Debug, Clone, Serialize)]
+pub struct PerformanceMetrics {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub initial_equity: Decimal,
+    pub final_equity: Decimal,
+    pub total_return: f64,
+    pub annualized_return: f64,
+    pub sharpe_ratio: f64,
+    pub sortino_ratio: f64,
+    pub max_drawdown: f64,
+    /// 最长回撤持续时间（秒），从前高开始到收复前高或区间结束
+    pub max_drawdown_duration_secs: i64,
+    /// 已平仓交易笔数（每次平仓计一笔）
+    pub total_trades: usize,
+    pub win_rate: f64,
+    /// 总盈利 / 总亏损，没有亏损交易时为空
+    pub profit_factor: Option<f64>,
+    pub average_win: Decimal,
+    pub average_loss: Decimal,
+    /// 持仓时间占比
+    pub exposure_time: f64,
+    /// 成交额 / 平均权益
+    pub turnover: f64,
+    pub total_fees: Decimal,
+}
+
+impl PerformanceMetrics {
+    pub fn compute(fills: &[Fill], equity_curve: &[EquityPoint]) -> Self {
+        let initial_equity = equity_curve.first().map(|p| p.equity).unwrap_or_default();
+        let final_equity = equity_curve.last().map(|p| p.equity).unwrap_or_default();
+        let start = equity_curve.first().map(|p| p.timestamp);
+        let end = equity_curve.last().map(|p| p.timestamp);
+
+        let total_return = if initial_equity.is_zero() {
+            0.0
+        } else {
+            ratio(final_equity, initial_equity) - 1.0
+        };
+        let elapsed_secs = match (start, end) {
+            (Some(start), Some(end)) => (end - start).num_seconds() as f64,
+            _ => 0.0,
+        };
+        let annualized_return = if elapsed_secs > 0.0 && total_return > -1.0 {
+            (1.0 + total_return).powf(SECONDS_PER_YEAR / elapsed_secs) - 1.0
+        } else {
+            0.0
+        };
+
+        let returns = period_returns(equity_curve);
+        let periods_per_year = if returns.is_empty() || elapsed_secs <= 0.0 {
+            0.0
+        } else {
+            SECONDS_PER_YEAR / (elapsed_secs / returns.len() as f64)
+        };
+        let (sharpe_ratio, sortino_ratio) = risk_adjusted(&returns, periods_per_year);
+
+        let (max_drawdown, max_drawdown_duration_secs) = max_drawdown(equity_curve);
+
+        let trade_pnls = closed_trade_pnls(fills);
+        let wins: Vec<Decimal> = trade_pnls
+            .iter()
+            .copied()
+            .filter(|p| *p > Decimal::ZERO)
+            .collect();
+        let losses: Vec<Decimal> = trade_pnls
+            .iter()
+            .copied()
+            .filter(|p| *p <= Decimal::ZERO)
+            .collect();
+        let gross_profit: Decimal = wins.iter().sum();
+        let gross_loss: Decimal = -losses.iter().sum::<Decimal>();
+
+        let exposure_time = if equity_curve.is_empty() {
+            0.0
+        } else {
+            equity_curve
+                .iter()
+                .filter(|p| !p.position_value.is_zero())
+                .count() as f64
+                / equity_curve.len() as f64
+        };
+
+        let traded: Decimal = fills.iter().map(|f| (f.price * f.size).abs()).sum();
+        let average_equity = if equity_curve.is_empty() {
+            Decimal::ZERO
+        } else {
+            equity_curve.iter().map(|p| p.equity).sum::<Decimal>()
+                / Decimal::from(equity_curve.len())
+        };
+
+        Self {
+            start,
+            end,
+            initial_equity,
+            final_equity,
+            total_return,
+            annualized_return,
+            sharpe_ratio,
+            sortino_ratio,
+            max_drawdown,
+            max_drawdown_duration_secs,
+            total_trades: trade_pnls.len(),
+            win_rate: if trade_pnls.is_empty() {
+                0.0
+            } else {
+                wins.len() as f64 / trade_pnls.len() as f64
+            },
+            profit_factor: if gross_loss.is_zero() {
+                None
+            } else {
+                Some(ratio(gross_profit, gross_loss))
+            },
+            average_win: average(&wins),
+            average_loss: average(&losses),
+            exposure_time,
+            turnover: ratio(traded, average_equity),
+            total_fees: fills.iter().map(|f| f.quote_fee()).sum(),
+        }
+    }
+}
+
+fn ratio(numerator: Decimal, denominator: Decimal) -> f64 {
+    if denominator.is_zero() {
+        return 0.0;
+    }
+    (numerator / denominator).to_f64().unwrap_or(0.0)
+}
+
+fn average(values: &[Decimal]) -> Decimal {
+    if values.is_empty() {
+        return Decimal::ZERO;
+    }
+    values.iter().sum::<Decimal>() / Decimal::from(values.len())
+}
+
+fn period_returns(equity_curve: &[EquityPoint]) -> Vec<f64> {
+    equity_curve
+        .windows(2)
+        .filter(|w| !w[0].equity.is_zero())
+        .map(|w| ratio(w[1].equity, w[0].equity) - 1.0)
+        .collect()
+}
+
+/// 年化夏普比率与索提诺比率（无风险利率取 0）
+fn risk_adjusted(returns: &[f64], periods_per_year: f64) -> (f64, f64) {
+    if returns.len() < 2 {
+        return (0.0, 0.0);
+    }
+
+    let n = returns.len() as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+    let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let downside = (returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / n).sqrt();
+    let scale = periods_per_year.sqrt();
+
+    let sharpe = if variance > 0.0 {
+        mean / variance.sqrt() * scale
+    } else {
+        0.0
+    };
+    let sortino = if downside > 0.0 {
+        mean / downside * scale
+    } else {
+        0.0
+    };
+    (sharpe, sortino)
+}
+
+/// 最大回撤比例及最长回撤持续时间（秒）
+fn max_drawdown(equity_curve: &[EquityPoint]) -> (f64, i64) {
+    let Some(first) = equity_curve.first() else {
+        return (0.0, 0);
+    };
+
+    let mut peak = first.equity;
+    let mut peak_time = first.timestamp;
+    let mut max_dd = 0.0;
+    let mut max_duration = 0;
+
+    for point in equity_curve {
+        if point.equity >= peak {
+            peak = point.equity;
+            peak_time = point.timestamp;
+            continue;
+        }
+
+        max_dd = f64::max(max_dd, ratio(peak - point.equity, peak));
+        max_duration = max_duration.max((point.timestamp - peak_time).num_seconds());
+    }
+
+    (max_dd, max_duration)
+}
+
+/// 按平均成本法在账本中回放成交，返回每次平仓的已实现盈亏（已扣除双边手续费）
+///
+/// 卖出超过持仓的部分按开空处理，空头买入平仓同样计为一笔交易。
+fn closed_trade_pnls(fills: &[Fill]) -> Vec<Decimal> {
+    let ledger = Portfolio::new(CostBasis::AverageCost).with_short_selling(true);
+    fills
+        .iter()
+        .filter_map(|fill| ledger.record_fill(fill))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{test_symbol, FeeAsset, PositionSide, Side};
+    use chrono::{Duration, TimeZone};
+    use rust_decimal_macros::dec;
+
+    fn curve(values: &[(Decimal, Decimal)]) -> Vec<EquityPoint> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &(equity, position_value))| EquityPoint {
+                timestamp: start + Duration::days(i as i64),
+                equity,
+                cash: equity - position_value,
+                position_value,
+            })
+            .collect()
+    }
+
+    fn fill(side: Side, price: Decimal, size: Decimal) -> Fill {
+        Fill {
+            order_id: "1".to_string(),
+            symbol: test_symbol("BTCUSDT"),
+            side,
+            price,
+            size,
+            fee: Decimal::ZERO,
+            fee_asset: FeeAsset::Quote,
+            position_side: PositionSide::Both,
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_drawdown_and_returns() {
+        let equity = curve(&[
+            (dec!(100), dec!(0)),
+            (dec!(110), dec!(110)),
+            (dec!(99), dec!(99)),
+            (dec!(104.5), dec!(0)),
+            (dec!(121), dec!(0)),
+        ]);
+        let metrics = PerformanceMetrics::compute(&[], &equity);
+
+        assert!((metrics.total_return - 0.21).abs() < 1e-12);
+        assert!((metrics.max_drawdown - 0.1).abs() < 1e-12);
+        // 第 1 天见顶，第 4 天收复
+        assert_eq!(metrics.max_drawdown_duration_secs, 2 * 86400);
+        assert!((metrics.exposure_time - 0.4).abs() < 1e-12);
+        assert!(metrics.annualized_return > metrics.total_return);
+        assert!(metrics.sharpe_ratio > 0.0);
+        assert!(metrics.sortino_ratio > metrics.sharpe_ratio);
+    }
+
+    #[test]
+    fn test_trade_statistics() {
+        let fills = vec![
+            fill(Side::Buy, dec!(100), dec!(2)),
+            fill(Side::Sell, dec!(110), dec!(1)),
+            fill(Side::Sell, dec!(95), dec!(1)),
+            fill(Side::Buy, dec!(100), dec!(1)),
+            fill(Side::Sell, dec!(130), dec!(1)),
+        ];
+        let equity = curve(&[(dec!(1000), dec!(0)), (dec!(1035), dec!(0))]);
+        let metrics = PerformanceMetrics::compute(&fills, &equity);
+
+        assert_eq!(metrics.total_trades, 3);
+        assert!((metrics.win_rate - 2.0 / 3.0).abs() < 1e-12);
+        assert_eq!(metrics.average_win, dec!(20));
+        assert_eq!(metrics.average_loss, dec!(-5));
+        assert_eq!(metrics.profit_factor, Some(8.0));
+        // 成交额 635，平均权益 1017.5
+        assert!((metrics.turnover - 635.0 / 1017.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_short_trades() {
+        let fills = vec![
+            fill(Side::Sell, dec!(100), dec!(1)),
+            fill(Side::Buy, dec!(90), dec!(1)),
+            fill(Side::Sell, dec!(100), dec!(1)),
+            fill(Side::Buy, dec!(105), dec!(1)),
+        ];
+        let metrics = PerformanceMetrics::compute(&fills, &[]);
+
+        assert_eq!(metrics.total_trades, 2);
+        assert_eq!(metrics.average_win, dec!(10));
+        assert_eq!(metrics.average_loss, dec!(-5));
+    }
+
+    #[test]
+    fn test_empty_inputs() {
+        let metrics = PerformanceMetrics::compute(&[], &[]);
+        assert_eq!(metrics.total_trades, 0);
+        assert_eq!(metrics.total_return, 0.0);
+        assert_eq!(metrics.profit_factor, None);
+    }
+}
@@ -0,0 +1,153 @@
+// src/analytics/report.rs
+use super::PerformanceMetrics;
+
+use crate::error::Error;
+use crate::notification::{dingtalk::DingTalkBot, NotificationMessage};
+
+/// 绩效报告，可输出为 JSON 摘要或推送到钉钉的 Markdown
+#[derive(Debug, Clone)]
+pub struct PerformanceReport {
+    pub title: String,
+    pub metrics: PerformanceMetrics,
+}
+
+impl PerformanceReport {
+    pub fn new(title: impl Into<String>, metrics: PerformanceMetrics) -> Self {
+        Self {
+            title: title.into(),
+            metrics,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, Error> {
+        Ok(serde_json::to_string_pretty(&self.metrics)?)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let m = &self.metrics;
+        let period = match (m.start, m.end) {
+            (Some(start), Some(end)) => format!(
+                "{} ~ {}",
+                start.format("%Y-%m-%d %H:%M"),
+                end.format("%Y-%m-%d %H:%M")
+            ),
+            _ => "-".to_string(),
+        };
+        let profit_factor = m
+            .profit_factor
+            .map(|pf| format!("{:.2}", pf))
+            .unwrap_or_else(|| "-".to_string());
+
+        format!(
+            "### {}\n\n\
+             - 区间: {}\n\
+             - 初始权益: {:.2}\n\
+             - 最终权益: {:.2}\n\
+             - 总收益率: {}\n\
+             - 年化收益率: {}\n\
+             - 夏普比率: {:.2}\n\
+             - 索提诺比率: {:.2}\n\
+             - 最大回撤: {}（持续 {}）\n\
+             - 交易次数: {}\n\
+             - 胜率: {}\n\
+             - 盈亏比: {}\n\
+             - 平均盈利: {:.2}\n\
+             - 平均亏损: {:.2}\n\
+             - 持仓时间占比: {}\n\
+             - 换手率: {:.2}\n\
+             - 手续费: {:.2}\n",
+            self.title,
+            period,
+            m.initial_equity,
+            m.final_equity,
+            percent(m.total_return),
+            percent(m.annualized_return),
+            m.sharpe_ratio,
+            m.sortino_ratio,
+            percent(m.max_drawdown),
+            format_duration(m.max_drawdown_duration_secs),
+            m.total_trades,
+            percent(m.win_rate),
+            profit_factor,
+            m.average_win,
+            m.average_loss,
+            percent(m.exposure_time),
+            m.turnover,
+            m.total_fees,
+        )
+    }
+
+    /// 通过钉钉机器人推送 Markdown 报告
+    pub async fn send(&self, dingtalk: &DingTalkBot) -> Result<(), Error> {
+        dingtalk
+            .send_markdown(self.title.clone(), self.to_markdown())
+            .await
+    }
+}
+
+impl NotificationMessage for PerformanceReport {
+    fn to_notification_message(&self) -> String {
+        self.to_markdown()
+    }
+}
+
+fn percent(value: f64) -> String {
+    format!("{:.2}%", value * 100.0)
+}
+
+fn format_duration(secs: i64) -> String {
+    let days = secs / 86400;
+    let hours = secs % 86400 / 3600;
+    let minutes = secs % 3600 / 60;
+
+    match (days, hours) {
+        (0, 0) => format!("{}分钟", minutes),
+        (0, _) => format!("{}小时{}分钟", hours, minutes),
+        _ => format!("{}天{}小时", days, hours),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::EquityPoint;
+    use chrono::{TimeZone, Utc};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_report_output() -> Result<(), Error> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let equity = vec![
+            EquityPoint {
+                timestamp: start,
+                equity: dec!(1000),
+                cash: dec!(1000),
+                position_value: dec!(0),
+            },
+            EquityPoint {
+                timestamp: start + chrono::Duration::hours(26),
+                equity: dec!(1100),
+                cash: dec!(1100),
+                position_value: dec!(0),
+            },
+        ];
+        let report = PerformanceReport::new(
+            "BTCUSDT 回测报告",
+            PerformanceMetrics::compute(&[], &equity),
+        );
+
+        let markdown = report.to_markdown();
+        assert!(markdown.starts_with("### BTCUSDT 回测报告"));
+        assert!(markdown.contains("总收益率: 10.00%"));
+        assert!(markdown.contains("盈亏比: -"));
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()?)?;
+        assert_eq!(json["final_equity"], "1100");
+        assert!((json["total_return"].as_f64().unwrap() - 0.1).abs() < 1e-12);
+
+        assert_eq!(format_duration(26 * 3600), "1天2小时");
+        assert_eq!(format_duration(90 * 60), "1小时30分钟");
+
+        Ok(())
+    }
+}
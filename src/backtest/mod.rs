@@ -0,0 +1,259 @@
+// src/backtest/mod.rs
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::analytics::PerformanceMetrics;
+use crate::config::TradingConfig;
+use crate::data::MultiTimeframe;
+use crate::error::Error;
+use crate::exchange::PaperExchange;
+use crate::execution::SignalExecutor;
+use crate::portfolio::Portfolio;
+use crate::risk::RiskManager;
+use crate::strategy::Strategy;
+use crate::types::{EquityPoint, Fill, Interval, Kline, Symbol};
+
+/// 默认手续费率（0.1%）
+const DEFAULT_FEE_RATE: Decimal = dec!(0.001);
+
+/// 回测结果
+#[derive(Debug, Clone, Serialize)]
+pub struct BacktestResult {
+    pub symbol: Symbol,
+    pub initial_cash: Decimal,
+    pub final_equity: Decimal,
+    pub realized_pnl: Decimal,
+    pub unrealized_pnl: Decimal,
+    pub trades: Vec<Fill>,
+    pub equity_curve: Vec<EquityPoint>,
+}
+
+impl BacktestResult {
+    pub fn metrics(&self) -> PerformanceMetrics {
+        PerformanceMetrics::compute(&self.trades, &self.equity_curve)
+    }
+}
+
+/// 事件驱动回测：逐根回放K线，经由与实盘相同的 `SignalExecutor` 下单
+pub struct Backtester {
+    config: Arc<TradingConfig>,
+    strategy: Box<dyn Strategy>,
+    initial_cash: Decimal,
+    fee_rate: Decimal,
+    slippage: Decimal,
+    interval: Interval,
+}
+
+impl Backtester {
+    pub fn new(
+        config: Arc<TradingConfig>,
+        strategy: Box<dyn Strategy>,
+        initial_cash: Decimal,
+    ) -> Self {
+        let interval = config.interval;
+        Self {
+            config,
+            strategy,
+            initial_cash,
+            fee_rate: DEFAULT_FEE_RATE,
+            slippage: Decimal::ZERO,
+            interval,
+        }
+    }
+
+    /// 回测K线的周期，默认取配置的 `interval`，用于聚合 `timeframes`
+    pub fn with_interval(mut self, interval: Interval) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_fee_rate(mut self, fee_rate: Decimal) -> Self {
+        self.fee_rate = fee_rate;
+        self
+    }
+
+    pub fn with_slippage(mut self, slippage: Decimal) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    pub async fn run(&self, symbol: &Symbol, klines: &[Kline]) -> Result<BacktestResult, Error> {
+        let exchange = PaperExchange::new(self.initial_cash)
+            .with_fee_rate(self.fee_rate)
+            .with_slippage(self.slippage);
+        let portfolio = Arc::new(Portfolio::new(self.config.cost_basis));
+        let risk_manager = RiskManager::new(self.config.clone(), portfolio.clone());
+        let executor = SignalExecutor::new(&exchange, self.strategy.as_ref(), &risk_manager);
+
+        let resamplers = self.config.resamplers();
+        for resampler in &resamplers {
+            resampler.check_source(self.interval)?;
+        }
+        // 长周期随回放逐根增量聚合，策略看不到未来的K线
+        let mut higher = vec![Vec::new(); resamplers.len()];
+
+        let mut equity_curve = Vec::with_capacity(klines.len());
+
+        for (i, kline) in klines.iter().enumerate() {
+            // 先撮合挂单，再在收盘时生成信号
+            exchange.on_kline(symbol, kline);
+            for fill in risk_manager.orders().sync(&exchange).await? {
+                portfolio.record_fill(&fill);
+            }
+            portfolio.mark(symbol, kline.close);
+            risk_manager.update(kline.timestamp);
+
+            for (resampler, series) in resamplers.iter().zip(higher.iter_mut()) {
+                resampler.update(series, kline);
+            }
+            let mut view = MultiTimeframe::new(self.interval, &klines[..=i]);
+            for (resampler, series) in resamplers.iter().zip(&higher) {
+                view = view.with_series(resampler.interval(), series);
+            }
+
+            match self.strategy.generate_signal_multi(symbol, &view).await {
+                Ok(signal) => {
+                    if let Err(e) = executor.execute(symbol, signal, kline.close).await {
+                        warn!("Backtest order failed at {}: {}", kline.timestamp, e);
+                    }
+                }
+                // 数据不足等策略错误视为观望
+                Err(Error::StrategyError(_)) => {}
+                Err(e) => return Err(e),
+            }
+
+            equity_curve.push(exchange.equity_point(kline.timestamp));
+        }
+
+        let final_equity = equity_curve
+            .last()
+            .map(|p| p.equity)
+            .unwrap_or(self.initial_cash);
+
+        Ok(BacktestResult {
+            symbol: symbol.clone(),
+            initial_cash: self.initial_cash,
+            final_equity,
+            realized_pnl: portfolio.realized_pnl(),
+            unrealized_pnl: portfolio.unrealized_pnl(),
+            trades: portfolio.fills(),
+            equity_curve,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+    use crate::types::{test_symbol, Side, Signal};
+    use async_trait::async_trait;
+    use chrono::{Duration, TimeZone, Utc};
+
+    /// 按K线序号输出预设信号的测试策略
+    struct ScriptedStrategy {
+        signals: Vec<(usize, Signal)>,
+    }
+
+    #[async_trait]
+    impl Strategy for ScriptedStrategy {
+        async fn generate_signal(&self, _symbol: &Symbol, data: &[Kline]) -> Result<Signal, Error> {
+            let index = data.len() - 1;
+            Ok(self
+                .signals
+                .iter()
+                .find(|(i, _)| *i == index)
+                .map(|(_, s)| s.clone())
+                .unwrap_or(Signal::Hold))
+        }
+
+        fn calculate_position_size(&self, _price: Decimal) -> Decimal {
+            dec!(1)
+        }
+    }
+
+    fn klines(bars: &[(Decimal, Decimal, Decimal, Decimal)]) -> Vec<Kline> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        bars.iter()
+            .enumerate()
+            .map(|(i, &(open, high, low, close))| Kline {
+                timestamp: start + Duration::hours(i as i64),
+                open,
+                high,
+                low,
+                close,
+                volume: dec!(10),
+            })
+            .collect()
+    }
+
+    fn backtester(signals: Vec<(usize, Signal)>) -> Backtester {
+        let mut config = test_config();
+        config.risk_limits.stop_loss_rate = dec!(0.05);
+        Backtester::new(
+            Arc::new(config),
+            Box::new(ScriptedStrategy { signals }),
+            dec!(1000),
+        )
+        .with_fee_rate(Decimal::ZERO)
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_and_equity_curve() -> Result<(), Error> {
+        let data = klines(&[
+            (dec!(100), dec!(101), dec!(99), dec!(100)),
+            (dec!(100), dec!(106), dec!(99), dec!(105)),
+            (dec!(105), dec!(111), dec!(104), dec!(110)),
+            (dec!(110), dec!(121), dec!(109), dec!(120)),
+        ]);
+
+        let result = backtester(vec![(1, Signal::Buy), (3, Signal::Sell)])
+            .run(&test_symbol("BTCUSDT"), &data)
+            .await?;
+
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(result.trades[0].side, Side::Buy);
+        assert_eq!(result.trades[0].price, dec!(105));
+        assert_eq!(result.trades[1].side, Side::Sell);
+        assert_eq!(result.trades[1].price, dec!(120));
+
+        let equity: Vec<Decimal> = result.equity_curve.iter().map(|p| p.equity).collect();
+        assert_eq!(equity, vec![dec!(1000), dec!(1000), dec!(1005), dec!(1015)]);
+        assert_eq!(result.final_equity, dec!(1015));
+        assert_eq!(result.realized_pnl, dec!(15));
+        assert_eq!(result.unrealized_pnl, Decimal::ZERO);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stop_loss_triggers() -> Result<(), Error> {
+        let data = klines(&[
+            (dec!(100), dec!(101), dec!(99), dec!(100)),
+            (dec!(100), dec!(100), dec!(97), dec!(98)),
+            // 跳空低开，止损按开盘价成交
+            (dec!(90), dec!(92), dec!(88), dec!(91)),
+            (dec!(91), dec!(93), dec!(90), dec!(92)),
+        ]);
+
+        let result = backtester(vec![(0, Signal::Buy)])
+            .run(&test_symbol("BTCUSDT"), &data)
+            .await?;
+
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(result.trades[1].side, Side::Sell);
+        assert_eq!(result.trades[1].price, dec!(90));
+        assert_eq!(result.final_equity, dec!(990));
+        // 止损成交同样记入账本
+        assert_eq!(result.realized_pnl, dec!(-10));
+        assert_eq!(
+            result.equity_curve.last().unwrap().position_value,
+            Decimal::ZERO
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,464 @@
+// src/cli.rs
+use anyhow::{bail, Context};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+use rust_decimal::Decimal;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::info;
+
+use rust_quant_trading::analytics::PerformanceReport;
+use rust_quant_trading::backtest::Backtester;
+use rust_quant_trading::config::TradingConfig;
+use rust_quant_trading::data::{export_klines, BinanceDataFetcher, DataFetcher, FileDataFetcher};
+use rust_quant_trading::exchange::{create_exchange, PaperExchange};
+use rust_quant_trading::store::KlineStore;
+use rust_quant_trading::strategy::MACrossStrategy;
+use rust_quant_trading::types::{Interval, Kline, Symbol};
+use rust_quant_trading::TradingSystem;
+
+/// 单次请求最多返回的K线数量
+const MAX_KLINES_PER_REQUEST: u32 = 1000;
+
+#[derive(Debug, Parser)]
+#[command(name = "rust-quant-trading", version, about = "量化交易系统")]
+pub struct Cli {
+    /// 配置文件路径
+    #[arg(long, global = true, default_value = "config.json")]
+    pub config: PathBuf,
+
+    /// 以 JSON 输出结果，便于脚本处理
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// 启动实盘交易循环
+    Run {
+        /// 只在本地模拟撮合，不向交易所下单
+        #[arg(long)]
+        dry_run: bool,
+
+        /// 模拟盘初始资金
+        #[arg(long, default_value = "10000")]
+        initial_cash: Decimal,
+    },
+    /// 用历史K线回测策略并输出绩效报告
+    Backtest {
+        /// 交易对，默认取配置中的第一个
+        #[arg(long)]
+        symbol: Option<Symbol>,
+
+        #[arg(long, default_value = "1h")]
+        interval: Interval,
+
+        #[arg(long, default_value_t = 1000)]
+        limit: u32,
+
+        /// 从文件（JSON、CSV 或 Parquet）读取K线，不再请求交易所
+        #[arg(long)]
+        input: Option<PathBuf>,
+
+        /// 回测起始时间（RFC 3339），配置了K线库时按区间读取
+        #[arg(long)]
+        start: Option<DateTime<Utc>>,
+
+        /// 回测结束时间（RFC 3339，不含）
+        #[arg(long)]
+        end: Option<DateTime<Utc>>,
+
+        #[arg(long, default_value = "10000")]
+        initial_cash: Decimal,
+
+        #[arg(long)]
+        fee_rate: Option<Decimal>,
+
+        /// 报告写入文件而不是标准输出
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// 下载K线保存到本地文件（按扩展名选择 JSON、CSV 或 Parquet）
+    Fetch {
+        /// 交易对，默认取配置中的第一个
+        #[arg(long)]
+        symbol: Option<Symbol>,
+
+        #[arg(long, default_value = "1h")]
+        interval: Interval,
+
+        #[arg(long, default_value_t = 1000)]
+        limit: u32,
+
+        /// 起始时间（RFC 3339），默认向前推 `limit` 根
+        #[arg(long)]
+        start: Option<DateTime<Utc>>,
+
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// 查询账户余额
+    Balance,
+    /// 查询当前持仓
+    Positions,
+    /// 从本地K线库导出K线
+    Export {
+        /// 交易对，默认取配置中的第一个
+        #[arg(long)]
+        symbol: Option<Symbol>,
+
+        #[arg(long, default_value = "1h")]
+        interval: Interval,
+
+        /// 起始时间（RFC 3339）
+        #[arg(long)]
+        start: Option<DateTime<Utc>>,
+
+        /// 结束时间（RFC 3339，不含）
+        #[arg(long)]
+        end: Option<DateTime<Utc>>,
+
+        /// 输出文件，按扩展名选择 JSON、CSV 或 Parquet
+        #[arg(long)]
+        output: PathBuf,
+    },
+    /// 检查配置文件
+    CheckConfig,
+}
+
+impl Cli {
+    pub async fn execute(self) -> anyhow::Result<()> {
+        let config = load_config(&self.config).await?;
+
+        match self.command {
+            Command::Run {
+                dry_run,
+                initial_cash,
+            } => {
+                config.validate()?;
+                let mut trading_system = TradingSystem::new(config).await?;
+                if dry_run {
+                    info!("Dry run with initial cash {}", initial_cash);
+                    trading_system =
+                        trading_system.with_paper_exchange(PaperExchange::new(initial_cash));
+                }
+                trading_system.run().await?;
+            }
+            Command::Backtest {
+                symbol,
+                interval,
+                limit,
+                input,
+                start,
+                end,
+                initial_cash,
+                fee_rate,
+                output,
+            } => {
+                config.validate()?;
+                let config = Arc::new(config);
+                let symbol = symbol.unwrap_or_else(|| config.symbols[0].clone());
+
+                let klines = match (input, &config.store) {
+                    (Some(path), _) => read_klines(&path).await?,
+                    (None, Some(store_config)) => {
+                        let store = KlineStore::open(&store_config.path)?;
+                        let fetcher = BinanceDataFetcher::new(config.clone());
+                        let step = interval.duration();
+                        let sync_start = start.unwrap_or_else(|| Utc::now() - step * limit as i32);
+                        store
+                            .sync(
+                                &fetcher,
+                                &store_config.source,
+                                &symbol,
+                                interval,
+                                sync_start,
+                            )
+                            .await?;
+
+                        if start.is_some() || end.is_some() {
+                            store.range(&store_config.source, &symbol, interval, start, end)?
+                        } else {
+                            store.latest(&store_config.source, &symbol, interval, limit as usize)?
+                        }
+                    }
+                    (None, None) => {
+                        let fetcher = BinanceDataFetcher::new(config.clone());
+                        fetch_klines(&fetcher, &symbol, interval, limit, start).await?
+                    }
+                };
+
+                let strategy = MACrossStrategy::without_notification(config.clone());
+                let mut backtester = Backtester::new(config, Box::new(strategy), initial_cash)
+                    .with_interval(interval);
+                if let Some(fee_rate) = fee_rate {
+                    backtester = backtester.with_fee_rate(fee_rate);
+                }
+                let result = backtester.run(&symbol, &klines).await?;
+
+                let report = PerformanceReport::new(
+                    format!("{} {} 回测报告", symbol, interval),
+                    result.metrics(),
+                );
+                let content = if self.json {
+                    report.to_json()?
+                } else {
+                    report.to_markdown()
+                };
+                match output {
+                    Some(path) => tokio::fs::write(&path, content)
+                        .await
+                        .with_context(|| format!("failed to write {}", path.display()))?,
+                    None => println!("{}", content),
+                }
+            }
+            Command::Fetch {
+                symbol,
+                interval,
+                limit,
+                start,
+                output,
+            } => {
+                let config = Arc::new(config);
+                let symbol = symbol.unwrap_or_else(|| config.symbols[0].clone());
+                let fetcher = BinanceDataFetcher::new(config);
+                let klines = fetch_klines(&fetcher, &symbol, interval, limit, start).await?;
+
+                write_klines(&output, &klines).await?;
+
+                if self.json {
+                    println!(
+                        "{}",
+                        json!({
+                            "symbol": symbol,
+                            "interval": interval,
+                            "count": klines.len(),
+                            "output": output,
+                        })
+                    );
+                } else {
+                    println!(
+                        "Saved {} {} klines for {} to {}",
+                        klines.len(),
+                        interval,
+                        symbol,
+                        output.display()
+                    );
+                }
+            }
+            Command::Export {
+                symbol,
+                interval,
+                start,
+                end,
+                output,
+            } => {
+                let Some(store_config) = &config.store else {
+                    bail!("no kline store configured in {}", self.config.display());
+                };
+                let symbol = symbol.unwrap_or_else(|| config.symbols[0].clone());
+                let store = KlineStore::open(&store_config.path)?;
+                let klines = store.range(&store_config.source, &symbol, interval, start, end)?;
+                write_klines(&output, &klines).await?;
+
+                if self.json {
+                    println!(
+                        "{}",
+                        json!({
+                            "symbol": symbol,
+                            "interval": interval,
+                            "count": klines.len(),
+                            "output": output,
+                        })
+                    );
+                } else {
+                    println!(
+                        "Exported {} {} klines for {} to {}",
+                        klines.len(),
+                        interval,
+                        symbol,
+                        output.display()
+                    );
+                }
+            }
+            Command::Balance => {
+                let exchange = create_exchange(Arc::new(config))?;
+                let mut balances: Vec<(String, Decimal)> = exchange
+                    .get_balance()
+                    .await?
+                    .into_iter()
+                    .filter(|(_, amount)| !amount.is_zero())
+                    .collect();
+                balances.sort();
+
+                if self.json {
+                    let map: serde_json::Map<String, serde_json::Value> = balances
+                        .into_iter()
+                        .map(|(asset, amount)| (asset, json!(amount)))
+                        .collect();
+                    println!("{}", serde_json::Value::Object(map));
+                } else {
+                    for (asset, amount) in balances {
+                        println!("{:<10} {}", asset, amount);
+                    }
+                }
+            }
+            Command::Positions => {
+                let exchange = create_exchange(Arc::new(config))?;
+                let positions = exchange.get_positions().await?;
+
+                if self.json {
+                    println!("{}", serde_json::to_string(&positions)?);
+                } else {
+                    for p in positions {
+                        println!(
+                            "{:<10} size={} entry={} current={} pnl={}",
+                            p.symbol, p.size, p.entry_price, p.current_price, p.unrealized_pnl
+                        );
+                    }
+                }
+            }
+            Command::CheckConfig => {
+                let result = config.validate();
+                if self.json {
+                    println!(
+                        "{}",
+                        json!({
+                            "config": self.config,
+                            "valid": result.is_ok(),
+                            "error": result.as_ref().err().map(|e| e.to_string()),
+                        })
+                    );
+                } else if result.is_ok() {
+                    println!("{}: OK", self.config.display());
+                }
+                result?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn load_config(path: &Path) -> anyhow::Result<TradingConfig> {
+    let path_str = path
+        .to_str()
+        .with_context(|| format!("invalid config path {}", path.display()))?;
+    TradingConfig::load_trading_config(path_str)
+        .await
+        .with_context(|| format!("failed to load config {}", path.display()))
+}
+
+/// 按扩展名读取K线：`.json` 为 `fetch` 的默认格式，其余交给 `FileDataFetcher`
+async fn read_klines(path: &Path) -> anyhow::Result<Vec<Kline>> {
+    if !is_json(path) {
+        return Ok(FileDataFetcher::new().read(path)?);
+    }
+
+    let data = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+/// 按扩展名写入 JSON、CSV 或 Parquet
+async fn write_klines(path: &Path, klines: &[Kline]) -> anyhow::Result<()> {
+    if is_json(path) {
+        tokio::fs::write(path, serde_json::to_vec_pretty(klines)?)
+            .await
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    } else {
+        export_klines(path, klines)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"))
+}
+
+/// 从 `start` 开始分页下载最多 `limit` 根K线
+async fn fetch_klines(
+    fetcher: &BinanceDataFetcher,
+    symbol: &Symbol,
+    interval: Interval,
+    limit: u32,
+    start: Option<DateTime<Utc>>,
+) -> anyhow::Result<Vec<Kline>> {
+    let step = interval.duration();
+    let mut cursor = match start {
+        Some(start) => start,
+        None => Utc::now() - step * limit as i32,
+    };
+
+    let mut klines: Vec<Kline> = Vec::with_capacity(limit as usize);
+    while klines.len() < limit as usize {
+        let remaining = limit - klines.len() as u32;
+        let batch = fetcher
+            .fetch_klines_since(
+                symbol,
+                interval,
+                cursor,
+                remaining.min(MAX_KLINES_PER_REQUEST),
+            )
+            .await?;
+        let Some(last) = batch.last() else {
+            break;
+        };
+        cursor = last.timestamp + chrono::Duration::milliseconds(1);
+        klines.extend(batch);
+    }
+
+    if klines.is_empty() {
+        bail!("no klines returned for {} {}", symbol, interval);
+    }
+    Ok(klines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_parse_global_options() {
+        let cli = Cli::try_parse_from([
+            "rust-quant-trading",
+            "backtest",
+            "--symbol",
+            "ETHUSDT",
+            "--fee-rate",
+            "0.0005",
+            "--config",
+            "other.json",
+            "--json",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.config, PathBuf::from("other.json"));
+        assert!(cli.json);
+        match cli.command {
+            Command::Backtest {
+                symbol, fee_rate, ..
+            } => {
+                assert_eq!(symbol, Some(Symbol::binance("ETH", "USDT")));
+                assert_eq!(fee_rate, Some(Decimal::new(5, 4)));
+            }
+            other => panic!("unexpected command {:?}", other),
+        }
+
+        let cli = Cli::try_parse_from(["rust-quant-trading", "run", "--dry-run"]).unwrap();
+        assert!(matches!(cli.command, Command::Run { dry_run: true, .. }));
+    }
+}
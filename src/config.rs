@@ -1,18 +1,49 @@
 use anyhow::Result;
-use config::{Config, File};
+use chrono::NaiveTime;
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use std::fmt;
 use tokio::fs;
 
+use crate::data::{
+    CalendarPreset, Resampler, BINANCE_API_URL, BINANCE_FUTURES_TESTNET_WS_URL,
+    BINANCE_FUTURES_WS_URL, BINANCE_TESTNET_API_URL, BINANCE_TESTNET_WS_URL, BINANCE_WS_URL,
+};
+use crate::error::Error;
+use crate::exchange::{BINANCE_FUTURES_API_URL, BINANCE_FUTURES_TESTNET_API_URL};
+use crate::portfolio::CostBasis;
+use crate::types::{Interval, Symbol};
+
 #[derive(Debug, Deserialize)]
 pub struct TradingConfig {
     pub api_key: String,
     pub api_secret: String,
     pub exchange: String,
-    pub symbols: Vec<String>,
+    pub symbols: Vec<Symbol>,
     pub risk_limits: RiskLimits,
     pub strategy_params: StrategyParams,
     pub notification: NotificationConfig,
+    /// 配置后改用 WebSocket 行情推送驱动交易循环
+    #[serde(default)]
+    pub stream: Option<StreamConfig>,
+    /// 配置后历史K线先同步到本地K线库再读取
+    #[serde(default)]
+    pub store: Option<StoreConfig>,
+    /// 持仓成本计算方式：`average_cost`（默认）或 `fifo`
+    #[serde(default)]
+    pub cost_basis: CostBasis,
+    /// 轮询模式下的基础K线周期
+    #[serde(default = "default_interval")]
+    pub interval: Interval,
+    /// 由基础周期聚合出的更长周期，供策略多周期分析
+    #[serde(default)]
+    pub timeframes: Vec<Interval>,
+    /// 按交易时段聚合长周期，如 A 股的 `a_share`；未配置时按 UTC 对齐
+    #[serde(default)]
+    pub calendar: Option<CalendarPreset>,
+    /// Binance 签名请求和时间校准
+    #[serde(default)]
+    pub binance: BinanceConfig,
 }
 
 impl TradingConfig {
@@ -21,12 +52,125 @@ impl TradingConfig {
         let config: TradingConfig = serde_json::from_str(&config_data)?;
         Ok(config)
     }
+
+    /// 检查配置中相互矛盾或明显无效的取值
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.symbols.is_empty() {
+            return Err(Error::config("symbols must not be empty"));
+        }
+
+        let params = &self.strategy_params;
+        if params.short_window == 0 || params.short_window >= params.long_window {
+            return Err(Error::config(format!(
+                "short_window ({}) must be positive and less than long_window ({})",
+                params.short_window, params.long_window
+            )));
+        }
+
+        let limits = &self.risk_limits;
+        if limits.max_position_size <= Decimal::ZERO {
+            return Err(Error::config("max_position_size must be positive"));
+        }
+        if limits.max_drawdown <= Decimal::ZERO {
+            return Err(Error::config("max_drawdown must be positive"));
+        }
+        if matches!(limits.max_daily_loss, Some(loss) if loss <= Decimal::ZERO) {
+            return Err(Error::config("max_daily_loss must be positive"));
+        }
+        if limits.stop_loss_rate <= Decimal::ZERO || limits.stop_loss_rate >= Decimal::ONE {
+            return Err(Error::config(format!(
+                "stop_loss_rate ({}) must be a fraction between 0 and 1, e.g. 0.05 for 5%; \
+                 percentage values such as 5.0 are no longer accepted",
+                limits.stop_loss_rate
+            )));
+        }
+
+        let recv_window = self.binance.recv_window_ms;
+        if recv_window == 0 || recv_window > MAX_RECV_WINDOW_MS {
+            return Err(Error::config(format!(
+                "recv_window_ms ({}) must be between 1 and {}",
+                recv_window, MAX_RECV_WINDOW_MS
+            )));
+        }
+
+        let leverage = self.binance.futures.leverage;
+        if leverage == 0 || leverage > MAX_LEVERAGE {
+            return Err(Error::config(format!(
+                "futures leverage ({}) must be between 1 and {}",
+                leverage, MAX_LEVERAGE
+            )));
+        }
+
+        if let BinanceEndpoint::Custom { rest_url, ws_url } = &self.binance.endpoint {
+            if !rest_url.starts_with("http://") && !rest_url.starts_with("https://") {
+                return Err(Error::config(format!(
+                    "custom rest_url must be an http(s) URL, got {:?}",
+                    rest_url
+                )));
+            }
+            if !ws_url.starts_with("ws://") && !ws_url.starts_with("wss://") {
+                return Err(Error::config(format!(
+                    "custom ws_url must be a ws(s) URL, got {:?}",
+                    ws_url
+                )));
+            }
+        }
+
+        for resampler in self.resamplers() {
+            resampler.check_source(self.interval)?;
+        }
+
+        Ok(())
+    }
+
+    /// 是否交易 U 本位合约；合约的行情同样取自合约市场
+    pub fn is_futures(&self) -> bool {
+        self.exchange == "binance_futures"
+    }
+
+    /// 所交易市场的 REST 地址
+    pub fn market_rest_url(&self) -> &str {
+        match self.is_futures() {
+            true => self.binance.endpoint.futures_rest_url(),
+            false => self.binance.endpoint.rest_url(),
+        }
+    }
+
+    /// 所交易市场的 WebSocket 行情地址
+    pub fn market_ws_url(&self) -> &str {
+        match self.is_futures() {
+            true => self.binance.endpoint.futures_ws_url(),
+            false => self.binance.endpoint.ws_url(),
+        }
+    }
+
+    /// 按 `timeframes` 和 `calendar` 构造各长周期的聚合器
+    pub fn resamplers(&self) -> Vec<Resampler> {
+        self.timeframes
+            .iter()
+            .map(|&interval| {
+                let resampler = Resampler::new(interval);
+                match self.calendar {
+                    Some(preset) => resampler.with_calendar(preset.into()),
+                    None => resampler,
+                }
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct RiskLimits {
     pub max_position_size: Decimal,
+    /// 自累计盈亏高点的最大回撤金额
     pub max_drawdown: Decimal,
+    /// 单个交易日允许的最大亏损金额，未配置时不限制
+    #[serde(default)]
+    pub max_daily_loss: Option<Decimal>,
+    /// 交易日切换时间（UTC），默认零点
+    #[serde(default)]
+    pub daily_reset_time: NaiveTime,
+    /// 止损比例，以小数表示（0.05 即 5%）；旧配置中的百分数 5.0 需改为 0.05
     pub stop_loss_rate: Decimal,
 }
 
@@ -34,6 +178,216 @@ pub struct RiskLimits {
 pub struct StrategyParams {
     pub short_window: usize,
     pub long_window: usize,
+    /// 交叉时快慢均线的最小差值，低于该值的交叉视为噪音
+    #[serde(default)]
+    pub min_cross_difference: f64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StreamConfig {
+    #[serde(default = "default_interval")]
+    pub interval: Interval,
+    /// 每个交易对在内存中保留的K线数量
+    #[serde(default = "default_window_size")]
+    pub window_size: usize,
+}
+
+/// Binance 允许的最大 `recvWindow`
+const MAX_RECV_WINDOW_MS: u64 = 60_000;
+
+/// Binance U 本位合约允许的最大杠杆倍数
+const MAX_LEVERAGE: u32 = 125;
+
+/// Binance 接入点，REST 和 WebSocket 地址成套切换
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinanceEndpoint {
+    /// 生产环境，订单使用真实资金
+    #[default]
+    Mainnet,
+    /// 现货测试网
+    Testnet,
+    /// 自定义地址，如本地 mock 服务
+    Custom { rest_url: String, ws_url: String },
+}
+
+impl BinanceEndpoint {
+    pub fn rest_url(&self) -> &str {
+        match self {
+            BinanceEndpoint::Mainnet => BINANCE_API_URL,
+            BinanceEndpoint::Testnet => BINANCE_TESTNET_API_URL,
+            BinanceEndpoint::Custom { rest_url, .. } => rest_url.trim_end_matches('/'),
+        }
+    }
+
+    /// U 本位合约的 REST 地址，自定义接入点与现货共用同一地址
+    pub fn futures_rest_url(&self) -> &str {
+        match self {
+            BinanceEndpoint::Mainnet => BINANCE_FUTURES_API_URL,
+            BinanceEndpoint::Testnet => BINANCE_FUTURES_TESTNET_API_URL,
+            BinanceEndpoint::Custom { rest_url, .. } => rest_url.trim_end_matches('/'),
+        }
+    }
+
+    pub fn ws_url(&self) -> &str {
+        match self {
+            BinanceEndpoint::Mainnet => BINANCE_WS_URL,
+            BinanceEndpoint::Testnet => BINANCE_TESTNET_WS_URL,
+            BinanceEndpoint::Custom { ws_url, .. } => ws_url.trim_end_matches('/'),
+        }
+    }
+
+    /// U 本位合约的 WebSocket 地址，自定义接入点与现货共用同一地址
+    pub fn futures_ws_url(&self) -> &str {
+        match self {
+            BinanceEndpoint::Mainnet => BINANCE_FUTURES_WS_URL,
+            BinanceEndpoint::Testnet => BINANCE_FUTURES_TESTNET_WS_URL,
+            BinanceEndpoint::Custom { ws_url, .. } => ws_url.trim_end_matches('/'),
+        }
+    }
+
+    pub fn is_mainnet(&self) -> bool {
+        *self == BinanceEndpoint::Mainnet
+    }
+}
+
+impl fmt::Display for BinanceEndpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BinanceEndpoint::Mainnet => write!(f, "mainnet"),
+            BinanceEndpoint::Testnet => write!(f, "testnet"),
+            BinanceEndpoint::Custom { .. } => write!(f, "custom"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BinanceConfig {
+    /// 接入点：`mainnet`（默认）、`testnet` 或 `{"custom": {"rest_url", "ws_url"}}`
+    #[serde(default)]
+    pub endpoint: BinanceEndpoint,
+    /// 签名请求的有效时间窗口（毫秒）
+    #[serde(default = "default_recv_window_ms")]
+    pub recv_window_ms: u64,
+    /// 向服务器校准时间的间隔（秒）
+    #[serde(default = "default_time_sync_interval_secs")]
+    pub time_sync_interval_secs: u64,
+    /// 本机时钟偏差超过该值（毫秒）时发送告警
+    #[serde(default = "default_max_clock_drift_ms")]
+    pub max_clock_drift_ms: i64,
+    /// U 本位合约的账户设置，`exchange` 为 `binance_futures` 时生效
+    #[serde(default)]
+    pub futures: BinanceFuturesConfig,
+}
+
+impl Default for BinanceConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: BinanceEndpoint::default(),
+            recv_window_ms: default_recv_window_ms(),
+            time_sync_interval_secs: default_time_sync_interval_secs(),
+            max_clock_drift_ms: default_max_clock_drift_ms(),
+            futures: BinanceFuturesConfig::default(),
+        }
+    }
+}
+
+fn default_recv_window_ms() -> u64 {
+    5_000
+}
+
+fn default_time_sync_interval_secs() -> u64 {
+    300
+}
+
+fn default_max_clock_drift_ms() -> i64 {
+    1_000
+}
+
+/// 合约保证金模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarginType {
+    /// 全仓
+    #[default]
+    Cross,
+    /// 逐仓
+    Isolated,
+}
+
+impl fmt::Display for MarginType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarginType::Cross => write!(f, "CROSSED"),
+            MarginType::Isolated => write!(f, "ISOLATED"),
+        }
+    }
+}
+
+/// 合约持仓模式，对账户下全部交易对生效
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionMode {
+    /// 单向持仓：每个交易对一个净持仓
+    #[default]
+    OneWay,
+    /// 双向持仓：多头和空头分别持仓，下单时指定持仓方向
+    Hedge,
+}
+
+impl fmt::Display for PositionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PositionMode::OneWay => write!(f, "one_way"),
+            PositionMode::Hedge => write!(f, "hedge"),
+        }
+    }
+}
+
+/// 合约账户设置，首次交易某个交易对前写入交易所
+#[derive(Debug, Clone, Deserialize)]
+pub struct BinanceFuturesConfig {
+    #[serde(default = "default_leverage")]
+    pub leverage: u32,
+    #[serde(default)]
+    pub margin_type: MarginType,
+    #[serde(default)]
+    pub position_mode: PositionMode,
+}
+
+impl Default for BinanceFuturesConfig {
+    fn default() -> Self {
+        Self {
+            leverage: default_leverage(),
+            margin_type: MarginType::default(),
+            position_mode: PositionMode::default(),
+        }
+    }
+}
+
+fn default_leverage() -> u32 {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StoreConfig {
+    /// SQLite 数据库文件路径
+    pub path: String,
+    /// 写入K线库时使用的数据源名称
+    #[serde(default = "default_store_source")]
+    pub source: String,
+}
+
+fn default_store_source() -> String {
+    "binance".to_string()
+}
+
+fn default_interval() -> Interval {
+    Interval::Minute1
+}
+
+fn default_window_size() -> usize {
+    1000
 }
 
 #[derive(Deserialize, Debug)]
@@ -46,3 +400,93 @@ pub struct DingTalkConfig {
     pub webhook: String,
     pub secret: String,
 }
+
+#[cfg(test)]
+pub(crate) fn test_config() -> TradingConfig {
+    serde_json::from_str(include_str!("../config.example.json")).expect("valid example config")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_validate() {
+        let mut config = test_config();
+        assert!(config.validate().is_ok());
+
+        // 止损比例以小数表示，5.0 会得到负的止损价
+        config.risk_limits.stop_loss_rate = dec!(5);
+        assert!(
+            matches!(config.validate(), Err(Error::ConfigError(msg)) if msg.contains("e.g. 0.05"))
+        );
+
+        config.risk_limits.stop_loss_rate = dec!(0.05);
+        config.strategy_params.short_window = config.strategy_params.long_window;
+        assert!(matches!(config.validate(), Err(Error::ConfigError(_))));
+
+        // 长周期必须是基础周期的整数倍
+        config.strategy_params.short_window = 14;
+        config.timeframes = vec![Interval::Minute15, Interval::Hour1];
+        assert!(config.validate().is_ok());
+        config.interval = Interval::Hour1;
+        assert!(matches!(config.validate(), Err(Error::ConfigError(_))));
+
+        config.interval = Interval::Minute1;
+        assert_eq!(config.binance.recv_window_ms, 5_000);
+        config.binance.recv_window_ms = 120_000;
+        assert!(matches!(config.validate(), Err(Error::ConfigError(_))));
+        config.binance.recv_window_ms = 5_000;
+
+        config.binance.endpoint = BinanceEndpoint::Custom {
+            rest_url: "http://127.0.0.1:8080".to_string(),
+            ws_url: "127.0.0.1:8081".to_string(),
+        };
+        assert!(matches!(config.validate(), Err(Error::ConfigError(_))));
+        config.binance.endpoint = BinanceEndpoint::Testnet;
+
+        assert_eq!(config.binance.futures.leverage, 1);
+        assert_eq!(config.binance.futures.position_mode, PositionMode::OneWay);
+        config.binance.futures.leverage = 126;
+        assert!(matches!(config.validate(), Err(Error::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_endpoint_profiles() {
+        let endpoint = |json: &str| serde_json::from_str::<BinanceEndpoint>(json).unwrap();
+
+        assert!(BinanceEndpoint::default().is_mainnet());
+        assert_eq!(
+            BinanceEndpoint::Mainnet.rest_url(),
+            "https://api.binance.com"
+        );
+
+        let testnet = endpoint(r#""testnet""#);
+        assert!(!testnet.is_mainnet());
+        assert_eq!(testnet.rest_url(), "https://testnet.binance.vision");
+        assert_eq!(testnet.ws_url(), "wss://stream.testnet.binance.vision");
+
+        let custom = endpoint(
+            r#"{"custom": {"rest_url": "http://127.0.0.1:8080", "ws_url": "ws://127.0.0.1:8081"}}"#,
+        );
+        assert_eq!(custom.rest_url(), "http://127.0.0.1:8080");
+        assert_eq!(custom.ws_url(), "ws://127.0.0.1:8081");
+        assert_eq!(custom.to_string(), "custom");
+        assert_eq!(custom.futures_rest_url(), "http://127.0.0.1:8080");
+        assert_eq!(
+            testnet.futures_rest_url(),
+            "https://testnet.binancefuture.com"
+        );
+        assert_eq!(custom.futures_ws_url(), "ws://127.0.0.1:8081");
+        assert_eq!(testnet.futures_ws_url(), "wss://stream.binancefuture.com");
+
+        // 交易合约时行情同样取自合约市场
+        let mut config = test_config();
+        config.binance.endpoint = BinanceEndpoint::Mainnet;
+        assert_eq!(config.market_ws_url(), "wss://stream.binance.com:9443");
+        config.exchange = "binance_futures".to_string();
+        assert_eq!(config.market_rest_url(), "https://fapi.binance.com");
+        assert_eq!(config.market_ws_url(), "wss://fstream.binance.com");
+    }
+}
@@ -2,29 +2,175 @@
 use super::DataFetcher;
 
 use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::Client;
 use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::Value;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use crate::config::TradingConfig;
 use crate::error::Error;
-use crate::types::Kline;
+use crate::exchange::read_response;
+use crate::rate_limit::{binance_futures_limiter, binance_spot_limiter, RateLimiter};
+use crate::types::{Interval, Kline, Symbol};
 
+/// Binance 现货 REST 默认地址
+pub const BINANCE_API_URL: &str = "https://api.binance.com";
+
+/// Binance 现货测试网 REST 地址
+pub const BINANCE_TESTNET_API_URL: &str = "https://testnet.binance.vision";
+
+/// 现货和 U 本位合约的行情接口路径前缀
+const SPOT_API: &str = "/api/v3";
+const FUTURES_API: &str = "/fapi/v1";
+
+/// Binance 行情数据；配置交易 U 本位合约时请求合约的行情接口
 #[derive(Debug)]
 pub struct BinanceDataFetcher {
     config: Arc<TradingConfig>,
+    client: Client,
+    base_url: String,
+    api: &'static str,
+    rate_limiter: Arc<RateLimiter>,
 }
 
 impl BinanceDataFetcher {
     pub fn new(config: Arc<TradingConfig>) -> Self {
-        BinanceDataFetcher { config }
+        let (api, rate_limiter) = match config.is_futures() {
+            true => (FUTURES_API, binance_futures_limiter()),
+            false => (SPOT_API, binance_spot_limiter()),
+        };
+        BinanceDataFetcher {
+            base_url: config.market_rest_url().to_string(),
+            config,
+            client: Client::new(),
+            api,
+            rate_limiter,
+        }
+    }
+
+    /// 替换 REST 基础地址（测试网或本地 mock 服务）
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// 替换限速器，默认与进程内其他 Binance 适配器共用
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
     }
 
     pub async fn fetch_data(&self) -> Result<Vec<Kline>, Error> {
-        // 在这里实现从 Binance 获取数据的逻辑
-        Ok(vec![]) // 返回模拟数据或实际 API 数据
+        // 默认取第一个配置交易对的 1m K线
+        match self.config.symbols.first() {
+            Some(symbol) => self.fetch_klines(symbol, Interval::Minute1, 500).await,
+            None => Ok(vec![]),
+        }
+    }
+
+    async fn request_klines(
+        &self,
+        symbol: &Symbol,
+        interval: Interval,
+        limit: u32,
+        start: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Kline>, Error> {
+        let mut query = vec![
+            ("symbol", symbol.to_string()),
+            ("interval", interval.to_string()),
+            ("limit", limit.to_string()),
+        ];
+        if let Some(start) = start {
+            query.push(("startTime", start.timestamp_millis().to_string()));
+        }
+
+        let payload = self
+            .get_json("klines", &query, klines_weight(limit))
+            .await?;
+        parse_klines(&payload)
+    }
+
+    /// 与交易适配器共用响应处理，错误按类别返回，5xx、429/418 可由重试层重试
+    ///
+    /// `endpoint` 为接口名，如 `klines`，按现货或合约补上路径前缀。
+    async fn get_json(
+        &self,
+        endpoint: &str,
+        query: &[(&str, String)],
+        weight: u32,
+    ) -> Result<Value, Error> {
+        self.rate_limiter.acquire(weight, 0).await;
+        let path = format!("{}/{}", self.api, endpoint);
+        let url = format!("{}{}", self.base_url, path);
+        let sent = self.client.get(&url).query(query).send().await;
+        read_response(&path, sent, &self.rate_limiter).await
     }
 }
 
+/// `klines` 的请求权重随 `limit` 增加，现货和合约相同
+fn klines_weight(limit: u32) -> u32 {
+    match limit {
+        0..100 => 1,
+        100..500 => 2,
+        500..=1000 => 5,
+        _ => 10,
+    }
+}
+
+/// 将 `/api/v3/klines` 返回的数组格式解析为 `Kline`
+///
+/// 每一行形如 `[openTime, "open", "high", "low", "close", "volume", closeTime, ...]`，
+/// 任何字段缺失或格式不符都会返回 `Error::ParseError`，不会以零值填充。
+pub fn parse_klines(payload: &Value) -> Result<Vec<Kline>, Error> {
+    let rows = payload
+        .as_array()
+        .ok_or_else(|| Error::ParseError("kline payload is not an array".to_string()))?;
+
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| {
+            parse_kline_row(row).map_err(|e| Error::ParseError(format!("kline[{}]: {}", i, e)))
+        })
+        .collect()
+}
+
+fn parse_kline_row(row: &Value) -> Result<Kline, String> {
+    let fields = row.as_array().ok_or("row is not an array")?;
+    if fields.len() < 6 {
+        return Err(format!("expected at least 6 fields, got {}", fields.len()));
+    }
+
+    let open_time = fields[0].as_i64().ok_or("open time is not an integer")?;
+    let timestamp = Utc
+        .timestamp_millis_opt(open_time)
+        .single()
+        .ok_or_else(|| format!("invalid open time {}", open_time))?;
+
+    let decimal = |idx: usize, name: &str| -> Result<Decimal, String> {
+        let raw = fields[idx]
+            .as_str()
+            .ok_or_else(|| format!("{} is not a string", name))?;
+        Decimal::from_str(raw).map_err(|e| format!("{} {:?}: {}", name, raw, e))
+    };
+
+    Ok(Kline {
+        timestamp,
+        open: decimal(1, "open")?,
+        high: decimal(2, "high")?,
+        low: decimal(3, "low")?,
+        close: decimal(4, "close")?,
+        volume: decimal(5, "volume")?,
+    })
+}
+
+#[derive(Deserialize)]
+struct PriceResponse {
+    price: String,
+}
+
 #[async_trait]
 impl DataFetcher for BinanceDataFetcher {
     async fn fetch(&self) -> Result<Vec<Kline>, Error> {
@@ -33,70 +179,266 @@ impl DataFetcher for BinanceDataFetcher {
 
     async fn fetch_klines(
         &self,
-        symbol: &str,
-        interval: &str,
+        symbol: &Symbol,
+        interval: Interval,
+        limit: u32,
+    ) -> Result<Vec<Kline>, Error> {
+        self.request_klines(symbol, interval, limit, None).await
+    }
+
+    async fn fetch_klines_since(
+        &self,
+        symbol: &Symbol,
+        interval: Interval,
+        start: DateTime<Utc>,
         limit: u32,
     ) -> Result<Vec<Kline>, Error> {
-        // let url = format!("{}/api/v3/klines", self.base_url);
-        // let response = self.client
-        //     .get(&url)
-        //     .query(&[
-        //         ("symbol", symbol),
-        //         ("interval", interval),
-        //         ("limit", &limit.to_string()),
-        //     ])
-        //     .send()
-        //     .await
-        //     .map_err(|e| Error::NetworkError(e.to_string()))?;
-
-        // let klines: Vec<BinanceKlineResponse> = response
-        //     .json()
-        //     .await
-        //     .map_err(|e| Error::ParseError(e.to_string()))?;
-
-        // // 将 Binance API 返回的数据转换为 Vec<Kline>
-        // let result = klines
-        //     .into_iter()
-        //     .map(|k| Kline {
-        //         timestamp: chrono::Utc.timestamp_millis(k.0),
-        //         open: k.1.parse().unwrap_or(Decimal::ZERO),
-        //         high: k.2.parse().unwrap_or(Decimal::ZERO),
-        //         low: k.3.parse().unwrap_or(Decimal::ZERO),
-        //         close: k.4.parse().unwrap_or(Decimal::ZERO),
-        //         volume: k.5.parse().unwrap_or(Decimal::ZERO),
-        //     })
-        //     .collect();
-
-        // Ok(result)
-        Ok(vec![])
-    }
-
-    async fn fetch_ticker(&self, symbol: &str) -> Result<Decimal, Error> {
-        // let url = format!("{}/api/v3/ticker/price", self.base_url);
-        // let response = self.client
-        //     .get(&url)
-        //     .query(&[("symbol", symbol)])
-        //     .send()
-        //     .await
-        //     .map_err(|e| Error::NetworkError(e.to_string()))?;
-
-        // // 从响应中提取价格
-        // #[derive(Deserialize)]
-        // struct PriceResponse {
-        //     price: String,
-        // }
-
-        // let price_data: PriceResponse = response
-        //     .json()
-        //     .await
-        //     .map_err(|e| Error::ParseError(e.to_string()))?;
-
-        // let price = price_data
-        //     .price
-        //     .parse::<Decimal>()
-        //     .map_err(|e| Error::ParseError(e.to_string()))?;
-
-        // Ok(price)
-        Ok(Decimal::new(0, 2))
+        self.request_klines(symbol, interval, limit, Some(start))
+            .await
+    }
+
+    async fn fetch_ticker(&self, symbol: &Symbol) -> Result<Decimal, Error> {
+        let payload = self
+            .get_json("ticker/price", &[("symbol", symbol.to_string())], 2)
+            .await?;
+
+        // 从响应中提取价格
+        let price_data: PriceResponse = serde_json::from_value(payload)?;
+
+        price_data
+            .price
+            .parse::<Decimal>()
+            .map_err(|e| Error::ParseError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+    use crate::data::closed_klines;
+    use crate::error::ExchangeErrorKind;
+    use crate::retry::{RetryDataFetcher, RetryPolicy};
+    use crate::types::test_symbol;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+    use std::time::Duration;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn fetcher(server: &MockServer) -> BinanceDataFetcher {
+        BinanceDataFetcher::new(Arc::new(test_config()))
+            .with_base_url(server.uri())
+            .with_rate_limiter(Arc::new(RateLimiter::binance_spot()))
+    }
+
+    #[test]
+    fn test_drop_forming_kline() -> Result<(), Error> {
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 0, 2, 30).unwrap();
+        let row = |open_time: DateTime<Utc>| {
+            json!([
+                open_time.timestamp_millis(),
+                "100",
+                "101",
+                "99",
+                "100",
+                "1",
+                open_time.timestamp_millis() + 59_999
+            ])
+        };
+        // 00:01 这根刚好收盘，00:02 这根仍在形成中
+        let payload = json!([
+            row(now - chrono::Duration::seconds(90)),
+            row(now - chrono::Duration::seconds(30)),
+        ]);
+
+        let klines = closed_klines(parse_klines(&payload)?, Interval::Minute1, now);
+        assert_eq!(klines.len(), 1);
+        assert_eq!(
+            klines[0].timestamp,
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 1, 0).unwrap()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_klines() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/klines"))
+            .and(query_param("symbol", "BTCUSDT"))
+            .and(query_param("interval", "1m"))
+            .and(query_param("limit", "2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                [
+                    1499040000000i64,
+                    "0.01634790",
+                    "0.80000000",
+                    "0.01575800",
+                    "0.01577100",
+                    "148976.11427815",
+                    1499644799999i64,
+                    "2434.19055334",
+                    308,
+                    "1756.87402397",
+                    "28.46694368",
+                    "0"
+                ],
+                [
+                    1499040060000i64,
+                    "0.01577100",
+                    "0.01600000",
+                    "0.01570000",
+                    "0.01590000",
+                    "10.5",
+                    1499040119999i64,
+                    "0.1",
+                    3,
+                    "5",
+                    "0.05",
+                    "0"
+                ]
+            ])))
+            .mount(&server)
+            .await;
+
+        let klines = fetcher(&server)
+            .fetch_klines(&Symbol::binance("BTC", "USDT"), Interval::Minute1, 2)
+            .await?;
+
+        assert_eq!(klines.len(), 2);
+        assert_eq!(klines[0].timestamp.timestamp_millis(), 1499040000000);
+        assert_eq!(klines[0].open, dec!(0.01634790));
+        assert_eq!(klines[0].high, dec!(0.80000000));
+        assert_eq!(klines[0].low, dec!(0.01575800));
+        assert_eq!(klines[0].close, dec!(0.01577100));
+        assert_eq!(klines[0].volume, dec!(148976.11427815));
+        assert_eq!(klines[1].close, dec!(0.01590000));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_fetch_ticker() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/ticker/price"))
+            .and(query_param("symbol", "ETHUSDT"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"symbol": "ETHUSDT", "price": "3521.45000000"})),
+            )
+            .mount(&server)
+            .await;
+
+        let price = fetcher(&server)
+            .fetch_ticker(&Symbol::binance("ETH", "USDT"))
+            .await?;
+        assert_eq!(price, dec!(3521.45));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_futures_market_data() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/fapi/v1/ticker/price"))
+            .and(query_param("symbol", "BTCUSDT"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"symbol": "BTCUSDT", "price": "64012.30"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // 交易合约时取合约的行情，而非现货价格
+        let mut config = test_config();
+        config.exchange = "binance_futures".to_string();
+        let price = BinanceDataFetcher::new(Arc::new(config))
+            .with_base_url(server.uri())
+            .with_rate_limiter(Arc::new(RateLimiter::binance_futures()))
+            .fetch_ticker(&test_symbol("BTCUSDT"))
+            .await?;
+        assert_eq!(price, dec!(64012.30));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_api_error_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/klines"))
+            .respond_with(
+                ResponseTemplate::new(400)
+                    .set_body_json(json!({"code": -1121, "msg": "Invalid symbol."})),
+            )
+            .mount(&server)
+            .await;
+
+        let result = fetcher(&server)
+            .fetch_klines(&Symbol::binance("NOPE", "USDT"), Interval::Minute1, 10)
+            .await;
+        let error = result.unwrap_err();
+        let error = error.exchange_error().expect("classified exchange error");
+        assert_eq!(error.kind, ExchangeErrorKind::InvalidParameter);
+        assert_eq!(error.code, Some(-1121));
+    }
+
+    #[tokio::test]
+    async fn test_server_error_is_retried() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/klines"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/klines"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([[
+                1499040000000i64,
+                "0.01634790",
+                "0.80000000",
+                "0.01575800",
+                "0.01577100",
+                "148976.11427815",
+                1499644799999i64
+            ]])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let fetcher = RetryDataFetcher::new(fetcher(&server)).with_policy(RetryPolicy::new(
+            3,
+            Duration::from_millis(1),
+            Duration::from_millis(10),
+        ));
+        let klines = fetcher
+            .fetch_klines(&test_symbol("BTCUSDT"), Interval::Minute1, 1)
+            .await?;
+        assert_eq!(klines.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_klines_rejects_malformed_rows() {
+        // 字段不足
+        assert!(parse_klines(&json!([[1499040000000i64, "1", "2", "0.5"]])).is_err());
+        // 价格不是字符串
+        assert!(parse_klines(&json!([[1499040000000i64, 1.0, "2", "0.5", "1", "10"]])).is_err());
+        // 价格无法解析
+        assert!(parse_klines(&json!([[1499040000000i64, "abc", "2", "0.5", "1", "10"]])).is_err());
+        // 开盘时间不是整数
+        assert!(parse_klines(&json!([["1499040000000", "1", "2", "0.5", "1", "10"]])).is_err());
+        // 顶层不是数组
+        assert!(parse_klines(&json!({"code": -1121})).is_err());
+
+        assert!(parse_klines(&json!([])).unwrap().is_empty());
     }
 }
@@ -0,0 +1,714 @@
+// src/data/binance_stream.rs
+use super::{closed_klines, BinanceDataFetcher, DataFetcher};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration as ChronoDuration, TimeZone, Utc};
+use futures::StreamExt;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{info, warn};
+
+use crate::error::Error;
+use crate::types::{Interval, Kline, Symbol};
+
+/// Binance 现货 WebSocket 默认地址
+pub const BINANCE_WS_URL: &str = "wss://stream.binance.com:9443";
+
+/// Binance 现货测试网 WebSocket 地址
+pub const BINANCE_TESTNET_WS_URL: &str = "wss://stream.testnet.binance.vision";
+
+/// Binance U 本位合约 WebSocket 地址
+pub const BINANCE_FUTURES_WS_URL: &str = "wss://fstream.binance.com";
+
+/// Binance U 本位合约测试网 WebSocket 地址
+pub const BINANCE_FUTURES_TESTNET_WS_URL: &str = "wss://stream.binancefuture.com";
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// 单次 REST 请求允许的最大K线数量
+const MAX_REST_LIMIT: usize = 1000;
+
+/// 最优挂单价（`bookTicker` 推送）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookTicker {
+    pub bid_price: Decimal,
+    pub bid_qty: Decimal,
+    pub ask_price: Decimal,
+    pub ask_qty: Decimal,
+}
+
+impl BookTicker {
+    pub fn mid_price(&self) -> Decimal {
+        (self.bid_price + self.ask_price) / Decimal::TWO
+    }
+}
+
+/// 推送给交易循环的已收盘K线
+#[derive(Debug, Clone)]
+pub struct StreamKline {
+    pub symbol: Symbol,
+    pub kline: Kline,
+    /// 预热或断线补齐得到的历史K线，只用于更新策略状态，不应触发下单
+    pub replay: bool,
+}
+
+#[derive(Debug, Default)]
+struct SymbolState {
+    klines: VecDeque<Kline>,
+    book: Option<BookTicker>,
+}
+
+/// 基于 Binance 组合流（`kline_<interval>` + `bookTicker`）的行情源
+///
+/// 每个交易对在内存中保留一个滚动窗口，断线后按指数退避重连，
+/// 并通过 REST 补齐断线期间缺失的K线。
+pub struct BinanceMarketStream {
+    rest: BinanceDataFetcher,
+    ws_url: String,
+    symbols: Vec<Symbol>,
+    interval: Interval,
+    window_size: usize,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    state: RwLock<HashMap<Symbol, SymbolState>>,
+}
+
+impl BinanceMarketStream {
+    pub fn new(
+        rest: BinanceDataFetcher,
+        symbols: Vec<Symbol>,
+        interval: Interval,
+        window_size: usize,
+    ) -> Self {
+        Self {
+            rest,
+            ws_url: BINANCE_WS_URL.to_string(),
+            symbols,
+            interval,
+            window_size: window_size.max(1),
+            initial_backoff: INITIAL_BACKOFF,
+            max_backoff: MAX_BACKOFF,
+            state: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// 替换 WebSocket 基础地址（测试网或本地模拟服务）
+    pub fn with_ws_url(mut self, ws_url: impl Into<String>) -> Self {
+        self.ws_url = ws_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// 设置重连退避的初始值和上限
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// 组合流订阅地址
+    pub fn stream_url(&self) -> String {
+        let streams = self
+            .symbols
+            .iter()
+            .flat_map(|symbol| {
+                let symbol = symbol.to_string().to_lowercase();
+                [
+                    format!("{}@kline_{}", symbol, self.interval),
+                    format!("{}@bookTicker", symbol),
+                ]
+            })
+            .collect::<Vec<String>>()
+            .join("/");
+        format!("{}/stream?streams={}", self.ws_url, streams)
+    }
+
+    /// 当前窗口内最近 `limit` 根已收盘K线
+    pub fn klines(&self, symbol: &Symbol, limit: usize) -> Vec<Kline> {
+        let state = self.state.read().expect("market state lock poisoned");
+        state
+            .get(symbol)
+            .map(|s| {
+                let skip = s.klines.len().saturating_sub(limit);
+                s.klines.iter().skip(skip).cloned().collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn book_ticker(&self, symbol: &Symbol) -> Option<BookTicker> {
+        let state = self.state.read().expect("market state lock poisoned");
+        state.get(symbol).and_then(|s| s.book)
+    }
+
+    /// 持续订阅行情，直到 `tx` 的接收端被关闭
+    pub async fn run(&self, tx: mpsc::Sender<StreamKline>) -> Result<(), Error> {
+        let mut backoff = self.initial_backoff;
+
+        loop {
+            match self.session(&tx).await {
+                // 收到过K线才说明连接可用，连上即断的服务端不会重置退避
+                Ok(true) => {
+                    info!("Market stream disconnected");
+                    backoff = self.initial_backoff;
+                }
+                Ok(false) => warn!("Market stream closed before any kline was received"),
+                Err(e) => warn!("Market stream error: {}", e),
+            }
+
+            if tx.is_closed() {
+                return Ok(());
+            }
+
+            info!("Reconnecting market stream in {:?}", backoff);
+            tokio::time::sleep(backoff).await;
+            backoff = (backoff * 2).min(self.max_backoff);
+        }
+    }
+
+    /// 一次连接的生命周期，返回期间是否收到过K线推送
+    async fn session(&self, tx: &mpsc::Sender<StreamKline>) -> Result<bool, Error> {
+        let (mut ws, _) = connect_async(self.stream_url())
+            .await
+            .map_err(|e| Error::WebSocketError(e.to_string()))?;
+        info!("Market stream connected: {}", self.stream_url());
+        let mut received_kline = false;
+
+        // 连接建立后推送会先缓存在套接字中，补齐缺口后再处理，不会漏数据
+        for symbol in &self.symbols {
+            self.backfill(symbol, tx).await?;
+        }
+
+        while let Some(message) = ws.next().await {
+            match message.map_err(|e| Error::WebSocketError(e.to_string()))? {
+                Message::Text(text) => received_kline |= self.handle_message(&text, tx).await?,
+                Message::Close(_) => break,
+                _ => {}
+            }
+
+            if tx.is_closed() {
+                break;
+            }
+        }
+
+        Ok(received_kline)
+    }
+
+    /// 通过 REST 补齐窗口：首次连接时预热，重连时分页取最后一根之后的全部K线
+    async fn backfill(&self, symbol: &Symbol, tx: &mpsc::Sender<StreamKline>) -> Result<(), Error> {
+        let Some(last) = self.last_timestamp(symbol) else {
+            let warmup = self
+                .rest
+                .fetch_klines(
+                    symbol,
+                    self.interval,
+                    self.window_size.min(MAX_REST_LIMIT) as u32,
+                )
+                .await?;
+            self.replay(symbol, warmup, tx).await;
+            return Ok(());
+        };
+
+        let mut start = last + ChronoDuration::milliseconds(1);
+        loop {
+            let page = self
+                .rest
+                .fetch_klines_since(symbol, self.interval, start, MAX_REST_LIMIT as u32)
+                .await?;
+            // 不足一页说明已取到最新
+            let complete = page.len() < MAX_REST_LIMIT;
+            let Some(next) = page.last().map(|k| k.timestamp) else {
+                break;
+            };
+            if !self.replay(symbol, page, tx).await || complete {
+                break;
+            }
+            start = next + ChronoDuration::milliseconds(1);
+        }
+
+        Ok(())
+    }
+
+    /// 将补齐的K线写入窗口并以回放事件推送，接收端关闭时返回 `false`
+    async fn replay(
+        &self,
+        symbol: &Symbol,
+        klines: Vec<Kline>,
+        tx: &mpsc::Sender<StreamKline>,
+    ) -> bool {
+        for kline in closed_klines(klines, self.interval, Utc::now()) {
+            if self.push_kline(symbol, kline.clone()) {
+                let event = StreamKline {
+                    symbol: symbol.clone(),
+                    kline,
+                    replay: true,
+                };
+                if tx.send(event).await.is_err() {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// 处理一条组合流消息，返回是否为K线推送
+    async fn handle_message(
+        &self,
+        text: &str,
+        tx: &mpsc::Sender<StreamKline>,
+    ) -> Result<bool, Error> {
+        let event: CombinedEvent = serde_json::from_str(text)?;
+
+        if event.stream.ends_with("@bookTicker") {
+            let book: WsBookTicker = serde_json::from_value(event.data)?;
+            let Some(symbol) = self.resolve(&book.symbol) else {
+                return Ok(false);
+            };
+            let mut state = self.state.write().expect("market state lock poisoned");
+            state.entry(symbol.clone()).or_default().book = Some(BookTicker {
+                bid_price: book.bid_price,
+                bid_qty: book.bid_qty,
+                ask_price: book.ask_price,
+                ask_qty: book.ask_qty,
+            });
+        } else if event.stream.contains("@kline_") {
+            let event: WsKlineEvent = serde_json::from_value(event.data)?;
+            if !event.kline.is_closed {
+                return Ok(true);
+            }
+
+            let Some(symbol) = self.resolve(&event.symbol) else {
+                return Ok(true);
+            };
+            let kline = event.kline.into_kline()?;
+            if self.push_kline(symbol, kline.clone()) {
+                // 接收端关闭时由 session 循环退出
+                let _ = tx
+                    .send(StreamKline {
+                        symbol: symbol.clone(),
+                        kline,
+                        replay: false,
+                    })
+                    .await;
+            }
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// 追加一根已收盘K线，按时间戳去重；返回是否为新K线
+    fn push_kline(&self, symbol: &Symbol, kline: Kline) -> bool {
+        let mut state = self.state.write().expect("market state lock poisoned");
+        let klines = &mut state.entry(symbol.clone()).or_default().klines;
+
+        match klines.back_mut() {
+            Some(last) if kline.timestamp < last.timestamp => return false,
+            Some(last) if kline.timestamp == last.timestamp => {
+                *last = kline;
+                return false;
+            }
+            _ => klines.push_back(kline),
+        }
+
+        while klines.len() > self.window_size {
+            klines.pop_front();
+        }
+        true
+    }
+
+    /// 推送中的交易对名称（如 `BTCUSDT`）对应的订阅交易对
+    fn resolve(&self, name: &str) -> Option<&Symbol> {
+        self.symbols
+            .iter()
+            .find(|symbol| symbol.to_string() == name)
+    }
+
+    fn last_timestamp(&self, symbol: &Symbol) -> Option<chrono::DateTime<Utc>> {
+        let state = self.state.read().expect("market state lock poisoned");
+        state
+            .get(symbol)
+            .and_then(|s| s.klines.back())
+            .map(|k| k.timestamp)
+    }
+}
+
+#[async_trait]
+impl DataFetcher for BinanceMarketStream {
+    async fn fetch(&self) -> Result<Vec<Kline>, Error> {
+        self.rest.fetch().await
+    }
+
+    async fn fetch_klines(
+        &self,
+        symbol: &Symbol,
+        interval: Interval,
+        limit: u32,
+    ) -> Result<Vec<Kline>, Error> {
+        if interval == self.interval {
+            let klines = self.klines(symbol, limit as usize);
+            if !klines.is_empty() {
+                return Ok(klines);
+            }
+        }
+        self.rest.fetch_klines(symbol, interval, limit).await
+    }
+
+    async fn fetch_klines_since(
+        &self,
+        symbol: &Symbol,
+        interval: Interval,
+        start: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<Kline>, Error> {
+        self.rest
+            .fetch_klines_since(symbol, interval, start, limit)
+            .await
+    }
+
+    async fn fetch_ticker(&self, symbol: &Symbol) -> Result<Decimal, Error> {
+        match self.book_ticker(symbol) {
+            Some(book) => Ok(book.mid_price()),
+            None => self.rest.fetch_ticker(symbol).await,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CombinedEvent {
+    stream: String,
+    data: Value,
+}
+
+#[derive(Deserialize)]
+struct WsKlineEvent {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "k")]
+    kline: WsKline,
+}
+
+#[derive(Deserialize)]
+struct WsKline {
+    #[serde(rename = "t")]
+    open_time: i64,
+    #[serde(rename = "o")]
+    open: Decimal,
+    #[serde(rename = "h")]
+    high: Decimal,
+    #[serde(rename = "l")]
+    low: Decimal,
+    #[serde(rename = "c")]
+    close: Decimal,
+    #[serde(rename = "v")]
+    volume: Decimal,
+    #[serde(rename = "x")]
+    is_closed: bool,
+}
+
+impl WsKline {
+    fn into_kline(self) -> Result<Kline, Error> {
+        let timestamp = Utc
+            .timestamp_millis_opt(self.open_time)
+            .single()
+            .ok_or_else(|| Error::ParseError(format!("invalid open time {}", self.open_time)))?;
+
+        Ok(Kline {
+            timestamp,
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume: self.volume,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct WsBookTicker {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "b")]
+    bid_price: Decimal,
+    #[serde(rename = "B")]
+    bid_qty: Decimal,
+    #[serde(rename = "a")]
+    ask_price: Decimal,
+    #[serde(rename = "A")]
+    ask_qty: Decimal,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+    use futures::SinkExt;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+    use std::sync::Arc;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const T0: i64 = 1_700_000_000_000;
+    const MINUTE: i64 = 60_000;
+
+    fn btcusdt() -> Symbol {
+        Symbol::binance("BTC", "USDT")
+    }
+
+    fn rest_row(open_time: i64, close: &str) -> Value {
+        json!([
+            open_time,
+            close,
+            close,
+            close,
+            close,
+            "1.0",
+            open_time + MINUTE - 1
+        ])
+    }
+
+    fn ws_kline(open_time: i64, close: &str, closed: bool) -> String {
+        json!({
+            "stream": "btcusdt@kline_1m",
+            "data": {
+                "e": "kline", "E": open_time + MINUTE, "s": "BTCUSDT",
+                "k": {
+                    "t": open_time, "T": open_time + MINUTE - 1, "s": "BTCUSDT", "i": "1m",
+                    "o": close, "c": close, "h": close, "l": close, "v": "2.0", "x": closed
+                }
+            }
+        })
+        .to_string()
+    }
+
+    fn ws_book(bid: &str, ask: &str) -> String {
+        json!({
+            "stream": "btcusdt@bookTicker",
+            "data": {"u": 400900217, "s": "BTCUSDT", "b": bid, "B": "1.5", "a": ask, "A": "2.5"}
+        })
+        .to_string()
+    }
+
+    async fn mock_rest() -> MockServer {
+        let server = MockServer::start().await;
+        // 重连补齐：只返回最后一根之后的K线
+        Mock::given(method("GET"))
+            .and(path("/api/v3/klines"))
+            .and(query_param("startTime", (T0 + 2 * MINUTE + 1).to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                rest_row(T0 + 3 * MINUTE, "103"),
+                rest_row(T0 + 4 * MINUTE, "104"),
+            ])))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+        // 首次预热
+        Mock::given(method("GET"))
+            .and(path("/api/v3/klines"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!([rest_row(T0, "100"), rest_row(T0 + MINUTE, "101"),])),
+            )
+            .mount(&server)
+            .await;
+        server
+    }
+
+    fn market_stream(rest: &MockServer, ws_addr: std::net::SocketAddr) -> BinanceMarketStream {
+        let fetcher = BinanceDataFetcher::new(Arc::new(test_config())).with_base_url(rest.uri());
+        BinanceMarketStream::new(fetcher, vec![btcusdt()], Interval::Minute1, 3)
+            .with_ws_url(format!("ws://{}", ws_addr))
+            .with_backoff(Duration::from_millis(10), Duration::from_millis(50))
+    }
+
+    #[test]
+    fn test_stream_url() {
+        let fetcher = BinanceDataFetcher::new(Arc::new(test_config()));
+        let stream = BinanceMarketStream::new(
+            fetcher,
+            vec![btcusdt(), Symbol::binance("ETH", "USDT")],
+            Interval::Minute5,
+            100,
+        );
+        assert_eq!(
+            stream.stream_url(),
+            "wss://stream.binance.com:9443/stream?streams=\
+             btcusdt@kline_5m/btcusdt@bookTicker/ethusdt@kline_5m/ethusdt@bookTicker"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_reconnect_and_backfill() -> Result<(), Error> {
+        let rest = mock_rest().await;
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let ws_addr = listener.local_addr()?;
+
+        // 本地 WebSocket 替身：第一次连接推送一根收盘K线后断开，第二次连接继续推送
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(socket).await.unwrap();
+            ws.send(Message::Text(ws_book("99.5", "100.5")))
+                .await
+                .unwrap();
+            ws.send(Message::Text(ws_kline(T0 + 2 * MINUTE, "102", false)))
+                .await
+                .unwrap();
+            ws.send(Message::Text(ws_kline(T0 + 2 * MINUTE, "102", true)))
+                .await
+                .unwrap();
+            ws.close(None).await.unwrap();
+
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(socket).await.unwrap();
+            // 与补齐结果重复的K线应被去重
+            ws.send(Message::Text(ws_kline(T0 + 4 * MINUTE, "104", true)))
+                .await
+                .unwrap();
+            ws.send(Message::Text(ws_kline(T0 + 5 * MINUTE, "105", true)))
+                .await
+                .unwrap();
+            // 保持连接，直到客户端退出
+            while ws.next().await.is_some() {}
+        });
+
+        let stream = Arc::new(market_stream(&rest, ws_addr));
+        let (tx, mut rx) = mpsc::channel(16);
+        let runner = {
+            let stream = stream.clone();
+            tokio::spawn(async move { stream.run(tx).await })
+        };
+
+        let mut events = Vec::new();
+        while events.len() < 6 {
+            let event = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .expect("timed out waiting for stream events")
+                .expect("stream ended early");
+            events.push((event.kline.timestamp.timestamp_millis(), event.replay));
+        }
+        drop(rx);
+
+        assert_eq!(
+            events,
+            vec![
+                (T0, true),
+                (T0 + MINUTE, true),
+                (T0 + 2 * MINUTE, false),
+                (T0 + 3 * MINUTE, true),
+                (T0 + 4 * MINUTE, true),
+                (T0 + 5 * MINUTE, false),
+            ]
+        );
+
+        // 滚动窗口只保留最近 3 根
+        let window = stream.klines(&btcusdt(), 10);
+        assert_eq!(window.len(), 3);
+        assert_eq!(window[0].close, dec!(103));
+        assert_eq!(window[2].close, dec!(105));
+
+        assert_eq!(stream.fetch_ticker(&btcusdt()).await?, dec!(100));
+        let tail = stream
+            .fetch_klines(&btcusdt(), Interval::Minute1, 2)
+            .await?;
+        assert_eq!(tail.len(), 2);
+        assert_eq!(tail[1].close, dec!(105));
+
+        runner.abort();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_reports_kline_activity() -> Result<(), Error> {
+        let rest = mock_rest().await;
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let ws_addr = listener.local_addr()?;
+
+        tokio::spawn(async move {
+            // 只推送盘口就断开，不算可用连接
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(socket).await.unwrap();
+            ws.send(Message::Text(ws_book("99.5", "100.5")))
+                .await
+                .unwrap();
+            ws.close(None).await.unwrap();
+
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(socket).await.unwrap();
+            ws.send(Message::Text(ws_kline(T0 + 2 * MINUTE, "102", false)))
+                .await
+                .unwrap();
+            ws.close(None).await.unwrap();
+        });
+
+        let stream = market_stream(&rest, ws_addr);
+        let (tx, _rx) = mpsc::channel(16);
+        assert!(!stream.session(&tx).await?);
+        assert!(stream.session(&tx).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_backfill_pages_long_gap() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        let page = |first: i64, count: i64| {
+            (first..first + count)
+                .map(|i| rest_row(T0 + i * MINUTE, "100"))
+                .collect::<Vec<_>>()
+        };
+        Mock::given(method("GET"))
+            .and(path("/api/v3/klines"))
+            .and(query_param("startTime", (T0 + 1).to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(page(1, MAX_REST_LIMIT as i64)))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/klines"))
+            .and(query_param(
+                "startTime",
+                (T0 + MAX_REST_LIMIT as i64 * MINUTE + 1).to_string(),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(page(MAX_REST_LIMIT as i64 + 1, 2)),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let fetcher = BinanceDataFetcher::new(Arc::new(test_config())).with_base_url(server.uri());
+        let stream = BinanceMarketStream::new(fetcher, vec![btcusdt()], Interval::Minute1, 3);
+        stream.push_kline(
+            &btcusdt(),
+            serde_json::from_value::<Kline>(json!({
+                "timestamp": Utc.timestamp_millis_opt(T0).unwrap(),
+                "open": "100", "high": "100", "low": "100", "close": "100", "volume": "1"
+            }))?,
+        );
+
+        // 断线超过一页的缺口全部补齐
+        let (tx, mut rx) = mpsc::channel(2 * MAX_REST_LIMIT);
+        stream.backfill(&btcusdt(), &tx).await?;
+        drop(tx);
+        let mut replayed = Vec::new();
+        while let Some(event) = rx.recv().await {
+            assert!(event.replay);
+            replayed.push(event.kline.timestamp.timestamp_millis());
+        }
+        assert_eq!(replayed.len(), MAX_REST_LIMIT + 2);
+        assert_eq!(
+            replayed.last(),
+            Some(&(T0 + (MAX_REST_LIMIT as i64 + 2) * MINUTE))
+        );
+
+        Ok(())
+    }
+}
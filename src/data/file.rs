@@ -0,0 +1,621 @@
+// src/data/file.rs
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use chrono_tz::Tz;
+use parquet::basic::Compression;
+use parquet::data_type::{DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::file::writer::SerializedFileWriter;
+use parquet::record::Field;
+use parquet::schema::parser::parse_message_type;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::DataFetcher;
+use crate::error::Error;
+use crate::types::{Interval, Kline, Symbol};
+
+/// 导出 Parquet 使用的表结构，价格和成交量为 DOUBLE 以便其他工具直接读取
+const PARQUET_SCHEMA: &str = "
+    message kline {
+        REQUIRED INT64 timestamp (TIMESTAMP(MILLIS, true));
+        REQUIRED DOUBLE open;
+        REQUIRED DOUBLE high;
+        REQUIRED DOUBLE low;
+        REQUIRED DOUBLE close;
+        REQUIRED DOUBLE volume;
+    }
+";
+
+/// 按扩展名区分的文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Csv,
+    Parquet,
+}
+
+impl FileFormat {
+    pub fn from_path(path: &Path) -> Result<Self, Error> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("csv") => Ok(FileFormat::Csv),
+            Some("parquet") | Some("pq") => Ok(FileFormat::Parquet),
+            _ => Err(Error::ConfigError(format!(
+                "unsupported kline file {}",
+                path.display()
+            ))),
+        }
+    }
+}
+
+/// 文件列名到K线字段的映射
+#[derive(Debug, Clone)]
+pub struct ColumnMapping {
+    pub timestamp: String,
+    pub open: String,
+    pub high: String,
+    pub low: String,
+    pub close: String,
+    pub volume: String,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            timestamp: "timestamp".to_string(),
+            open: "open".to_string(),
+            high: "high".to_string(),
+            low: "low".to_string(),
+            close: "close".to_string(),
+            volume: "volume".to_string(),
+        }
+    }
+}
+
+impl ColumnMapping {
+    fn names(&self) -> [&str; 6] {
+        [
+            &self.timestamp,
+            &self.open,
+            &self.high,
+            &self.low,
+            &self.close,
+            &self.volume,
+        ]
+    }
+}
+
+/// 时间戳列的格式
+#[derive(Debug, Clone, Default)]
+pub enum TimestampFormat {
+    /// 整数按数量级识别为秒或毫秒，字符串依次尝试 RFC 3339 和常见日期格式
+    #[default]
+    Auto,
+    Seconds,
+    Millis,
+    Rfc3339,
+    /// chrono 格式串，如 `%Y%m%d` 或 `%Y-%m-%d %H:%M:%S`，按配置的时区解释
+    Pattern(String),
+}
+
+/// `Auto` 模式下尝试的本地时间格式
+const AUTO_PATTERNS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%d",
+    "%Y%m%d",
+];
+
+/// 小于该值的整数时间戳视为秒
+const MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// 从本地 CSV / Parquet 文件读取K线的数据源
+///
+/// 每个 (交易对, 周期) 对应一个文件，每次请求时重新读取以反映文件更新。
+pub struct FileDataFetcher {
+    files: HashMap<(Symbol, Interval), PathBuf>,
+    columns: ColumnMapping,
+    timestamp_format: TimestampFormat,
+    timezone: Tz,
+    delimiter: u8,
+}
+
+impl Default for FileDataFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileDataFetcher {
+    pub fn new() -> Self {
+        Self {
+            files: HashMap::new(),
+            columns: ColumnMapping::default(),
+            timestamp_format: TimestampFormat::default(),
+            timezone: Tz::UTC,
+            delimiter: b',',
+        }
+    }
+
+    pub fn with_file(
+        mut self,
+        symbol: Symbol,
+        interval: Interval,
+        path: impl Into<PathBuf>,
+    ) -> Self {
+        self.files.insert((symbol, interval), path.into());
+        self
+    }
+
+    pub fn with_columns(mut self, columns: ColumnMapping) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn with_timestamp_format(mut self, format: TimestampFormat) -> Self {
+        self.timestamp_format = format;
+        self
+    }
+
+    /// 不带时区的时间戳按该时区解释，默认 UTC
+    pub fn with_timezone(mut self, timezone: Tz) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// CSV 分隔符，默认逗号
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// 读取文件中的全部K线，按时间升序返回
+    pub fn read(&self, path: &Path) -> Result<Vec<Kline>, Error> {
+        let mut klines = match FileFormat::from_path(path)? {
+            FileFormat::Csv => self.read_csv(path)?,
+            FileFormat::Parquet => self.read_parquet(path)?,
+        };
+        klines.sort_by_key(|k| k.timestamp);
+        klines.dedup_by_key(|k| k.timestamp);
+        Ok(klines)
+    }
+
+    fn read_csv(&self, path: &Path) -> Result<Vec<Kline>, Error> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(self.delimiter)
+            .trim(csv::Trim::All)
+            .from_path(path)?;
+
+        let headers = reader.headers()?.clone();
+        let mut indices = [0usize; 6];
+        for (index, name) in indices.iter_mut().zip(self.columns.names()) {
+            *index = headers
+                .iter()
+                .position(|h| h == name)
+                .ok_or_else(|| missing_column(name, path))?;
+        }
+
+        let mut klines = Vec::new();
+        for (row, record) in reader.records().enumerate() {
+            let record = record?;
+            let field = |i: usize| record.get(indices[i]).unwrap_or_default();
+            let decimal = |i: usize| {
+                Decimal::from_str(field(i)).or_else(|_| Decimal::from_scientific(field(i)))
+            };
+
+            let parse = || -> Result<Kline, String> {
+                Ok(Kline {
+                    timestamp: self.parse_timestamp(field(0))?,
+                    open: decimal(1).map_err(|e| e.to_string())?,
+                    high: decimal(2).map_err(|e| e.to_string())?,
+                    low: decimal(3).map_err(|e| e.to_string())?,
+                    close: decimal(4).map_err(|e| e.to_string())?,
+                    volume: decimal(5).map_err(|e| e.to_string())?,
+                })
+            };
+            klines.push(parse().map_err(|e| {
+                Error::ParseError(format!("{} row {}: {}", path.display(), row + 1, e))
+            })?);
+        }
+
+        Ok(klines)
+    }
+
+    fn read_parquet(&self, path: &Path) -> Result<Vec<Kline>, Error> {
+        let reader = SerializedFileReader::new(File::open(path)?)?;
+
+        let mut klines = Vec::new();
+        for (row, record) in reader.get_row_iter(None)?.enumerate() {
+            let record = record?;
+            let mut fields: [Option<&Field>; 6] = [None; 6];
+            for (name, field) in record.get_column_iter() {
+                if let Some(i) = self.columns.names().iter().position(|c| c == name) {
+                    fields[i] = Some(field);
+                }
+            }
+
+            let parse = || -> Result<Kline, String> {
+                let get = |i: usize| {
+                    fields[i].ok_or_else(|| format!("missing column {}", self.columns.names()[i]))
+                };
+                Ok(Kline {
+                    timestamp: self.field_timestamp(get(0)?)?,
+                    open: field_decimal(get(1)?)?,
+                    high: field_decimal(get(2)?)?,
+                    low: field_decimal(get(3)?)?,
+                    close: field_decimal(get(4)?)?,
+                    volume: field_decimal(get(5)?)?,
+                })
+            };
+            klines.push(parse().map_err(|e| {
+                Error::ParseError(format!("{} row {}: {}", path.display(), row + 1, e))
+            })?);
+        }
+
+        Ok(klines)
+    }
+
+    fn parse_timestamp(&self, value: &str) -> Result<DateTime<Utc>, String> {
+        let invalid = || format!("invalid timestamp {:?}", value);
+
+        match &self.timestamp_format {
+            TimestampFormat::Seconds => value
+                .parse()
+                .ok()
+                .and_then(|s| Utc.timestamp_opt(s, 0).single())
+                .ok_or_else(invalid),
+            TimestampFormat::Millis => value
+                .parse()
+                .ok()
+                .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+                .ok_or_else(invalid),
+            TimestampFormat::Rfc3339 => DateTime::parse_from_rfc3339(value)
+                .map(|t| t.with_timezone(&Utc))
+                .map_err(|_| invalid()),
+            TimestampFormat::Pattern(pattern) => {
+                self.parse_local(value, pattern).ok_or_else(invalid)
+            }
+            TimestampFormat::Auto => {
+                if let Ok(number) = value.parse::<i64>() {
+                    return self.numeric_timestamp(number).ok_or_else(invalid);
+                }
+                if let Ok(t) = DateTime::parse_from_rfc3339(value) {
+                    return Ok(t.with_timezone(&Utc));
+                }
+                AUTO_PATTERNS
+                    .iter()
+                    .find_map(|pattern| self.parse_local(value, pattern))
+                    .ok_or_else(invalid)
+            }
+        }
+    }
+
+    fn field_timestamp(&self, field: &Field) -> Result<DateTime<Utc>, String> {
+        let invalid = || format!("invalid timestamp {}", field);
+
+        match field {
+            Field::TimestampMillis(ms) => {
+                Utc.timestamp_millis_opt(*ms).single().ok_or_else(invalid)
+            }
+            Field::TimestampMicros(us) => Utc.timestamp_micros(*us).single().ok_or_else(invalid),
+            Field::Date(days) => NaiveDate::from_num_days_from_ce_opt(*days + 719_163)
+                .and_then(|d| self.localize(d.and_time(chrono::NaiveTime::MIN)))
+                .ok_or_else(invalid),
+            Field::Int(n) => self.numeric_field(i64::from(*n)).ok_or_else(invalid),
+            Field::Long(n) => self.numeric_field(*n).ok_or_else(invalid),
+            Field::Str(s) => self.parse_timestamp(s),
+            _ => Err(invalid()),
+        }
+    }
+
+    fn numeric_field(&self, number: i64) -> Option<DateTime<Utc>> {
+        match self.timestamp_format {
+            TimestampFormat::Seconds => Utc.timestamp_opt(number, 0).single(),
+            TimestampFormat::Millis => Utc.timestamp_millis_opt(number).single(),
+            _ => self.numeric_timestamp(number),
+        }
+    }
+
+    fn numeric_timestamp(&self, number: i64) -> Option<DateTime<Utc>> {
+        if number.abs() < MILLIS_THRESHOLD {
+            Utc.timestamp_opt(number, 0).single()
+        } else {
+            Utc.timestamp_millis_opt(number).single()
+        }
+    }
+
+    /// 按配置时区解析不带时区的时间，只有日期时取当日零点
+    fn parse_local(&self, value: &str, pattern: &str) -> Option<DateTime<Utc>> {
+        let naive = NaiveDateTime::parse_from_str(value, pattern)
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(value, pattern)
+                    .ok()
+                    .map(|d| d.and_time(chrono::NaiveTime::MIN))
+            })?;
+        self.localize(naive)
+    }
+
+    fn localize(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        // 夏令时重叠时取较早的时刻
+        self.timezone
+            .from_local_datetime(&naive)
+            .earliest()
+            .map(|t| t.with_timezone(&Utc))
+    }
+
+    fn load(&self, symbol: &Symbol, interval: Interval) -> Result<Vec<Kline>, Error> {
+        let path = self.files.get(&(symbol.clone(), interval)).ok_or_else(|| {
+            Error::ConfigError(format!("no kline file for {} {}", symbol, interval))
+        })?;
+        self.read(path)
+    }
+}
+
+#[async_trait]
+impl DataFetcher for FileDataFetcher {
+    async fn fetch(&self) -> Result<Vec<Kline>, Error> {
+        match self.files.values().next() {
+            Some(path) => self.read(path),
+            None => Ok(vec![]),
+        }
+    }
+
+    async fn fetch_klines(
+        &self,
+        symbol: &Symbol,
+        interval: Interval,
+        limit: u32,
+    ) -> Result<Vec<Kline>, Error> {
+        let klines = self.load(symbol, interval)?;
+        let skip = klines.len().saturating_sub(limit as usize);
+        Ok(klines[skip..].to_vec())
+    }
+
+    async fn fetch_klines_since(
+        &self,
+        symbol: &Symbol,
+        interval: Interval,
+        start: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<Kline>, Error> {
+        Ok(self
+            .load(symbol, interval)?
+            .into_iter()
+            .filter(|k| k.timestamp >= start)
+            .take(limit as usize)
+            .collect())
+    }
+
+    /// 文件数据没有实时报价，取最后一根K线的收盘价
+    async fn fetch_ticker(&self, symbol: &Symbol) -> Result<Decimal, Error> {
+        self.files
+            .iter()
+            .filter(|((s, _), _)| s == symbol)
+            .find_map(|(_, path)| self.read(path).ok()?.last().map(|k| k.close))
+            .ok_or_else(|| Error::ApiError(format!("no kline data for {}", symbol)))
+    }
+}
+
+fn missing_column(name: &str, path: &Path) -> Error {
+    Error::ParseError(format!("{}: missing column {}", path.display(), name))
+}
+
+fn field_decimal(field: &Field) -> Result<Decimal, String> {
+    let invalid = || format!("invalid number {}", field);
+
+    match field {
+        Field::Int(n) => Ok(Decimal::from(*n)),
+        Field::Long(n) => Ok(Decimal::from(*n)),
+        Field::Float(f) => Decimal::from_f32(*f).ok_or_else(invalid),
+        Field::Double(f) => Decimal::from_f64(*f).ok_or_else(invalid),
+        Field::Str(s) => Decimal::from_str(s).map_err(|_| invalid()),
+        Field::Decimal(d) => {
+            // 大端补码的无标度整数
+            let bytes = d.data();
+            if bytes.is_empty() || bytes.len() > 16 {
+                return Err(invalid());
+            }
+            let fill = if bytes[0] & 0x80 != 0 { 0xff } else { 0 };
+            let mut buf = [fill; 16];
+            buf[16 - bytes.len()..].copy_from_slice(bytes);
+            Decimal::try_from_i128_with_scale(i128::from_be_bytes(buf), d.scale() as u32)
+                .map_err(|_| invalid())
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// 按扩展名导出K线到 CSV 或 Parquet 文件
+pub fn export_klines(path: &Path, klines: &[Kline]) -> Result<(), Error> {
+    match FileFormat::from_path(path)? {
+        FileFormat::Csv => write_csv(path, klines),
+        FileFormat::Parquet => write_parquet(path, klines),
+    }
+}
+
+/// 导出为 CSV，时间戳为 RFC 3339，数值保留原始精度
+pub fn write_csv(path: &Path, klines: &[Kline]) -> Result<(), Error> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(ColumnMapping::default().names())?;
+    for k in klines {
+        writer.write_record([
+            k.timestamp.to_rfc3339_opts(SecondsFormat::Millis, true),
+            k.open.to_string(),
+            k.high.to_string(),
+            k.low.to_string(),
+            k.close.to_string(),
+            k.volume.to_string(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// 导出为 Snappy 压缩的 Parquet，时间戳为 UTC 毫秒
+pub fn write_parquet(path: &Path, klines: &[Kline]) -> Result<(), Error> {
+    let schema = Arc::new(parse_message_type(PARQUET_SCHEMA)?);
+    let props = Arc::new(
+        WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build(),
+    );
+    let mut writer = SerializedFileWriter::new(File::create(path)?, schema, props)?;
+    let mut row_group = writer.next_row_group()?;
+
+    let timestamps: Vec<i64> = klines
+        .iter()
+        .map(|k| k.timestamp.timestamp_millis())
+        .collect();
+    if let Some(mut column) = row_group.next_column()? {
+        column
+            .typed::<Int64Type>()
+            .write_batch(&timestamps, None, None)?;
+        column.close()?;
+    }
+
+    let values: [fn(&Kline) -> Decimal; 5] =
+        [|k| k.open, |k| k.high, |k| k.low, |k| k.close, |k| k.volume];
+    for value in values {
+        let column_values: Vec<f64> = klines
+            .iter()
+            .map(|k| value(k).to_f64().unwrap_or(f64::NAN))
+            .collect();
+        if let Some(mut column) = row_group.next_column()? {
+            column
+                .typed::<DoubleType>()
+                .write_batch(&column_values, None, None)?;
+            column.close()?;
+        }
+    }
+
+    row_group.close()?;
+    writer.close()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use rust_decimal_macros::dec;
+
+    fn btcusdt() -> Symbol {
+        Symbol::binance("BTC", "USDT")
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rqt-{}-{}", std::process::id(), name))
+    }
+
+    fn sample() -> Vec<Kline> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        (0..3)
+            .map(|i| Kline {
+                timestamp: start + Duration::hours(i),
+                open: dec!(42000.5) + Decimal::from(i),
+                high: dec!(42100.25),
+                low: dec!(41900.75),
+                close: dec!(42050.125),
+                volume: dec!(12.5),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_csv_round_trip() -> Result<(), Error> {
+        let path = temp_path("round-trip.csv");
+        export_klines(&path, &sample())?;
+
+        let fetcher = FileDataFetcher::new().with_file(btcusdt(), Interval::Hour1, &path);
+        let klines = fetcher.fetch_klines(&btcusdt(), Interval::Hour1, 2).await?;
+        assert_eq!(klines.len(), 2);
+        assert_eq!(klines[1].timestamp, sample()[2].timestamp);
+        assert_eq!(klines[1].open, dec!(42002.5));
+        assert_eq!(fetcher.fetch_ticker(&btcusdt()).await?, dec!(42050.125));
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parquet_round_trip() -> Result<(), Error> {
+        let path = temp_path("round-trip.parquet");
+        export_klines(&path, &sample())?;
+
+        let fetcher = FileDataFetcher::new().with_file(btcusdt(), Interval::Hour1, &path);
+        let start = sample()[1].timestamp;
+        let klines = fetcher
+            .fetch_klines_since(&btcusdt(), Interval::Hour1, start, 10)
+            .await?;
+        assert_eq!(klines.len(), 2);
+        assert_eq!(klines[0].timestamp, start);
+        assert_eq!(klines[0].open, dec!(42001.5));
+        assert_eq!(klines[0].close, dec!(42050.125));
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_vendor_csv_mapping_and_timezone() -> Result<(), Error> {
+        let path = temp_path("vendor.csv");
+        std::fs::write(
+            &path,
+            "trade_date;o;h;l;c;vol\n20240102;10.1;10.5;9.9;10.3;1200\n20240103;10.3;10.8;10.2;10.7;1500\n",
+        )?;
+
+        let fetcher = FileDataFetcher::new()
+            .with_delimiter(b';')
+            .with_columns(ColumnMapping {
+                timestamp: "trade_date".to_string(),
+                open: "o".to_string(),
+                high: "h".to_string(),
+                low: "l".to_string(),
+                close: "c".to_string(),
+                volume: "vol".to_string(),
+            })
+            .with_timestamp_format(TimestampFormat::Pattern("%Y%m%d".to_string()))
+            .with_timezone(chrono_tz::Asia::Shanghai);
+        let klines = fetcher.read(&path)?;
+
+        // 北京时间零点即 UTC 前一日 16:00
+        assert_eq!(
+            klines[0].timestamp,
+            Utc.with_ymd_and_hms(2024, 1, 1, 16, 0, 0).unwrap()
+        );
+        assert_eq!(klines[1].close, dec!(10.7));
+
+        // 缺少成交量列
+        std::fs::write(&path, "trade_date;o;h;l;c\n20240102;1;1;1;1\n")?;
+        assert!(matches!(fetcher.read(&path), Err(Error::ParseError(_))));
+
+        std::fs::remove_file(path)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_auto_timestamps() {
+        let fetcher = FileDataFetcher::new();
+        let expected = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        for value in [
+            "1704067200",
+            "1704067200000",
+            "2024-01-01T00:00:00Z",
+            "2024-01-01 00:00:00",
+            "2024-01-01",
+        ] {
+            assert_eq!(fetcher.parse_timestamp(value), Ok(expected), "{}", value);
+        }
+        assert!(fetcher.parse_timestamp("yesterday").is_err());
+    }
+}
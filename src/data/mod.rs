@@ -1,22 +1,65 @@
 mod binance;
-pub use binance::BinanceDataFetcher;
+pub use binance::{BinanceDataFetcher, BINANCE_API_URL, BINANCE_TESTNET_API_URL};
+
+mod binance_stream;
+pub use binance_stream::{
+    BinanceMarketStream, BookTicker, StreamKline, BINANCE_FUTURES_TESTNET_WS_URL,
+    BINANCE_FUTURES_WS_URL, BINANCE_TESTNET_WS_URL, BINANCE_WS_URL,
+};
+
+mod file;
+pub use file::{
+    export_klines, write_csv, write_parquet, ColumnMapping, FileDataFetcher, FileFormat,
+    TimestampFormat,
+};
+
+mod resample;
+pub use resample::{resample, CalendarPreset, MultiTimeframe, Resampler, SessionCalendar};
 
 mod tushare;
 pub use tushare::TushareDataFetcher;
 
 use crate::error::Error;
-use crate::types::Kline;
+use crate::types::{Interval, Kline, Symbol};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 
+/// 剔除到 `now` 仍未收盘的K线，REST 返回的最后一根通常还在形成中
+pub fn closed_klines(klines: Vec<Kline>, interval: Interval, now: DateTime<Utc>) -> Vec<Kline> {
+    let step = interval.duration();
+    klines
+        .into_iter()
+        .filter(|k| k.timestamp + step <= now)
+        .collect()
+}
+
 #[async_trait]
-pub trait DataFetcher {
+pub trait DataFetcher: Send + Sync {
     async fn fetch(&self) -> Result<Vec<Kline>, Error>;
     async fn fetch_klines(
         &self,
-        symbol: &str,
-        interval: &str,
+        symbol: &Symbol,
+        interval: Interval,
         limit: u32,
     ) -> Result<Vec<Kline>, Error>;
-    async fn fetch_ticker(&self, symbol: &str) -> Result<Decimal, Error>;
+
+    /// 获取 `start` 之后（含）最多 `limit` 根K线，用于增量同步和缺口补齐
+    ///
+    /// 默认实现取最近 `limit` 根再按时间过滤，支持按起始时间查询的数据源应覆盖。
+    async fn fetch_klines_since(
+        &self,
+        symbol: &Symbol,
+        interval: Interval,
+        start: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<Kline>, Error> {
+        let klines = self.fetch_klines(symbol, interval, limit).await?;
+        Ok(klines
+            .into_iter()
+            .filter(|k| k.timestamp >= start)
+            .collect())
+    }
+
+    async fn fetch_ticker(&self, symbol: &Symbol) -> Result<Decimal, Error>;
 }
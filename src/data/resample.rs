@@ -0,0 +1,348 @@
+// src/data/resample.rs
+use crate::error::Error;
+use crate::types::{Interval, Kline, WEEK_ANCHOR};
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+/// 交易时段日历：日内周期在每个时段内从开盘起切分，日线及以上按当地日期切分
+#[derive(Debug, Clone)]
+pub struct SessionCalendar {
+    timezone: Tz,
+    sessions: Vec<(NaiveTime, NaiveTime)>,
+}
+
+impl SessionCalendar {
+    /// 时段按当地时间给出，需按时间先后排列且不跨午夜
+    pub fn new(timezone: Tz, sessions: Vec<(NaiveTime, NaiveTime)>) -> Result<Self, Error> {
+        if sessions.is_empty() {
+            return Err(Error::config("session calendar needs at least one session"));
+        }
+        let mut last_end = NaiveTime::MIN;
+        for &(start, end) in &sessions {
+            if start >= end || start < last_end {
+                return Err(Error::config(format!(
+                    "invalid session {}-{}: sessions must be ordered and not overlap",
+                    start, end
+                )));
+            }
+            last_end = end;
+        }
+        Ok(Self { timezone, sessions })
+    }
+
+    /// A 股：北京时间 09:30-11:30、13:00-15:00
+    pub fn a_share() -> Self {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).expect("valid session time");
+        Self {
+            timezone: chrono_tz::Asia::Shanghai,
+            sessions: vec![(time(9, 30), time(11, 30)), (time(13, 0), time(15, 0))],
+        }
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// 当地时间 `time` 所在的时段，收盘时刻计入该时段（部分数据源以收盘时间标记K线）
+    fn session(&self, time: NaiveTime) -> Option<(NaiveTime, NaiveTime)> {
+        self.sessions
+            .iter()
+            .copied()
+            .find(|&(start, end)| start <= time && time <= end)
+    }
+}
+
+/// 配置文件中可选的交易时段日历
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalendarPreset {
+    AShare,
+}
+
+impl From<CalendarPreset> for SessionCalendar {
+    fn from(preset: CalendarPreset) -> Self {
+        match preset {
+            CalendarPreset::AShare => SessionCalendar::a_share(),
+        }
+    }
+}
+
+/// 将K线聚合为更长周期：开盘取首根、收盘取末根、最高最低取极值、成交量求和
+///
+/// 聚合后的K线以所在区间的起始时间标记，最后一根可能尚未走完。
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    interval: Interval,
+    calendar: Option<SessionCalendar>,
+}
+
+impl Resampler {
+    pub fn new(interval: Interval) -> Self {
+        Self {
+            interval,
+            calendar: None,
+        }
+    }
+
+    /// 按交易时段切分，不在任何时段内的日内K线会被丢弃
+    pub fn with_calendar(mut self, calendar: SessionCalendar) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
+
+    pub fn interval(&self) -> Interval {
+        self.interval
+    }
+
+    /// 检查源周期能否无缝聚合为目标周期
+    pub fn check_source(&self, interval: Interval) -> Result<(), Error> {
+        if !self.interval.is_multiple_of(interval) {
+            return Err(Error::config(format!(
+                "cannot resample {} klines into {}",
+                interval, self.interval
+            )));
+        }
+        Ok(())
+    }
+
+    /// `timestamp` 所属聚合区间的起始时间，不在交易时段内时为 `None`
+    pub fn bucket(&self, timestamp: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match &self.calendar {
+            None => Some(self.interval.floor(timestamp)),
+            Some(calendar) => self.session_bucket(calendar, timestamp),
+        }
+    }
+
+    pub fn resample(&self, klines: &[Kline]) -> Vec<Kline> {
+        let mut series = Vec::new();
+        for kline in klines {
+            self.update(&mut series, kline);
+        }
+        series
+    }
+
+    /// 把一根新K线并入已聚合的序列，输入需按时间升序
+    pub fn update(&self, series: &mut Vec<Kline>, kline: &Kline) {
+        let Some(bucket) = self.bucket(kline.timestamp) else {
+            return;
+        };
+
+        match series.last_mut() {
+            Some(last) if last.timestamp == bucket => {
+                last.high = last.high.max(kline.high);
+                last.low = last.low.min(kline.low);
+                last.close = kline.close;
+                last.volume += kline.volume;
+            }
+            _ => series.push(Kline {
+                timestamp: bucket,
+                ..kline.clone()
+            }),
+        }
+    }
+
+    fn session_bucket(
+        &self,
+        calendar: &SessionCalendar,
+        timestamp: DateTime<Utc>,
+    ) -> Option<DateTime<Utc>> {
+        let local = timestamp.with_timezone(&calendar.timezone).naive_local();
+        let step = self.interval.duration();
+
+        let start = if step.num_milliseconds() % Duration::days(1).num_milliseconds() == 0 {
+            // 日线及以上：按当地日期切分，整周从周一开始
+            let days = step.num_days();
+            let offset = (local.date() - WEEK_ANCHOR).num_days();
+            let date = WEEK_ANCHOR + Duration::days(offset.div_euclid(days) * days);
+            date.and_time(NaiveTime::MIN)
+        } else {
+            // 日内：区间不跨时段，午休前后分别从各自开盘起算
+            let (open, close) = calendar.session(local.time())?;
+            let mut elapsed = (local.time() - open).num_milliseconds();
+            if local.time() == close {
+                elapsed -= 1;
+            }
+            let step = step.num_milliseconds();
+            local.date().and_time(open) + Duration::milliseconds(elapsed.max(0) / step * step)
+        };
+
+        calendar
+            .timezone
+            .from_local_datetime(&start)
+            .earliest()
+            .map(|start| start.with_timezone(&Utc))
+    }
+}
+
+/// 按 UTC 对齐聚合为 `interval` 周期
+pub fn resample(klines: &[Kline], interval: Interval) -> Vec<Kline> {
+    Resampler::new(interval).resample(klines)
+}
+
+/// 同一交易对多个周期的K线视图，供策略一次查询
+///
+/// 长周期的最后一根由已收盘的基础K线聚合而来，可能尚未走完。
+#[derive(Debug, Clone)]
+pub struct MultiTimeframe<'a> {
+    interval: Interval,
+    series: Vec<(Interval, &'a [Kline])>,
+}
+
+impl<'a> MultiTimeframe<'a> {
+    pub fn new(interval: Interval, base: &'a [Kline]) -> Self {
+        Self {
+            interval,
+            series: vec![(interval, base)],
+        }
+    }
+
+    pub fn with_series(mut self, interval: Interval, klines: &'a [Kline]) -> Self {
+        self.series.push((interval, klines));
+        self
+    }
+
+    /// 基础周期
+    pub fn interval(&self) -> Interval {
+        self.interval
+    }
+
+    /// 基础周期的K线
+    pub fn base(&self) -> &'a [Kline] {
+        self.series[0].1
+    }
+
+    pub fn get(&self, interval: Interval) -> Option<&'a [Kline]> {
+        self.series
+            .iter()
+            .find(|(i, _)| *i == interval)
+            .map(|(_, klines)| *klines)
+    }
+
+    pub fn intervals(&self) -> impl Iterator<Item = Interval> + '_ {
+        self.series.iter().map(|(interval, _)| *interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn kline(timestamp: DateTime<Utc>, open: Decimal, close: Decimal) -> Kline {
+        Kline {
+            timestamp,
+            open,
+            high: open.max(close) + dec!(1),
+            low: open.min(close) - dec!(1),
+            close,
+            volume: dec!(10),
+        }
+    }
+
+    fn shanghai(s: &str) -> DateTime<Utc> {
+        let local = NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        chrono_tz::Asia::Shanghai
+            .from_local_datetime(&local)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_resample_ohlcv() -> Result<(), Error> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let closes = [dec!(10), dec!(12), dec!(9), dec!(11), dec!(15), dec!(14)];
+        let klines: Vec<Kline> = closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| {
+                kline(
+                    start + Duration::minutes(5 * i as i64),
+                    close - dec!(1),
+                    close,
+                )
+            })
+            .collect();
+
+        let bars = resample(&klines, Interval::Minute15);
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[0].timestamp, start);
+        assert_eq!(bars[0].open, dec!(9));
+        assert_eq!(bars[0].high, dec!(13));
+        assert_eq!(bars[0].low, dec!(7));
+        assert_eq!(bars[0].close, dec!(9));
+        assert_eq!(bars[0].volume, dec!(30));
+        assert_eq!(bars[1].timestamp, start + Duration::minutes(15));
+        assert_eq!(bars[1].open, dec!(10));
+        assert_eq!(bars[1].close, dec!(14));
+
+        // 2024-01-03 是周三，周线从周一 01-01 开始
+        let weekly = Resampler::new(Interval::Week1);
+        assert_eq!(weekly.bucket(start + Duration::days(2)), Some(start));
+        assert!(weekly.check_source(Interval::Day1).is_ok());
+        assert!(Resampler::new(Interval::Day1)
+            .check_source(Interval::Week1)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_a_share_sessions() -> Result<(), Error> {
+        let hourly = Resampler::new(Interval::Hour1).with_calendar(SessionCalendar::a_share());
+
+        // 上午按 09:30、10:30 切分，下午从 13:00 重新起算
+        assert_eq!(
+            hourly.bucket(shanghai("2024-01-02 10:29")),
+            Some(shanghai("2024-01-02 09:30"))
+        );
+        assert_eq!(
+            hourly.bucket(shanghai("2024-01-02 11:30")),
+            Some(shanghai("2024-01-02 10:30"))
+        );
+        assert_eq!(
+            hourly.bucket(shanghai("2024-01-02 13:15")),
+            Some(shanghai("2024-01-02 13:00"))
+        );
+        assert_eq!(hourly.bucket(shanghai("2024-01-02 12:00")), None);
+
+        // 日线以北京时间日期切分，周线从周一开始
+        let daily: Vec<Kline> = ["2024-01-02 00:00", "2024-01-03 00:00", "2024-01-08 00:00"]
+            .iter()
+            .map(|s| kline(shanghai(s), dec!(10), dec!(11)))
+            .collect();
+        let weekly = Resampler::new(Interval::Week1)
+            .with_calendar(SessionCalendar::a_share())
+            .resample(&daily);
+        assert_eq!(weekly.len(), 2);
+        assert_eq!(weekly[0].timestamp, shanghai("2024-01-01 00:00"));
+        assert_eq!(weekly[0].volume, dec!(20));
+        assert_eq!(weekly[1].timestamp, shanghai("2024-01-08 00:00"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_multi_timeframe() -> Result<(), Error> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let base: Vec<Kline> = (0..120)
+            .map(|i| kline(start + Duration::minutes(i), dec!(1), dec!(2)))
+            .collect();
+        let hourly = resample(&base, Interval::Hour1);
+
+        let view =
+            MultiTimeframe::new(Interval::Minute1, &base).with_series(Interval::Hour1, &hourly);
+        assert_eq!(view.base().len(), 120);
+        assert_eq!(view.get(Interval::Minute1).map(<[Kline]>::len), Some(120));
+        assert_eq!(view.get(Interval::Hour1).map(<[Kline]>::len), Some(2));
+        assert!(view.get(Interval::Hour4).is_none());
+        assert_eq!(
+            view.intervals().collect::<Vec<_>>(),
+            vec![Interval::Minute1, Interval::Hour1]
+        );
+
+        Ok(())
+    }
+}
@@ -2,9 +2,10 @@
 use super::DataFetcher;
 
 use crate::error::Error; // 自定义错误类型
-use crate::types::Kline; // 假设 Kline 在 types.rs 中定义
+use crate::types::{Interval, Kline, Symbol};
 use async_trait::async_trait;
 use chrono::prelude::*;
+use chrono_tz::Asia::Shanghai;
 use reqwest::Client;
 use rust_decimal::Decimal;
 use serde_json::json;
@@ -24,6 +25,26 @@ impl TushareDataFetcher {
     }
 }
 
+/// 交易日期（`YYYYMMDD`）对应的北京时间零点
+fn trade_date(s: &str) -> Result<DateTime<Utc>, Error> {
+    let date =
+        NaiveDate::parse_from_str(s, "%Y%m%d").map_err(|e| Error::ParseError(e.to_string()))?;
+    Shanghai
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .single()
+        .map(|t| t.with_timezone(&Utc))
+        .ok_or_else(|| Error::ParseError(format!("invalid trade date: {}", s)))
+}
+
+/// 行情字段可能是数字或字符串
+fn decimal(value: &serde_json::Value) -> Decimal {
+    match value {
+        serde_json::Value::String(s) => Decimal::from_str(s).unwrap_or_default(),
+        serde_json::Value::Number(n) => Decimal::from_str(&n.to_string()).unwrap_or_default(),
+        _ => Decimal::ZERO,
+    }
+}
+
 #[async_trait]
 impl DataFetcher for TushareDataFetcher {
     async fn fetch(&self) -> Result<Vec<Kline>, Error> {
@@ -32,16 +53,24 @@ impl DataFetcher for TushareDataFetcher {
 
     async fn fetch_klines(
         &self,
-        symbol: &str,
-        interval: &str,
+        symbol: &Symbol,
+        interval: Interval,
         limit: u32,
     ) -> Result<Vec<Kline>, Error> {
+        // `daily` 接口只提供日线，更长周期由 `Resampler` 聚合
+        if interval != Interval::Day1 {
+            return Err(Error::ApiError(format!(
+                "tushare daily API does not provide {} klines",
+                interval
+            )));
+        }
+
         let url = "https://api.tushare.pro";
         let request_body = json!({
             "api_name": "daily",
             "token": self.api_key,
             "params": {
-                "ts_code": symbol,
+                "ts_code": symbol.to_string(),
                 "start_date": "20220101", // 示例开始日期
                 "end_date": "20221231", // 示例结束日期
                 "limit": limit
@@ -65,27 +94,27 @@ impl DataFetcher for TushareDataFetcher {
         if let Some(items) = response["data"]["items"].as_array() {
             for item in items {
                 let kline = Kline {
-                    timestamp: DateTime::parse_from_str(item[0].as_str().unwrap_or(""), "%Y%m%d")
-                        .map_err(|e| Error::ParseError(e.to_string()))?
-                        .with_timezone(&Utc),
-                    open: Decimal::from_str(item[1].as_str().unwrap_or("0")).unwrap_or_default(),
-                    high: Decimal::from_str(item[2].as_str().unwrap_or("0")).unwrap_or_default(),
-                    low: Decimal::from_str(item[3].as_str().unwrap_or("0")).unwrap_or_default(),
-                    close: Decimal::from_str(item[4].as_str().unwrap_or("0")).unwrap_or_default(),
-                    volume: Decimal::from_str(item[5].as_str().unwrap_or("0")).unwrap_or_default(),
+                    timestamp: trade_date(item[0].as_str().unwrap_or(""))?,
+                    open: decimal(&item[1]),
+                    high: decimal(&item[2]),
+                    low: decimal(&item[3]),
+                    close: decimal(&item[4]),
+                    volume: decimal(&item[5]),
                 };
                 klines.push(kline);
             }
         }
+        // Tushare 按日期倒序返回
+        klines.sort_by_key(|k| k.timestamp);
         Ok(klines)
     }
 
-    async fn fetch_ticker(&self, symbol: &str) -> Result<Decimal, Error> {
+    async fn fetch_ticker(&self, symbol: &Symbol) -> Result<Decimal, Error> {
         let url = "https://api.tushare.pro";
         let request_body = json!({
             "api_name": "daily_basic",
             "token": self.api_key,
-            "params": { "ts_code": symbol },
+            "params": { "ts_code": symbol.to_string() },
             "fields": "close"
         });
 
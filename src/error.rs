@@ -1,4 +1,5 @@
 use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
 // pub type Result<T> = std::result::Result<T, Error>;
@@ -14,9 +15,24 @@ pub enum Error {
     #[error("JSON serialization error: {0}")]
     JsonError(#[from] serde_json::Error),
 
+    #[error("Storage error: {0}")]
+    StorageError(#[from] rusqlite::Error),
+
+    #[error("CSV error: {0}")]
+    CsvError(#[from] csv::Error),
+
+    #[error("Parquet error: {0}")]
+    ParquetError(#[from] parquet::errors::ParquetError),
+
+    #[error("WebSocket error: {0}")]
+    WebSocketError(String),
+
     #[error("API error: {0}")]
     ApiError(String),
 
+    #[error("Exchange error: {0}")]
+    ExchangeError(ExchangeError),
+
     #[error("Configuration error: {0}")]
     ConfigError(String),
 
@@ -26,6 +42,9 @@ pub enum Error {
     #[error("Strategy error: {0}")]
     StrategyError(String),
 
+    #[error("Invalid order: {0}")]
+    InvalidOrder(String),
+
     #[error("Risk check failed: {0}")]
     RiskError(String),
 
@@ -51,6 +70,74 @@ impl Error {
     pub fn config(msg: impl Into<String>) -> Self {
         Error::ConfigError(msg.into())
     }
+
+    pub fn invalid_order(msg: impl Into<String>) -> Self {
+        Error::InvalidOrder(msg.into())
+    }
+
+    /// 交易所返回的错误，其他错误返回 `None`
+    pub fn exchange_error(&self) -> Option<&ExchangeError> {
+        match self {
+            Error::ExchangeError(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// 交易所错误的类别，调用方据此决定重试、退避还是放弃
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExchangeErrorKind {
+    /// 服务端暂时故障或超时，可以重试
+    Retryable,
+    /// 触发频率限制，需等待后再请求
+    RateLimited,
+    InsufficientBalance,
+    /// 参数不合法或不满足交易规则，重试无意义
+    InvalidParameter,
+    /// API Key、签名或权限错误
+    Auth,
+    /// 其他被交易所拒绝的请求
+    Rejected,
+}
+
+/// 交易所返回的错误：HTTP 状态码、交易所错误码和错误信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExchangeError {
+    pub kind: ExchangeErrorKind,
+    /// 请求未得到响应（连接失败、超时）时为空
+    pub status: Option<u16>,
+    pub code: Option<i64>,
+    pub message: String,
+    /// 频率限制时交易所要求的等待时间
+    pub retry_after: Option<Duration>,
+}
+
+impl ExchangeError {
+    /// 稍后重试可能成功
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.kind,
+            ExchangeErrorKind::Retryable | ExchangeErrorKind::RateLimited
+        )
+    }
+}
+
+impl fmt::Display for ExchangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.kind)?;
+        match (self.status, self.code) {
+            (Some(status), Some(code)) => write!(f, " (HTTP {}, code {})", status, code)?,
+            (Some(status), None) => write!(f, " (HTTP {})", status)?,
+            _ => {}
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl From<ExchangeError> for Error {
+    fn from(err: ExchangeError) -> Self {
+        Error::ExchangeError(err)
+    }
 }
 
 // 实现从字符串转换为Error的能力
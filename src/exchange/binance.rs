@@ -1,163 +1,532 @@
-use super::Exchange;
+use super::binance_request::BinanceRequest;
+use super::{Exchange, ServerClock};
 use async_trait::async_trait;
-use reqwest::Client;
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::{CONTENT_TYPE, RETRY_AFTER};
+use reqwest::{Client, Method, Response};
 use rust_decimal::Decimal;
-use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use crate::config::TradingConfig;
-use crate::crypto_tools;
-use crate::error::Error;
-use crate::types::{Order, OrderType, Position};
+use crate::error::{Error, ExchangeError, ExchangeErrorKind};
+use crate::notification::dingtalk::DingTalkBot;
+use crate::rate_limit::{binance_spot_limiter, RateLimiter};
+use crate::types::{
+    FeeAsset, Fill, Order, OrderKind, OrderRequest, OrderStatus, Position, PositionSide, Side,
+    Symbol, SymbolInfo, TimeInForce,
+};
+
+const TIME_PATH: &str = "/api/v3/time";
 
 pub struct BinanceExchange {
     client: Client,
     config: Arc<TradingConfig>,
     base_url: String,
+    // 交易对规则，首次下单时载入
+    symbol_info: Mutex<HashMap<Symbol, SymbolInfo>>,
+    rate_limiter: Arc<RateLimiter>,
+    clock: ServerClock,
 }
 
 impl BinanceExchange {
     pub fn new(config: Arc<TradingConfig>) -> Self {
         let client = Client::new();
+        let base_url = config.binance.endpoint.rest_url().to_string();
+        let clock = ServerClock::new(
+            format!("{}{}", base_url, TIME_PATH),
+            Duration::from_secs(config.binance.time_sync_interval_secs),
+            config.binance.max_clock_drift_ms,
+        );
         Self {
             client,
             config,
-            base_url: "https://api.binance.com".to_string(),
+            base_url,
+            symbol_info: Mutex::new(HashMap::new()),
+            rate_limiter: binance_spot_limiter(),
+            clock,
         }
     }
 
-    async fn sign_request(&self, params: &mut HashMap<String, String>) -> String {
-        params.insert(
-            "timestamp".to_string(),
-            chrono::Utc::now().timestamp_millis().to_string(),
+    /// 替换限速器，默认与进程内其他 Binance 适配器共用
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// 替换 REST 基础地址（测试网或本地 mock 服务）
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self.clock = self
+            .clock
+            .with_time_url(format!("{}{}", self.base_url, TIME_PATH));
+        self
+    }
+
+    /// 时钟偏差超限时通过钉钉告警
+    pub fn with_notifier(mut self, notifier: DingTalkBot) -> Self {
+        self.clock = self.clock.with_notifier(notifier);
+        self
+    }
+
+    /// 按交易对规则取整数量和价格，不合规的订单在本地拒绝
+    async fn normalize(&self, request: &OrderRequest) -> Result<OrderRequest, Error> {
+        let info = self.symbol_info(&request.symbol).await?;
+        // 市价单按最新价估算成交额
+        let reference_price = match (request.kind, info.min_notional) {
+            (OrderKind::Market, Some(_)) => Some(self.ticker_price(&request.symbol).await?),
+            _ => None,
+        };
+        info.normalize(request, reference_price)
+    }
+
+    async fn ticker_price(&self, symbol: &Symbol) -> Result<Decimal, Error> {
+        let ticker: BinanceTicker = self.send_public("/api/v3/ticker/price", symbol).await?;
+        Ok(ticker.price)
+    }
+
+    /// 以校准后的服务器时间签名，发送请求并解析 JSON 响应
+    async fn send_signed<T: DeserializeOwned>(&self, request: BinanceRequest) -> Result<T, Error> {
+        let (method, path) = (request.method.clone(), request.path);
+        let signed = request.sign(
+            &self.config.api_secret,
+            self.clock.now_ms().await,
+            self.config.binance.recv_window_ms,
         );
+        let url = match signed.query.is_empty() {
+            true => format!("{}{}", self.base_url, path),
+            false => format!("{}{}?{}", self.base_url, path, signed.query),
+        };
 
-        let query = params
-            .iter()
-            .map(|(k, v)| format!("{}={}", k, v))
-            .collect::<Vec<String>>()
-            .join("&");
+        let (weight, orders) = request_weight(&method, path);
+        self.rate_limiter.acquire(weight, orders).await;
+        let mut builder = self
+            .client
+            .request(method, &url)
+            .header("X-MBX-APIKEY", &self.config.api_key);
+        if !signed.body.is_empty() {
+            builder = builder
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(signed.body);
+        }
+        let sent = builder.send().await;
+        let result = read_response(path, sent, &self.rate_limiter).await;
+        // -1021：时间戳超出 recvWindow，下次请求前重新校准
+        if let Err(e) = &result {
+            if e.exchange_error().and_then(|e| e.code) == Some(-1021) {
+                self.clock.invalidate();
+            }
+        }
+        result
+    }
 
-        let signature = crypto_tools::hmac_sha256(&self.config.api_secret, &query);
-        format!("{}&signature={}", query, signature)
+    /// 发送按交易对查询的公开请求
+    async fn send_public<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        symbol: &Symbol,
+    ) -> Result<T, Error> {
+        let url = format!("{}{}", self.base_url, path);
+        let (weight, _) = request_weight(&Method::GET, path);
+        self.rate_limiter.acquire(weight, 0).await;
+        let sent = self
+            .client
+            .get(&url)
+            .query(&[("symbol", symbol.to_string())])
+            .send()
+            .await;
+        read_response(path, sent, &self.rate_limiter).await
+    }
+}
+
+/// 接口的请求权重和计入的下单数
+fn request_weight(method: &Method, path: &str) -> (u32, u32) {
+    match (method.as_str(), path) {
+        ("POST", "/api/v3/order") => (1, 1),
+        ("GET", "/api/v3/order") => (4, 0),
+        ("GET", "/api/v3/openOrders") => (6, 0),
+        ("GET", "/api/v3/myTrades") => (20, 0),
+        ("GET", "/api/v3/account") => (20, 0),
+        ("GET", "/api/v3/exchangeInfo") => (20, 0),
+        ("GET", "/api/v3/ticker/price") => (2, 0),
+        _ => (1, 0),
+    }
+}
+
+/// 检查 HTTP 状态并解析响应，错误响应转换为带类别的 `ExchangeError`
+///
+/// 连接失败或超时视为可重试；下单请求此时可能已被交易所受理。
+/// 响应头中的用量同步给限速器，429/418 时限速器按 `Retry-After` 暂停请求。
+pub(crate) async fn read_response<T: DeserializeOwned>(
+    path: &str,
+    sent: reqwest::Result<Response>,
+    rate_limiter: &RateLimiter,
+) -> Result<T, Error> {
+    let unreachable = |e: reqwest::Error| ExchangeError {
+        kind: ExchangeErrorKind::Retryable,
+        status: None,
+        code: None,
+        message: format!("{}: {}", path, e),
+        retry_after: None,
+    };
+
+    let response = sent.map_err(unreachable)?;
+    let status = response.status();
+    let retry_after = response
+        .headers()
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok())
+        .map(Duration::from_secs);
+    rate_limiter.update_from_headers(response.headers());
+    if matches!(status.as_u16(), 429 | 418) {
+        rate_limiter.back_off(retry_after);
+    }
+    let body = response.text().await.map_err(unreachable)?;
+
+    if !status.is_success() {
+        return Err(api_error(status.as_u16(), &body, retry_after).into());
+    }
+    serde_json::from_str(&body)
+        .map_err(|e| Error::ApiError(format!("unexpected response from {}: {}", path, e)))
+}
+
+/// 解析 Binance 错误响应体 `{"code": -2010, "msg": "..."}`
+fn api_error(status: u16, body: &str, retry_after: Option<Duration>) -> ExchangeError {
+    let (code, message) = match serde_json::from_str::<BinanceErrorBody>(body) {
+        Ok(error) => (Some(error.code), error.msg),
+        Err(_) => (None, body.to_string()),
+    };
+    ExchangeError {
+        kind: classify(status, code, &message),
+        status: Some(status),
+        code,
+        message,
+        retry_after,
+    }
+}
+
+/// 按 HTTP 状态码和 Binance 错误码归类
+fn classify(status: u16, code: Option<i64>, message: &str) -> ExchangeErrorKind {
+    match (status, code) {
+        // 418 表示频率超限后 IP 已被封禁
+        (429 | 418, _) | (_, Some(-1003 | -1015)) => ExchangeErrorKind::RateLimited,
+        (401, _) | (_, Some(-1002 | -1022 | -2014 | -2015)) => ExchangeErrorKind::Auth,
+        (_, Some(-2018 | -2019)) => ExchangeErrorKind::InsufficientBalance,
+        // -2010 是笼统的下单被拒，余额不足只体现在错误信息中
+        (_, Some(-2010)) if message.to_lowercase().contains("insufficient balance") => {
+            ExchangeErrorKind::InsufficientBalance
+        }
+        // -1021 为时间戳超出 recvWindow，校准时间后可重试
+        (_, Some(-1000 | -1001 | -1006 | -1007 | -1008 | -1021)) => ExchangeErrorKind::Retryable,
+        (_, Some(-1013)) | (_, Some(-1199..=-1100)) => ExchangeErrorKind::InvalidParameter,
+        // 合约接口的参数和下单规则错误
+        (_, Some(-4199..=-4000)) => ExchangeErrorKind::InvalidParameter,
+        (500..=599, _) => ExchangeErrorKind::Retryable,
+        _ => ExchangeErrorKind::Rejected,
     }
 }
 
 #[async_trait]
 impl Exchange for BinanceExchange {
-    async fn place_order(
+    async fn place_order(&self, request: &OrderRequest) -> Result<Order, Error> {
+        let request = &self.normalize(request).await?;
+        let binance_order: BinanceOrder = self.send_signed(order_params(request)?).await?;
+
+        let mut order = Order::new(
+            binance_order.order_id.to_string(),
+            request,
+            binance_order.status,
+        );
+        order.set_filled(
+            binance_order.executed_qty,
+            binance_order.cummulative_quote_qty,
+        );
+        order.fills = binance_order
+            .fills
+            .iter()
+            .map(|fill| fill.to_fill(&order.order_id, &request.symbol, request.side))
+            .collect();
+
+        Ok(order)
+    }
+
+    async fn cancel_order(&self, symbol: &Symbol, order_id: &str) -> Result<(), Error> {
+        let request = BinanceRequest::delete("/api/v3/order")
+            .param("symbol", symbol)
+            .param("orderId", order_id);
+
+        self.send_signed::<Value>(request).await?;
+        Ok(())
+    }
+
+    async fn get_order(&self, symbol: &Symbol, order_id: &str) -> Result<Order, Error> {
+        let request = BinanceRequest::get("/api/v3/order")
+            .param("symbol", symbol)
+            .param("orderId", order_id);
+
+        let detail: BinanceOrderDetail = self.send_signed(request).await?;
+        detail.to_order(symbol)
+    }
+
+    async fn find_order(
         &self,
-        symbol: &str,
-        order_type: OrderType,
-        size: Decimal,
-        price: Option<Decimal>,
-    ) -> Result<Order, Error> {
-        let mut params = HashMap::new();
-        params.insert("symbol".to_string(), symbol.to_string());
-        params.insert("side".to_string(), order_type.to_string());
-        params.insert("type".to_string(), "MARKET".to_string());
-        params.insert("quantity".to_string(), size.to_string());
-
-        if let Some(price) = price {
-            params.insert("price".to_string(), price.to_string());
+        symbol: &Symbol,
+        client_order_id: &str,
+    ) -> Result<Option<Order>, Error> {
+        let request = BinanceRequest::get("/api/v3/order")
+            .param("symbol", symbol)
+            .param("origClientOrderId", client_order_id);
+
+        match self.send_signed::<BinanceOrderDetail>(request).await {
+            Ok(detail) => detail.to_order(symbol).map(Some),
+            // -2013：订单不存在
+            Err(e) if e.exchange_error().and_then(|e| e.code) == Some(-2013) => Ok(None),
+            Err(e) => Err(e),
         }
+    }
 
-        let query = self.sign_request(&mut params).await;
-        let url = format!("{}/api/v3/order?{}", self.base_url, query);
+    async fn get_open_orders(&self, symbol: &Symbol) -> Result<Vec<Order>, Error> {
+        let request = BinanceRequest::get("/api/v3/openOrders").param("symbol", symbol);
 
-        let response = self
-            .client
-            .post(&url)
-            .send()
-            .await
-            .map_err(|e| Error::ApiError(e.to_string()))?;
+        let details: Vec<BinanceOrderDetail> = self.send_signed(request).await?;
+        details.iter().map(|d| d.to_order(symbol)).collect()
+    }
 
-        let binance_order: BinanceOrder = response
-            .json()
-            .await
-            .map_err(|e| Error::ApiError(e.to_string()))?;
-
-        Ok(Order {
-            order_id: binance_order.orderId.to_string(),
-            symbol: binance_order.symbol,
-            order_type,
-            size,
-            price,
-            status: binance_order.status,
-        })
+    async fn cancel_all(&self, symbol: &Symbol) -> Result<Vec<Order>, Error> {
+        let request = BinanceRequest::delete("/api/v3/openOrders").param("symbol", symbol);
+
+        let canceled: Vec<Value> = self.send_signed(request).await?;
+        // OCO 订单以订单列表的形式返回，不属于单个订单
+        canceled
+            .into_iter()
+            .filter(|entry| entry.get("orderId").is_some())
+            .map(|entry| serde_json::from_value::<BinanceOrderDetail>(entry)?.to_order(symbol))
+            .collect()
     }
 
-    async fn cancel_order(&self, order_id: &str) -> Result<(), Error> {
-        let mut params = HashMap::new();
-        params.insert("orderId".to_string(), order_id.to_string());
+    async fn get_my_trades(
+        &self,
+        symbol: &Symbol,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Fill>, Error> {
+        let request = BinanceRequest::get("/api/v3/myTrades")
+            .param("symbol", symbol)
+            .param_opt("startTime", since.map(|since| since.timestamp_millis()));
 
-        let query = self.sign_request(&mut params).await;
-        let url = format!("{}/api/v3/order?{}", self.base_url, query);
+        let trades: Vec<BinanceTrade> = self.send_signed(request).await?;
+        trades.iter().map(|t| t.to_fill(symbol)).collect()
+    }
 
-        self.client
-            .delete(&url)
-            .send()
-            .await
-            .map_err(|e| Error::ApiError(e.to_string()))?;
+    async fn get_exchange_info(&self, symbol: &Symbol) -> Result<SymbolInfo, Error> {
+        let payload: Value = self.send_public("/api/v3/exchangeInfo", symbol).await?;
 
-        Ok(())
+        let info = parse_symbol_info(symbol, &payload)?;
+        self.symbol_info
+            .lock()
+            .expect("symbol info lock poisoned")
+            .insert(symbol.clone(), info.clone());
+        Ok(info)
+    }
+
+    /// 读取下单取整时缓存的交易对规则，未缓存时从交易所载入
+    async fn symbol_info(&self, symbol: &Symbol) -> Result<SymbolInfo, Error> {
+        let cached = self
+            .symbol_info
+            .lock()
+            .expect("symbol info lock poisoned")
+            .get(symbol)
+            .cloned();
+        match cached {
+            Some(info) => Ok(info),
+            None => self.get_exchange_info(symbol).await,
+        }
     }
 
     async fn get_positions(&self) -> Result<Vec<Position>, Error> {
-        let mut params = HashMap::new();
-        let query = self.sign_request(&mut params).await;
-        let url = format!("{}/api/v3/account?{}", self.base_url, query);
+        let account: BinanceAccount = self
+            .send_signed(BinanceRequest::get("/api/v3/account"))
+            .await?;
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| Error::ApiError(e.to_string()))?;
+        Ok(positions_from_balances(
+            &self.config.symbols,
+            account.balances,
+        ))
+    }
 
-        let account: BinanceAccount = response
-            .json()
-            .await
-            .map_err(|e| Error::ApiError(e.to_string()))?;
+    async fn get_balance(&self) -> Result<HashMap<String, Decimal>, Error> {
+        let account: BinanceAccount = self
+            .send_signed(BinanceRequest::get("/api/v3/account"))
+            .await?;
 
         Ok(account
             .balances
             .into_iter()
-            .filter(|balance| balance.free > Decimal::ZERO)
-            .map(|balance| Position {
-                symbol: balance.asset,
-                size: balance.free,
-                entry_price: Decimal::ZERO,    // 需要从其他API获取
-                current_price: Decimal::ZERO,  // 需要从其他API获取
-                unrealized_pnl: Decimal::ZERO, // 需要计算
-            })
+            .map(|balance| (balance.asset, balance.free))
             .collect())
     }
+}
 
-    async fn get_balance(&self) -> Result<HashMap<String, Decimal>, Error> {
-        let mut params = HashMap::new();
-        let query = self.sign_request(&mut params).await;
-        let url = format!("{}/api/v3/account?{}", self.base_url, query);
+pub(super) fn side_name(side: Side) -> &'static str {
+    match side {
+        Side::Buy => "BUY",
+        Side::Sell => "SELL",
+    }
+}
 
-        let response = self
-            .client
-            .get(&url)
-            .send()
-            .await
-            .map_err(|e| Error::ApiError(e.to_string()))?;
+fn kind_name(kind: OrderKind) -> &'static str {
+    match kind {
+        OrderKind::Market => "MARKET",
+        OrderKind::Limit => "LIMIT",
+        OrderKind::StopLoss => "STOP_LOSS",
+        OrderKind::StopLossLimit => "STOP_LOSS_LIMIT",
+        OrderKind::TakeProfit => "TAKE_PROFIT",
+        OrderKind::TakeProfitLimit => "TAKE_PROFIT_LIMIT",
+        OrderKind::LimitMaker => "LIMIT_MAKER",
+    }
+}
 
-        let account: BinanceAccount = response
-            .json()
-            .await
-            .map_err(|e| Error::ApiError(e.to_string()))?;
+/// 按 Binance 返回的名称反查枚举取值
+pub(super) fn parse_name<T: Copy>(
+    values: &[T],
+    name_of: impl Fn(T) -> String,
+    field: &str,
+    name: &str,
+) -> Result<T, Error> {
+    values
+        .iter()
+        .copied()
+        .find(|value| name_of(*value) == name)
+        .ok_or_else(|| Error::ParseError(format!("unknown order {} {:?}", field, name)))
+}
 
-        Ok(account
-            .balances
-            .into_iter()
-            .map(|balance| (balance.asset, balance.free))
-            .collect())
+/// 将下单请求映射为 `/api/v3/order` 的参数
+///
+/// 限价类订单带 `price`，止损止盈类带 `stopPrice`；
+/// `timeInForce` 只用于 LIMIT、STOP_LOSS_LIMIT 和 TAKE_PROFIT_LIMIT，未指定时为 GTC。
+fn order_params(request: &OrderRequest) -> Result<BinanceRequest, Error> {
+    request.validate()?;
+    if request.has_futures_options() {
+        return Err(Error::invalid_order(
+            "spot orders do not take position side, reduce only or close position",
+        ));
     }
+
+    Ok(BinanceRequest::post("/api/v3/order")
+        .param("symbol", &request.symbol)
+        .param("side", side_name(request.side))
+        .param("type", kind_name(request.kind))
+        .param("quantity", request.size)
+        // 返回逐笔成交，供持仓账本记账
+        .param("newOrderRespType", "FULL")
+        .param_opt("price", request.price)
+        .param_opt("stopPrice", request.trigger_price)
+        .param_opt("timeInForce", request.effective_time_in_force())
+        .param_opt("newClientOrderId", request.client_order_id.as_ref())
+        .in_body())
+}
+
+/// 从 `/api/v3/exchangeInfo` 的响应中取出交易对的状态、订单类型和过滤器
+///
+/// 取值为 0 的过滤器字段表示不限制。
+fn parse_symbol_info(symbol: &Symbol, payload: &Value) -> Result<SymbolInfo, Error> {
+    let entry = symbol_entry(symbol, payload)?;
+    let filter = |filter_type: &str, field: &str| filter_value(entry, filter_type, field);
+
+    let order_kinds = entry["orderTypes"]
+        .as_array()
+        .map(|types| {
+            OrderKind::ALL
+                .into_iter()
+                .filter(|kind| types.iter().any(|t| t == kind_name(*kind)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(SymbolInfo {
+        symbol: symbol.clone(),
+        trading: entry["status"] == "TRADING",
+        tick_size: filter("PRICE_FILTER", "tickSize")?,
+        step_size: filter("LOT_SIZE", "stepSize")?,
+        min_size: filter("LOT_SIZE", "minQty")?,
+        max_size: filter("LOT_SIZE", "maxQty")?,
+        // 新交易对使用 NOTIONAL，旧交易对仍是 MIN_NOTIONAL
+        min_notional: match filter("NOTIONAL", "minNotional")? {
+            Some(value) => Some(value),
+            None => filter("MIN_NOTIONAL", "minNotional")?,
+        },
+        order_kinds,
+    })
+}
+
+/// 在 `exchangeInfo` 的响应中查找交易对
+pub(super) fn symbol_entry<'a>(symbol: &Symbol, payload: &'a Value) -> Result<&'a Value, Error> {
+    let name = symbol.to_string();
+    payload["symbols"]
+        .as_array()
+        .and_then(|symbols| symbols.iter().find(|s| s["symbol"] == name.as_str()))
+        .ok_or_else(|| Error::ApiError(format!("{} not found in exchange info", name)))
+}
+
+/// 读取交易对过滤器的字段，缺失或为 0 时返回 `None`
+pub(super) fn filter_value(
+    entry: &Value,
+    filter_type: &str,
+    field: &str,
+) -> Result<Option<Decimal>, Error> {
+    let value = entry["filters"]
+        .as_array()
+        .and_then(|filters| filters.iter().find(|f| f["filterType"] == filter_type))
+        .and_then(|f| f[field].as_str());
+    match value {
+        Some(raw) => {
+            let value = Decimal::from_str(raw).map_err(|e| {
+                Error::ParseError(format!("{}.{} {:?}: {}", filter_type, field, raw, e))
+            })?;
+            Ok((value > Decimal::ZERO).then_some(value))
+        }
+        None => Ok(None),
+    }
+}
+
+/// 现货账户按资产返回余额，换算为以该资产为基础资产的交易对持仓
+///
+/// 不属于任何交易对的资产（如计价资产 USDT）不视为持仓。挂着止损等卖单时
+/// 余额被冻结在 `locked` 中，仍属于持仓。
+fn positions_from_balances(symbols: &[Symbol], balances: Vec<BinanceBalance>) -> Vec<Position> {
+    balances
+        .into_iter()
+        .filter(|balance| balance.total() > Decimal::ZERO)
+        .filter_map(|balance| {
+            let symbol = symbols.iter().find(|s| s.base() == balance.asset)?;
+            Some(Position {
+                symbol: symbol.clone(),
+                size: balance.total(),
+                // 现货账户不提供成本价，成本和盈亏以 Portfolio 账本为准
+                entry_price: Decimal::ZERO,
+                current_price: Decimal::ZERO,
+                unrealized_pnl: Decimal::ZERO,
+                position_side: PositionSide::Both,
+                liquidation_price: None,
+            })
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct BinanceErrorBody {
+    code: i64,
+    msg: String,
+}
+
+#[derive(Deserialize)]
+pub(super) struct BinanceTicker {
+    pub(super) price: Decimal,
 }
 
 #[derive(Deserialize)]
@@ -172,9 +541,549 @@ struct BinanceBalance {
     locked: Decimal,
 }
 
+impl BinanceBalance {
+    /// 全部持有量，包括挂单冻结的部分
+    fn total(&self) -> Decimal {
+        self.free + self.locked
+    }
+}
+
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct BinanceOrder {
-    orderId: u64,
-    symbol: String,
-    status: String,
+    order_id: u64,
+    status: OrderStatus,
+    executed_qty: Decimal,
+    cummulative_quote_qty: Decimal,
+    #[serde(default)]
+    fills: Vec<BinanceFill>,
+}
+
+/// `GET /api/v3/order` 的响应，不含逐笔成交
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceOrderDetail {
+    order_id: u64,
+    #[serde(default)]
+    client_order_id: Option<String>,
+    side: String,
+    #[serde(rename = "type")]
+    order_type: String,
+    orig_qty: Decimal,
+    price: Decimal,
+    #[serde(default)]
+    stop_price: Decimal,
+    time_in_force: String,
+    status: OrderStatus,
+    executed_qty: Decimal,
+    cummulative_quote_qty: Decimal,
+}
+
+impl BinanceOrderDetail {
+    fn to_order(&self, symbol: &Symbol) -> Result<Order, Error> {
+        let kind = parse_name(
+            &OrderKind::ALL,
+            |k| kind_name(k).to_string(),
+            "type",
+            &self.order_type,
+        )?;
+        let side = parse_name(
+            &[Side::Buy, Side::Sell],
+            |s| side_name(s).to_string(),
+            "side",
+            &self.side,
+        )?;
+        let time_in_force = if kind.accepts_time_in_force() {
+            Some(parse_name(
+                &[TimeInForce::Gtc, TimeInForce::Ioc, TimeInForce::Fok],
+                |tif| tif.to_string(),
+                "timeInForce",
+                &self.time_in_force,
+            )?)
+        } else {
+            None
+        };
+        // 未使用的价格字段以 0 返回
+        let positive = |value: Decimal| (value > Decimal::ZERO).then_some(value);
+
+        let mut order = Order {
+            order_id: self.order_id.to_string(),
+            client_order_id: self.client_order_id.clone(),
+            symbol: symbol.clone(),
+            side,
+            kind,
+            size: self.orig_qty,
+            price: positive(self.price).filter(|_| kind.has_limit_price()),
+            trigger_price: positive(self.stop_price).filter(|_| kind.has_trigger_price()),
+            time_in_force,
+            status: self.status,
+            filled_size: Decimal::ZERO,
+            average_price: None,
+            fills: Vec::new(),
+            reduce_only: false,
+            position_side: PositionSide::Both,
+        };
+        order.set_filled(self.executed_qty, self.cummulative_quote_qty);
+        Ok(order)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceFill {
+    price: Decimal,
+    qty: Decimal,
+    commission: Decimal,
+    commission_asset: String,
+}
+
+impl BinanceFill {
+    fn to_fill(&self, order_id: &str, symbol: &Symbol, side: Side) -> Fill {
+        Fill {
+            order_id: order_id.to_string(),
+            symbol: symbol.clone(),
+            side,
+            price: self.price,
+            size: self.qty,
+            fee: self.commission,
+            fee_asset: FeeAsset::of(symbol, &self.commission_asset),
+            position_side: PositionSide::Both,
+            timestamp: Utc::now(),
+        }
+    }
+}
+
+/// `GET /api/v3/myTrades` 返回的成交
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceTrade {
+    order_id: u64,
+    price: Decimal,
+    qty: Decimal,
+    commission: Decimal,
+    commission_asset: String,
+    time: i64,
+    is_buyer: bool,
+}
+
+impl BinanceTrade {
+    fn to_fill(&self, symbol: &Symbol) -> Result<Fill, Error> {
+        let timestamp = Utc
+            .timestamp_millis_opt(self.time)
+            .single()
+            .ok_or_else(|| Error::ParseError(format!("invalid trade time {}", self.time)))?;
+
+        Ok(Fill {
+            order_id: self.order_id.to_string(),
+            symbol: symbol.clone(),
+            side: if self.is_buyer { Side::Buy } else { Side::Sell },
+            price: self.price,
+            size: self.qty,
+            fee: self.commission,
+            fee_asset: FeeAsset::of(symbol, &self.commission_asset),
+            position_side: PositionSide::Both,
+            timestamp,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+    use crate::types::test_symbol;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+    use wiremock::matchers::{body_string_contains, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn exchange(server: &MockServer) -> BinanceExchange {
+        BinanceExchange::new(Arc::new(test_config()))
+            .with_base_url(server.uri())
+            .with_rate_limiter(Arc::new(RateLimiter::binance_spot()))
+    }
+
+    #[test]
+    fn test_order_params() -> Result<(), Error> {
+        let btc = test_symbol("BTCUSDT");
+
+        let market = order_params(&OrderRequest::market(&btc, Side::Buy, dec!(0.5)))?;
+        assert_eq!(&market["side"], "BUY");
+        assert_eq!(&market["type"], "MARKET");
+        assert_eq!(&market["quantity"], "0.5");
+        assert!(!market.contains_key("price"));
+        assert!(!market.contains_key("timeInForce"));
+
+        // 止损单以 stopPrice 下单，不带限价
+        let stop = order_params(&OrderRequest::stop_loss(
+            &btc,
+            Side::Sell,
+            dec!(1),
+            dec!(90),
+        ))?;
+        assert_eq!(&stop["side"], "SELL");
+        assert_eq!(&stop["type"], "STOP_LOSS");
+        assert_eq!(&stop["stopPrice"], "90");
+        assert!(!stop.contains_key("price"));
+
+        let limit = order_params(&OrderRequest::limit(&btc, Side::Buy, dec!(1), dec!(95)))?;
+        assert_eq!(&limit["type"], "LIMIT");
+        assert_eq!(&limit["price"], "95");
+        assert_eq!(&limit["timeInForce"], "GTC");
+
+        let stop_limit = order_params(
+            &OrderRequest::take_profit_limit(&btc, Side::Sell, dec!(1), dec!(120), dec!(119))
+                .with_time_in_force(TimeInForce::Fok),
+        )?;
+        assert_eq!(&stop_limit["type"], "TAKE_PROFIT_LIMIT");
+        assert_eq!(&stop_limit["stopPrice"], "120");
+        assert_eq!(&stop_limit["price"], "119");
+        assert_eq!(&stop_limit["timeInForce"], "FOK");
+
+        let maker = order_params(&OrderRequest::limit_maker(
+            &btc,
+            Side::Sell,
+            dec!(1),
+            dec!(120),
+        ))?;
+        assert_eq!(&maker["type"], "LIMIT_MAKER");
+        assert!(!maker.contains_key("timeInForce"));
+        assert!(!maker.contains_key("newClientOrderId"));
+
+        let tagged = order_params(
+            &OrderRequest::market(&btc, Side::Buy, dec!(1)).with_client_order_id("rqt-1-1"),
+        )?;
+        assert_eq!(&tagged["newClientOrderId"], "rqt-1-1");
+
+        assert!(matches!(
+            order_params(
+                &OrderRequest::market(&btc, Side::Buy, dec!(1))
+                    .with_time_in_force(TimeInForce::Ioc)
+            ),
+            Err(Error::InvalidOrder(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_detail_to_order() -> Result<(), Error> {
+        let detail: BinanceOrderDetail = serde_json::from_value(serde_json::json!({
+            "symbol": "BTCUSDT",
+            "orderId": 28,
+            "clientOrderId": "6gCrw2kRUAF9CvJDGP16IP",
+            "price": "100.00000000",
+            "origQty": "3.00000000",
+            "executedQty": "1.00000000",
+            "cummulativeQuoteQty": "99.00000000",
+            "status": "PARTIALLY_FILLED",
+            "timeInForce": "GTC",
+            "type": "LIMIT",
+            "side": "BUY",
+            "stopPrice": "0.00000000"
+        }))?;
+
+        let order = detail.to_order(&test_symbol("BTCUSDT"))?;
+        assert_eq!(order.order_id, "28");
+        assert_eq!(
+            order.client_order_id.as_deref(),
+            Some("6gCrw2kRUAF9CvJDGP16IP")
+        );
+        assert_eq!(order.side, Side::Buy);
+        assert_eq!(order.kind, OrderKind::Limit);
+        assert_eq!(order.price, Some(dec!(100)));
+        assert_eq!(order.trigger_price, None);
+        assert_eq!(order.time_in_force, Some(TimeInForce::Gtc));
+        assert_eq!(order.status, OrderStatus::PartiallyFilled);
+        assert_eq!(order.filled_size, dec!(1));
+        assert_eq!(order.average_price, Some(dec!(99)));
+        assert_eq!(order.remaining_size(), dec!(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_symbol_info() -> Result<(), Error> {
+        let payload = serde_json::json!({
+            "symbols": [{
+                "symbol": "BTCUSDT",
+                "status": "TRADING",
+                "baseAsset": "BTC",
+                "quoteAsset": "USDT",
+                "orderTypes": ["LIMIT", "LIMIT_MAKER", "MARKET", "STOP_LOSS_LIMIT", "TAKE_PROFIT_LIMIT"],
+                "filters": [
+                    {"filterType": "PRICE_FILTER", "minPrice": "0.01000000", "maxPrice": "1000000.00000000", "tickSize": "0.01000000"},
+                    {"filterType": "LOT_SIZE", "minQty": "0.00001000", "maxQty": "9000.00000000", "stepSize": "0.00001000"},
+                    {"filterType": "NOTIONAL", "minNotional": "5.00000000", "applyMinToMarket": true, "maxNotional": "9000000.00000000"}
+                ]
+            }]
+        });
+
+        let info = parse_symbol_info(&test_symbol("BTCUSDT"), &payload)?;
+        assert!(info.trading);
+        assert_eq!(info.tick_size, Some(dec!(0.01)));
+        assert_eq!(info.step_size, Some(dec!(0.00001)));
+        assert_eq!(info.min_size, Some(dec!(0.00001)));
+        assert_eq!(info.max_size, Some(dec!(9000)));
+        assert_eq!(info.min_notional, Some(dec!(5)));
+        // 只包含交易对开放的订单类型
+        assert!(!info.order_kinds.contains(&OrderKind::StopLoss));
+        assert!(info.order_kinds.contains(&OrderKind::StopLossLimit));
+
+        assert!(parse_symbol_info(&test_symbol("ETHUSDT"), &payload).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_trade_to_fill() -> Result<(), Error> {
+        let trade: BinanceTrade = serde_json::from_value(serde_json::json!({
+            "symbol": "BTCUSDT",
+            "id": 28457,
+            "orderId": 100234,
+            "orderListId": -1,
+            "price": "4.00000100",
+            "qty": "12.00000000",
+            "quoteQty": "48.000012",
+            "commission": "0.01200000",
+            "commissionAsset": "BTC",
+            "time": 1499865549590i64,
+            "isBuyer": true,
+            "isMaker": false,
+            "isBestMatch": true
+        }))?;
+
+        let fill = trade.to_fill(&test_symbol("BTCUSDT"))?;
+        assert_eq!(fill.order_id, "100234");
+        assert_eq!(fill.side, Side::Buy);
+        assert_eq!(fill.size, dec!(12));
+        // 以 BTC 收取的手续费从到账数量中扣除
+        assert_eq!(fill.fee, dec!(0.012));
+        assert_eq!(fill.fee_asset, FeeAsset::Base);
+        assert_eq!(fill.net_size(), dec!(11.988));
+        assert_eq!(fill.quote_fee(), dec!(0.048000012));
+        assert_eq!(fill.timestamp.timestamp_millis(), 1499865549590);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_positions_from_balances() {
+        let balance = |asset: &str, free, locked| BinanceBalance {
+            asset: asset.to_string(),
+            free,
+            locked,
+        };
+        let symbols = [test_symbol("BTCUSDT"), test_symbol("ETHUSDT")];
+
+        let positions = positions_from_balances(
+            &symbols,
+            vec![
+                balance("BTC", dec!(0.5), dec!(0.25)),
+                balance("ETH", dec!(0), dec!(0)),
+                balance("USDT", dec!(1000), dec!(0)),
+                balance("BNB", dec!(3), dec!(0)),
+            ],
+        );
+
+        // 余额为零、计价资产和未配置的资产都不算持仓
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].symbol, test_symbol("BTCUSDT"));
+        // 被止损单冻结的部分同样计入
+        assert_eq!(positions[0].size, dec!(0.75));
+
+        // 全部冻结时仍然是持仓
+        let positions = positions_from_balances(&symbols, vec![balance("ETH", dec!(0), dec!(2))]);
+        assert_eq!(positions[0].size, dec!(2));
+    }
+
+    #[tokio::test]
+    async fn test_place_order_applies_symbol_filters() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        // 交易对规则只载入一次
+        Mock::given(method("GET"))
+            .and(path("/api/v3/exchangeInfo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "symbols": [{
+                    "symbol": "BTCUSDT",
+                    "status": "TRADING",
+                    "orderTypes": ["LIMIT", "MARKET"],
+                    "filters": [
+                        {"filterType": "PRICE_FILTER", "tickSize": "0.01000000"},
+                        {"filterType": "LOT_SIZE", "minQty": "0.00100000", "maxQty": "9000.00000000", "stepSize": "0.00100000"},
+                        {"filterType": "NOTIONAL", "minNotional": "10.00000000"}
+                    ]
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/ticker/price"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"symbol": "BTCUSDT", "price": "4000.00000000"})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order"))
+            .and(header("X-MBX-APIKEY", "your_api_key_here"))
+            .and(header("Content-Type", "application/x-www-form-urlencoded"))
+            // 下单参数按插入顺序放在请求体中
+            .and(body_string_contains(
+                "symbol=BTCUSDT&side=BUY&type=LIMIT&quantity=0.123&newOrderRespType=FULL\
+                 &price=3999.99&timeInForce=GTC&newClientOrderId=rqt-1-1&recvWindow=5000",
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("X-MBX-USED-WEIGHT-1M", "25")
+                    .insert_header("X-MBX-ORDER-COUNT-10S", "1")
+                    .set_body_json(json!({
+                        "orderId": 7,
+                        "status": "NEW",
+                        "executedQty": "0.00000000",
+                        "cummulativeQuoteQty": "0.00000000",
+                        "fills": []
+                    })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let exchange = exchange(&server);
+        let btc = test_symbol("BTCUSDT");
+
+        let order = exchange
+            .place_order(
+                &OrderRequest::limit(&btc, Side::Buy, dec!(0.12345678), dec!(3999.987))
+                    .with_client_order_id("rqt-1-1"),
+            )
+            .await?;
+        assert_eq!(order.size, dec!(0.123));
+        assert_eq!(order.price, Some(dec!(3999.99)));
+        // 用量以响应头为准
+        let usage = exchange.rate_limiter.usage();
+        assert_eq!((usage[0].used, usage[1].used), (25, 1));
+
+        // 0.002 * 4000 = 8 低于最小成交额，不发出下单请求
+        let rejected = exchange
+            .place_order(&OrderRequest::market(&btc, Side::Buy, dec!(0.002)))
+            .await;
+        assert!(matches!(rejected, Err(Error::InvalidOrder(_))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_classify_error() {
+        let kind = |status, code, msg| classify(status, code, msg);
+        assert_eq!(
+            kind(429, Some(-1003), "Too many requests"),
+            ExchangeErrorKind::RateLimited
+        );
+        assert_eq!(kind(418, None, ""), ExchangeErrorKind::RateLimited);
+        assert_eq!(
+            kind(
+                401,
+                Some(-2015),
+                "Invalid API-key, IP, or permissions for action."
+            ),
+            ExchangeErrorKind::Auth
+        );
+        assert_eq!(
+            kind(400, Some(-1022), "Signature for this request is not valid."),
+            ExchangeErrorKind::Auth
+        );
+        assert_eq!(
+            kind(
+                400,
+                Some(-2010),
+                "Account has insufficient balance for requested action."
+            ),
+            ExchangeErrorKind::InsufficientBalance
+        );
+        assert_eq!(
+            kind(400, Some(-2010), "Order would immediately match and take."),
+            ExchangeErrorKind::Rejected
+        );
+        assert_eq!(
+            kind(400, Some(-1013), "Filter failure: LOT_SIZE"),
+            ExchangeErrorKind::InvalidParameter
+        );
+        assert_eq!(
+            kind(400, Some(-1121), "Invalid symbol."),
+            ExchangeErrorKind::InvalidParameter
+        );
+        assert_eq!(
+            kind(503, None, "Service Unavailable"),
+            ExchangeErrorKind::Retryable
+        );
+        assert_eq!(
+            kind(
+                400,
+                Some(-1021),
+                "Timestamp for this request is outside of the recvWindow."
+            ),
+            ExchangeErrorKind::Retryable
+        );
+        assert_eq!(
+            kind(400, Some(-2013), "Order does not exist."),
+            ExchangeErrorKind::Rejected
+        );
+        assert_eq!(
+            kind(
+                400,
+                Some(-4164),
+                "Order's notional must be no smaller than 5.0"
+            ),
+            ExchangeErrorKind::InvalidParameter
+        );
+        assert_eq!(
+            kind(400, Some(-2019), "Margin is insufficient."),
+            ExchangeErrorKind::InsufficientBalance
+        );
+    }
+
+    #[tokio::test]
+    async fn test_error_responses() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/account"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("Retry-After", "7")
+                    .set_body_json(json!({"code": -1003, "msg": "Too many requests."})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/v3/order"))
+            .respond_with(ResponseTemplate::new(502).set_body_string("Bad Gateway"))
+            .mount(&server)
+            .await;
+
+        let exchange = exchange(&server);
+
+        // 非 JSON 的网关错误保留原始响应
+        let err = exchange
+            .cancel_order(&test_symbol("BTCUSDT"), "1")
+            .await
+            .unwrap_err();
+        let gateway = err.exchange_error().expect("exchange error");
+        assert_eq!(gateway.kind, ExchangeErrorKind::Retryable);
+        assert_eq!(gateway.code, None);
+        assert_eq!(gateway.message, "Bad Gateway");
+
+        // 429 之后限速器暂停请求，放在最后避免测试等待
+        let err = exchange.get_balance().await.unwrap_err();
+        let limited = err.exchange_error().expect("exchange error");
+        assert_eq!(limited.kind, ExchangeErrorKind::RateLimited);
+        assert_eq!(limited.status, Some(429));
+        assert_eq!(limited.code, Some(-1003));
+        assert_eq!(limited.retry_after, Some(Duration::from_secs(7)));
+        assert!(limited.is_retryable());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,1080 @@
+// src/exchange/binance_futures.rs
+//! Binance U 本位合约（`/fapi`）：杠杆、保证金模式、持仓模式，以及做空和只减仓订单
+
+use super::binance::{
+    filter_value, parse_name, read_response, side_name, symbol_entry, BinanceTicker,
+};
+use super::binance_request::BinanceRequest;
+use super::{Exchange, ServerClock};
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Client, Method};
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::config::{MarginType, PositionMode, TradingConfig};
+use crate::error::Error;
+use crate::notification::dingtalk::DingTalkBot;
+use crate::rate_limit::{binance_futures_limiter, RateLimiter};
+use crate::types::{
+    FeeAsset, Fill, Order, OrderKind, OrderRequest, OrderStatus, Position, PositionSide, Side,
+    Symbol, SymbolInfo, TimeInForce,
+};
+
+pub const BINANCE_FUTURES_API_URL: &str = "https://fapi.binance.com";
+pub const BINANCE_FUTURES_TESTNET_API_URL: &str = "https://testnet.binancefuture.com";
+
+const TIME_PATH: &str = "/fapi/v1/time";
+
+// 批量撤单每次最多 10 个订单
+const BATCH_CANCEL_SIZE: usize = 10;
+
+/// 已写入交易所的账户设置
+#[derive(Debug, Default)]
+struct Prepared {
+    position_mode: bool,
+    symbols: HashSet<Symbol>,
+}
+
+/// Binance U 本位永续合约
+///
+/// 配置中的杠杆、保证金模式和持仓模式在首次交易某个交易对前写入交易所。
+/// 持仓数量带符号，空头为负数。
+pub struct BinanceFuturesExchange {
+    client: Client,
+    config: Arc<TradingConfig>,
+    base_url: String,
+    // 交易对规则，首次下单时载入
+    symbol_info: Mutex<HashMap<Symbol, SymbolInfo>>,
+    prepared: Mutex<Prepared>,
+    rate_limiter: Arc<RateLimiter>,
+    clock: ServerClock,
+}
+
+impl BinanceFuturesExchange {
+    pub fn new(config: Arc<TradingConfig>) -> Self {
+        let client = Client::new();
+        let base_url = config.binance.endpoint.futures_rest_url().to_string();
+        let clock = ServerClock::new(
+            format!("{}{}", base_url, TIME_PATH),
+            Duration::from_secs(config.binance.time_sync_interval_secs),
+            config.binance.max_clock_drift_ms,
+        );
+        Self {
+            client,
+            config,
+            base_url,
+            symbol_info: Mutex::new(HashMap::new()),
+            prepared: Mutex::new(Prepared::default()),
+            rate_limiter: binance_futures_limiter(),
+            clock,
+        }
+    }
+
+    /// 替换限速器，默认与进程内其他合约适配器共用
+    pub fn with_rate_limiter(mut self, rate_limiter: Arc<RateLimiter>) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// 替换 REST 基础地址（测试网或本地 mock 服务）
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self.clock = self
+            .clock
+            .with_time_url(format!("{}{}", self.base_url, TIME_PATH));
+        self
+    }
+
+    /// 时钟偏差超限时通过钉钉告警
+    pub fn with_notifier(mut self, notifier: DingTalkBot) -> Self {
+        self.clock = self.clock.with_notifier(notifier);
+        self
+    }
+
+    /// 设置交易对的杠杆倍数，返回交易所实际生效的倍数
+    pub async fn set_leverage(&self, symbol: &Symbol, leverage: u32) -> Result<u32, Error> {
+        let request = BinanceRequest::post("/fapi/v1/leverage")
+            .param("symbol", symbol)
+            .param("leverage", leverage)
+            .in_body();
+
+        let response: LeverageResponse = self.send_signed(request).await?;
+        Ok(response.leverage)
+    }
+
+    /// 设置交易对的保证金模式，已是该模式时直接返回
+    pub async fn set_margin_type(
+        &self,
+        symbol: &Symbol,
+        margin_type: MarginType,
+    ) -> Result<(), Error> {
+        let request = BinanceRequest::post("/fapi/v1/marginType")
+            .param("symbol", symbol)
+            .param("marginType", margin_type)
+            .in_body();
+
+        match self.send_signed::<Value>(request).await {
+            // -4046：无需变更保证金模式
+            Err(e) if has_code(&e, -4046) => Ok(()),
+            result => result.map(|_| ()),
+        }
+    }
+
+    /// 设置账户的持仓模式，已是该模式时直接返回；有持仓或挂单时交易所会拒绝
+    pub async fn set_position_mode(&self, mode: PositionMode) -> Result<(), Error> {
+        let request = BinanceRequest::post("/fapi/v1/positionSide/dual")
+            .param("dualSidePosition", mode == PositionMode::Hedge)
+            .in_body();
+
+        match self.send_signed::<Value>(request).await {
+            // -4059：无需变更持仓模式
+            Err(e) if has_code(&e, -4059) => Ok(()),
+            result => result.map(|_| ()),
+        }
+    }
+
+    /// 账户当前的持仓模式
+    pub async fn position_mode(&self) -> Result<PositionMode, Error> {
+        let response: DualSidePosition = self
+            .send_signed(BinanceRequest::get("/fapi/v1/positionSide/dual"))
+            .await?;
+        Ok(match response.dual_side_position {
+            true => PositionMode::Hedge,
+            false => PositionMode::OneWay,
+        })
+    }
+
+    /// 首次交易交易对前写入配置的持仓模式、保证金模式和杠杆
+    async fn prepare(&self, symbol: &Symbol) -> Result<(), Error> {
+        let (position_mode_ready, symbol_ready) = {
+            let prepared = self
+                .prepared
+                .lock()
+                .expect("futures settings lock poisoned");
+            (prepared.position_mode, prepared.symbols.contains(symbol))
+        };
+        let settings = &self.config.binance.futures;
+
+        if !position_mode_ready {
+            self.set_position_mode(settings.position_mode).await?;
+            self.prepared
+                .lock()
+                .expect("futures settings lock poisoned")
+                .position_mode = true;
+        }
+        if !symbol_ready {
+            self.set_margin_type(symbol, settings.margin_type).await?;
+            let leverage = self.set_leverage(symbol, settings.leverage).await?;
+            info!(
+                "{} futures settings: {}x leverage, {} margin, {} position mode",
+                symbol, leverage, settings.margin_type, settings.position_mode
+            );
+            self.prepared
+                .lock()
+                .expect("futures settings lock poisoned")
+                .symbols
+                .insert(symbol.clone());
+        }
+        Ok(())
+    }
+
+    /// 按交易对规则取整数量和价格，不合规的订单在本地拒绝
+    async fn normalize(&self, request: &OrderRequest) -> Result<OrderRequest, Error> {
+        let mut info = self.symbol_info(&request.symbol).await?;
+        // 只减仓订单不受最小名义价值限制
+        if request.reduce_only {
+            info.min_notional = None;
+        }
+        // 市价单按最新价估算名义价值
+        let reference_price = match (request.kind, info.min_notional) {
+            (OrderKind::Market, Some(_)) => Some(self.ticker_price(&request.symbol).await?),
+            _ => None,
+        };
+        info.normalize(request, reference_price)
+    }
+
+    async fn ticker_price(&self, symbol: &Symbol) -> Result<Decimal, Error> {
+        let ticker: BinanceTicker = self
+            .send_public("/fapi/v1/ticker/price", Some(symbol))
+            .await?;
+        Ok(ticker.price)
+    }
+
+    /// 以校准后的服务器时间签名，发送请求并解析 JSON 响应
+    async fn send_signed<T: DeserializeOwned>(&self, request: BinanceRequest) -> Result<T, Error> {
+        let (method, path) = (request.method.clone(), request.path);
+        let signed = request.sign(
+            &self.config.api_secret,
+            self.clock.now_ms().await,
+            self.config.binance.recv_window_ms,
+        );
+        let url = match signed.query.is_empty() {
+            true => format!("{}{}", self.base_url, path),
+            false => format!("{}{}?{}", self.base_url, path, signed.query),
+        };
+
+        let (weight, orders) = request_weight(&method, path);
+        self.rate_limiter.acquire(weight, orders).await;
+        let mut builder = self
+            .client
+            .request(method, &url)
+            .header("X-MBX-APIKEY", &self.config.api_key);
+        if !signed.body.is_empty() {
+            builder = builder
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(signed.body);
+        }
+        let sent = builder.send().await;
+        let result = read_response(path, sent, &self.rate_limiter).await;
+        // -1021：时间戳超出 recvWindow，下次请求前重新校准
+        if let Err(e) = &result {
+            if has_code(e, -1021) {
+                self.clock.invalidate();
+            }
+        }
+        result
+    }
+
+    /// 发送公开请求，`symbol` 为空时查询全部交易对
+    async fn send_public<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        symbol: Option<&Symbol>,
+    ) -> Result<T, Error> {
+        let url = format!("{}{}", self.base_url, path);
+        let (weight, _) = request_weight(&Method::GET, path);
+        self.rate_limiter.acquire(weight, 0).await;
+        let mut builder = self.client.get(&url);
+        if let Some(symbol) = symbol {
+            builder = builder.query(&[("symbol", symbol.to_string())]);
+        }
+        read_response(path, builder.send().await, &self.rate_limiter).await
+    }
+}
+
+impl BinanceFuturesExchange {
+    /// 某个订单的逐笔成交
+    async fn order_trades(&self, symbol: &Symbol, order_id: &str) -> Result<Vec<Fill>, Error> {
+        let request = BinanceRequest::get("/fapi/v1/userTrades")
+            .param("symbol", symbol)
+            .param("orderId", order_id);
+
+        let trades: Vec<FuturesTrade> = self.send_signed(request).await?;
+        trades.iter().map(|t| t.to_fill(symbol)).collect()
+    }
+}
+
+fn has_code(error: &Error, code: i64) -> bool {
+    error.exchange_error().and_then(|e| e.code) == Some(code)
+}
+
+/// 接口的请求权重和计入的下单数
+fn request_weight(method: &Method, path: &str) -> (u32, u32) {
+    match (method.as_str(), path) {
+        ("POST", "/fapi/v1/order") => (0, 1),
+        ("GET", "/fapi/v1/userTrades") => (5, 0),
+        ("GET", "/fapi/v2/positionRisk") => (5, 0),
+        ("GET", "/fapi/v2/balance") => (5, 0),
+        ("GET", "/fapi/v1/positionSide/dual") => (30, 0),
+        _ => (1, 0),
+    }
+}
+
+#[async_trait]
+impl Exchange for BinanceFuturesExchange {
+    async fn place_order(&self, request: &OrderRequest) -> Result<Order, Error> {
+        self.prepare(&request.symbol).await?;
+        let request = &self.normalize(request).await?;
+        let params = order_params(request, self.config.binance.futures.position_mode)?;
+        let futures_order: FuturesOrderResult = self.send_signed(params).await?;
+
+        let mut order = Order::new(
+            futures_order.order_id.to_string(),
+            request,
+            futures_order.status,
+        );
+        order.set_filled(futures_order.executed_qty, futures_order.cum_quote);
+
+        // 下单结果不带成交明细，从成交记录取回实际价格和手续费
+        if order.filled_size > Decimal::ZERO {
+            match self.order_trades(&request.symbol, &order.order_id).await {
+                Ok(fills) => order.fills = fills,
+                Err(e) => warn!("Failed to fetch trades for order {}: {}", order.order_id, e),
+            }
+        }
+        Ok(order)
+    }
+
+    async fn cancel_order(&self, symbol: &Symbol, order_id: &str) -> Result<(), Error> {
+        let request = BinanceRequest::delete("/fapi/v1/order")
+            .param("symbol", symbol)
+            .param("orderId", order_id);
+
+        self.send_signed::<Value>(request).await?;
+        Ok(())
+    }
+
+    async fn get_order(&self, symbol: &Symbol, order_id: &str) -> Result<Order, Error> {
+        let request = BinanceRequest::get("/fapi/v1/order")
+            .param("symbol", symbol)
+            .param("orderId", order_id);
+
+        let order: FuturesOrder = self.send_signed(request).await?;
+        order.to_order(symbol)
+    }
+
+    async fn find_order(
+        &self,
+        symbol: &Symbol,
+        client_order_id: &str,
+    ) -> Result<Option<Order>, Error> {
+        let request = BinanceRequest::get("/fapi/v1/order")
+            .param("symbol", symbol)
+            .param("origClientOrderId", client_order_id);
+
+        match self.send_signed::<FuturesOrder>(request).await {
+            Ok(order) => order.to_order(symbol).map(Some),
+            // -2013：订单不存在
+            Err(e) if has_code(&e, -2013) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn get_open_orders(&self, symbol: &Symbol) -> Result<Vec<Order>, Error> {
+        let request = BinanceRequest::get("/fapi/v1/openOrders").param("symbol", symbol);
+
+        let orders: Vec<FuturesOrder> = self.send_signed(request).await?;
+        orders.iter().map(|o| o.to_order(symbol)).collect()
+    }
+
+    /// `allOpenOrders` 不返回订单，改为按挂单分批撤销，以拿到每个订单撤销时的成交
+    async fn cancel_all(&self, symbol: &Symbol) -> Result<Vec<Order>, Error> {
+        let open = self.get_open_orders(symbol).await?;
+        let mut canceled = Vec::new();
+        for batch in open.chunks(BATCH_CANCEL_SIZE) {
+            let ids: Vec<u64> = batch
+                .iter()
+                .filter_map(|order| order.order_id.parse().ok())
+                .collect();
+            let request = BinanceRequest::delete("/fapi/v1/batchOrders")
+                .param("symbol", symbol)
+                .param("orderIdList", serde_json::to_string(&ids)?);
+
+            let results: Vec<Value> = self.send_signed(request).await?;
+            // 已成交或已撤销的订单以错误项返回
+            for entry in results
+                .into_iter()
+                .filter(|entry| entry.get("orderId").is_some())
+            {
+                canceled.push(serde_json::from_value::<FuturesOrder>(entry)?.to_order(symbol)?);
+            }
+        }
+        Ok(canceled)
+    }
+
+    async fn get_my_trades(
+        &self,
+        symbol: &Symbol,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Fill>, Error> {
+        let request = BinanceRequest::get("/fapi/v1/userTrades")
+            .param("symbol", symbol)
+            .param_opt("startTime", since.map(|since| since.timestamp_millis()));
+
+        let trades: Vec<FuturesTrade> = self.send_signed(request).await?;
+        trades.iter().map(|t| t.to_fill(symbol)).collect()
+    }
+
+    async fn get_exchange_info(&self, symbol: &Symbol) -> Result<SymbolInfo, Error> {
+        let payload: Value = self.send_public("/fapi/v1/exchangeInfo", None).await?;
+
+        let info = parse_symbol_info(symbol, &payload)?;
+        self.symbol_info
+            .lock()
+            .expect("symbol info lock poisoned")
+            .insert(symbol.clone(), info.clone());
+        Ok(info)
+    }
+
+    /// 读取下单取整时缓存的交易对规则，未缓存时从交易所载入
+    async fn symbol_info(&self, symbol: &Symbol) -> Result<SymbolInfo, Error> {
+        let cached = self
+            .symbol_info
+            .lock()
+            .expect("symbol info lock poisoned")
+            .get(symbol)
+            .cloned();
+        match cached {
+            Some(info) => Ok(info),
+            None => self.get_exchange_info(symbol).await,
+        }
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>, Error> {
+        let risks: Vec<FuturesPosition> = self
+            .send_signed(BinanceRequest::get("/fapi/v2/positionRisk"))
+            .await?;
+
+        Ok(risks
+            .iter()
+            .filter_map(|risk| risk.to_position(&self.config.symbols))
+            .collect())
+    }
+
+    async fn get_balance(&self) -> Result<HashMap<String, Decimal>, Error> {
+        let balances: Vec<FuturesBalance> = self
+            .send_signed(BinanceRequest::get("/fapi/v2/balance"))
+            .await?;
+
+        Ok(balances
+            .into_iter()
+            .map(|balance| (balance.asset, balance.available_balance))
+            .collect())
+    }
+
+    fn futures_mode(&self) -> Option<PositionMode> {
+        Some(self.config.binance.futures.position_mode)
+    }
+}
+
+/// 合约的订单类型名称；`LimitMaker` 以 LIMIT 加 GTX 有效期下单
+fn kind_name(kind: OrderKind) -> &'static str {
+    match kind {
+        OrderKind::Market => "MARKET",
+        OrderKind::Limit | OrderKind::LimitMaker => "LIMIT",
+        OrderKind::StopLoss => "STOP_MARKET",
+        OrderKind::StopLossLimit => "STOP",
+        OrderKind::TakeProfit => "TAKE_PROFIT_MARKET",
+        OrderKind::TakeProfitLimit => "TAKE_PROFIT",
+    }
+}
+
+/// 只做 maker 的有效期
+const GTX: &str = "GTX";
+
+/// 将下单请求映射为 `/fapi/v1/order` 的参数
+///
+/// 单向持仓模式不带 `positionSide`；双向持仓模式必须指定多头或空头，
+/// 平仓通过反向下单完成，交易所不接受 `reduceOnly`。平仓单不带数量。
+fn order_params(request: &OrderRequest, mode: PositionMode) -> Result<BinanceRequest, Error> {
+    request.validate()?;
+
+    let position_side = match (mode, request.position_side) {
+        (PositionMode::OneWay, None | Some(PositionSide::Both)) => None,
+        (PositionMode::Hedge, Some(side @ (PositionSide::Long | PositionSide::Short))) => {
+            Some(side)
+        }
+        (mode, side) => {
+            return Err(Error::invalid_order(format!(
+                "position side {:?} is not valid in {} position mode",
+                side, mode
+            )))
+        }
+    };
+    if mode == PositionMode::Hedge && request.reduce_only {
+        return Err(Error::invalid_order(
+            "reduce only is not accepted in hedge position mode",
+        ));
+    }
+
+    let time_in_force = match request.kind {
+        OrderKind::LimitMaker => Some(GTX.to_string()),
+        _ => request.effective_time_in_force().map(|tif| tif.to_string()),
+    };
+
+    Ok(BinanceRequest::post("/fapi/v1/order")
+        .param("symbol", &request.symbol)
+        .param("side", side_name(request.side))
+        .param_opt("positionSide", position_side)
+        .param("type", kind_name(request.kind))
+        .param_opt(
+            "quantity",
+            (!request.close_position).then_some(request.size),
+        )
+        .param_opt("price", request.price)
+        .param_opt("stopPrice", request.trigger_price)
+        .param_opt("timeInForce", time_in_force)
+        .param_opt("reduceOnly", request.reduce_only.then_some(true))
+        .param_opt("closePosition", request.close_position.then_some(true))
+        .param_opt("newClientOrderId", request.client_order_id.as_ref())
+        // 返回撮合后的状态和成交数量
+        .param("newOrderRespType", "RESULT")
+        .in_body())
+}
+
+/// 从 `/fapi/v1/exchangeInfo` 的响应中取出交易对的状态、订单类型和过滤器
+fn parse_symbol_info(symbol: &Symbol, payload: &Value) -> Result<SymbolInfo, Error> {
+    let entry = symbol_entry(symbol, payload)?;
+    let filter = |filter_type: &str, field: &str| filter_value(entry, filter_type, field);
+
+    let order_kinds = entry["orderTypes"]
+        .as_array()
+        .map(|types| {
+            OrderKind::ALL
+                .into_iter()
+                .filter(|kind| types.iter().any(|t| t == kind_name(*kind)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(SymbolInfo {
+        symbol: symbol.clone(),
+        trading: entry["status"] == "TRADING",
+        tick_size: filter("PRICE_FILTER", "tickSize")?,
+        step_size: filter("LOT_SIZE", "stepSize")?,
+        min_size: filter("LOT_SIZE", "minQty")?,
+        max_size: filter("LOT_SIZE", "maxQty")?,
+        min_notional: filter("MIN_NOTIONAL", "notional")?,
+        order_kinds,
+    })
+}
+
+#[derive(Deserialize)]
+struct LeverageResponse {
+    leverage: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DualSidePosition {
+    dual_side_position: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FuturesBalance {
+    asset: String,
+    available_balance: Decimal,
+}
+
+/// 下单接口返回的撮合结果
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FuturesOrderResult {
+    order_id: u64,
+    status: OrderStatus,
+    executed_qty: Decimal,
+    cum_quote: Decimal,
+}
+
+/// 查询和撤单接口返回的订单
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FuturesOrder {
+    order_id: u64,
+    #[serde(default)]
+    client_order_id: Option<String>,
+    side: String,
+    #[serde(rename = "type")]
+    order_type: String,
+    orig_qty: Decimal,
+    price: Decimal,
+    #[serde(default)]
+    stop_price: Decimal,
+    time_in_force: String,
+    status: OrderStatus,
+    executed_qty: Decimal,
+    cum_quote: Decimal,
+    #[serde(default)]
+    reduce_only: bool,
+    #[serde(default)]
+    close_position: bool,
+    #[serde(default)]
+    position_side: PositionSide,
+}
+
+impl FuturesOrder {
+    fn to_order(&self, symbol: &Symbol) -> Result<Order, Error> {
+        let kind = match parse_name(
+            &OrderKind::ALL,
+            |k| kind_name(k).to_string(),
+            "type",
+            &self.order_type,
+        )? {
+            OrderKind::Limit if self.time_in_force == GTX => OrderKind::LimitMaker,
+            kind => kind,
+        };
+        let side = parse_name(
+            &[Side::Buy, Side::Sell],
+            |s| side_name(s).to_string(),
+            "side",
+            &self.side,
+        )?;
+        let time_in_force = if kind.accepts_time_in_force() {
+            Some(parse_name(
+                &[TimeInForce::Gtc, TimeInForce::Ioc, TimeInForce::Fok],
+                |tif| tif.to_string(),
+                "timeInForce",
+                &self.time_in_force,
+            )?)
+        } else {
+            None
+        };
+        // 未使用的价格字段以 0 返回
+        let positive = |value: Decimal| (value > Decimal::ZERO).then_some(value);
+
+        let mut order = Order {
+            order_id: self.order_id.to_string(),
+            client_order_id: self.client_order_id.clone(),
+            symbol: symbol.clone(),
+            side,
+            kind,
+            size: self.orig_qty,
+            price: positive(self.price).filter(|_| kind.has_limit_price()),
+            trigger_price: positive(self.stop_price).filter(|_| kind.has_trigger_price()),
+            time_in_force,
+            status: self.status,
+            filled_size: Decimal::ZERO,
+            average_price: None,
+            fills: Vec::new(),
+            reduce_only: self.reduce_only || self.close_position,
+            position_side: self.position_side,
+        };
+        order.set_filled(self.executed_qty, self.cum_quote);
+        Ok(order)
+    }
+}
+
+/// `GET /fapi/v1/userTrades` 返回的成交
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FuturesTrade {
+    order_id: u64,
+    price: Decimal,
+    qty: Decimal,
+    commission: Decimal,
+    commission_asset: String,
+    time: i64,
+    buyer: bool,
+    #[serde(default)]
+    position_side: PositionSide,
+}
+
+impl FuturesTrade {
+    fn to_fill(&self, symbol: &Symbol) -> Result<Fill, Error> {
+        let timestamp = Utc
+            .timestamp_millis_opt(self.time)
+            .single()
+            .ok_or_else(|| Error::ParseError(format!("invalid trade time {}", self.time)))?;
+
+        Ok(Fill {
+            order_id: self.order_id.to_string(),
+            symbol: symbol.clone(),
+            side: if self.buyer { Side::Buy } else { Side::Sell },
+            price: self.price,
+            size: self.qty,
+            fee: self.commission,
+            fee_asset: FeeAsset::of(symbol, &self.commission_asset),
+            position_side: self.position_side,
+            timestamp,
+        })
+    }
+}
+
+/// `GET /fapi/v2/positionRisk` 返回的持仓，`positionAmt` 空头为负数
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FuturesPosition {
+    symbol: String,
+    position_amt: Decimal,
+    entry_price: Decimal,
+    mark_price: Decimal,
+    un_realized_profit: Decimal,
+    liquidation_price: Decimal,
+    position_side: PositionSide,
+}
+
+impl FuturesPosition {
+    /// 只保留配置中交易对的非零持仓
+    fn to_position(&self, symbols: &[Symbol]) -> Option<Position> {
+        if self.position_amt.is_zero() {
+            return None;
+        }
+        let symbol = symbols.iter().find(|s| s.to_string() == self.symbol)?;
+        Some(Position {
+            symbol: symbol.clone(),
+            size: self.position_amt,
+            entry_price: self.entry_price,
+            current_price: self.mark_price,
+            unrealized_pnl: self.un_realized_profit,
+            position_side: self.position_side,
+            // 无强平风险时返回 0
+            liquidation_price: (self.liquidation_price > Decimal::ZERO)
+                .then_some(self.liquidation_price),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+    use crate::orders::OrderManager;
+    use crate::types::test_symbol;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+    use wiremock::matchers::{body_string_contains, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn exchange(server: &MockServer) -> BinanceFuturesExchange {
+        BinanceFuturesExchange::new(Arc::new(test_config()))
+            .with_base_url(server.uri())
+            .with_rate_limiter(Arc::new(RateLimiter::binance_futures()))
+    }
+
+    #[test]
+    fn test_order_params() -> Result<(), Error> {
+        let btc = test_symbol("BTCUSDT");
+        let one_way = PositionMode::OneWay;
+        let hedge = PositionMode::Hedge;
+
+        // 单向持仓：做空即卖出开仓，平空用只减仓买单
+        let short = order_params(&OrderRequest::market(&btc, Side::Sell, dec!(0.5)), one_way)?;
+        assert_eq!(&short["side"], "SELL");
+        assert_eq!(&short["type"], "MARKET");
+        assert!(!short.contains_key("positionSide"));
+        assert!(!short.contains_key("reduceOnly"));
+        let cover = order_params(
+            &OrderRequest::market(&btc, Side::Buy, dec!(0.5)).with_reduce_only(),
+            one_way,
+        )?;
+        assert_eq!(&cover["reduceOnly"], "true");
+
+        // 双向持仓：必须指定持仓方向，不接受只减仓
+        let long = order_params(
+            &OrderRequest::limit(&btc, Side::Buy, dec!(1), dec!(95))
+                .with_position_side(PositionSide::Long),
+            hedge,
+        )?;
+        assert_eq!(&long["positionSide"], "LONG");
+        assert_eq!(&long["timeInForce"], "GTC");
+        let market = OrderRequest::market(&btc, Side::Sell, dec!(1));
+        assert!(order_params(&market, hedge).is_err());
+        assert!(order_params(
+            &market.clone().with_position_side(PositionSide::Short),
+            one_way
+        )
+        .is_err());
+        assert!(order_params(
+            &market
+                .with_position_side(PositionSide::Short)
+                .with_reduce_only(),
+            hedge
+        )
+        .is_err());
+
+        // 平仓单不带数量
+        let close = order_params(
+            &OrderRequest::close_position(&btc, Side::Buy, OrderKind::StopLoss, dec!(110)),
+            one_way,
+        )?;
+        assert_eq!(&close["type"], "STOP_MARKET");
+        assert_eq!(&close["stopPrice"], "110");
+        assert_eq!(&close["closePosition"], "true");
+        assert!(!close.contains_key("quantity"));
+
+        let maker = order_params(
+            &OrderRequest::limit_maker(&btc, Side::Sell, dec!(1), dec!(120)),
+            one_way,
+        )?;
+        assert_eq!(&maker["type"], "LIMIT");
+        assert_eq!(&maker["timeInForce"], "GTX");
+
+        let stop_limit = order_params(
+            &OrderRequest::take_profit_limit(&btc, Side::Sell, dec!(1), dec!(120), dec!(119)),
+            one_way,
+        )?;
+        assert_eq!(&stop_limit["type"], "TAKE_PROFIT");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_futures_order_to_order() -> Result<(), Error> {
+        let order = |value| -> Result<Order, Error> {
+            serde_json::from_value::<FuturesOrder>(value)?.to_order(&test_symbol("BTCUSDT"))
+        };
+
+        let maker = order(json!({
+            "orderId": 22542179,
+            "clientOrderId": "rqt-1-1",
+            "symbol": "BTCUSDT",
+            "side": "SELL",
+            "positionSide": "BOTH",
+            "type": "LIMIT",
+            "origQty": "0.010",
+            "price": "70000.00",
+            "stopPrice": "0",
+            "timeInForce": "GTX",
+            "status": "NEW",
+            "executedQty": "0",
+            "cumQuote": "0",
+            "avgPrice": "0.00",
+            "reduceOnly": false,
+            "closePosition": false
+        }))?;
+        assert_eq!(maker.kind, OrderKind::LimitMaker);
+        assert_eq!(maker.price, Some(dec!(70000)));
+        assert_eq!(maker.time_in_force, None);
+
+        let stop = order(json!({
+            "orderId": 22542180,
+            "symbol": "BTCUSDT",
+            "side": "BUY",
+            "positionSide": "BOTH",
+            "type": "STOP_MARKET",
+            "origQty": "0",
+            "price": "0",
+            "stopPrice": "72000",
+            "timeInForce": "GTC",
+            "status": "FILLED",
+            "executedQty": "0.010",
+            "cumQuote": "720.50",
+            "avgPrice": "72050.00",
+            "reduceOnly": true,
+            "closePosition": true
+        }))?;
+        assert_eq!(stop.kind, OrderKind::StopLoss);
+        assert_eq!(stop.trigger_price, Some(dec!(72000)));
+        assert_eq!(stop.average_price, Some(dec!(72050)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_position_risk_to_position() -> Result<(), Error> {
+        let symbols = [test_symbol("BTCUSDT")];
+        let risks: Vec<FuturesPosition> = serde_json::from_value(json!([
+            {
+                "symbol": "BTCUSDT",
+                "positionAmt": "-0.250",
+                "entryPrice": "60000.0",
+                "markPrice": "59000.00000000",
+                "unRealizedProfit": "250.00000000",
+                "liquidationPrice": "79234.51234567",
+                "leverage": "3",
+                "marginType": "isolated",
+                "positionSide": "BOTH"
+            },
+            {
+                "symbol": "ETHUSDT",
+                "positionAmt": "1.000",
+                "entryPrice": "3000.0",
+                "markPrice": "3100.00000000",
+                "unRealizedProfit": "100.00000000",
+                "liquidationPrice": "0",
+                "positionSide": "BOTH"
+            },
+            {
+                "symbol": "BTCUSDT",
+                "positionAmt": "0.000",
+                "entryPrice": "0.0",
+                "markPrice": "59000.00000000",
+                "unRealizedProfit": "0.00000000",
+                "liquidationPrice": "0",
+                "positionSide": "LONG"
+            }
+        ]))?;
+
+        let positions: Vec<Position> = risks
+            .iter()
+            .filter_map(|risk| risk.to_position(&symbols))
+            .collect();
+        // 空仓和未配置的交易对都不算持仓
+        assert_eq!(positions.len(), 1);
+        let short = &positions[0];
+        assert_eq!(short.size, dec!(-0.25));
+        assert_eq!(short.entry_price, dec!(60000));
+        assert_eq!(short.current_price, dec!(59000));
+        assert_eq!(short.unrealized_pnl, dec!(250));
+        assert_eq!(short.liquidation_price, Some(dec!(79234.51234567)));
+        assert_eq!(short.position_side, PositionSide::Both);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_place_order_applies_settings_once() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/fapi/v1/exchangeInfo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "symbols": [{
+                    "symbol": "BTCUSDT",
+                    "status": "TRADING",
+                    "orderTypes": ["LIMIT", "MARKET", "STOP", "STOP_MARKET", "TAKE_PROFIT", "TAKE_PROFIT_MARKET"],
+                    "filters": [
+                        {"filterType": "PRICE_FILTER", "tickSize": "0.10"},
+                        {"filterType": "LOT_SIZE", "minQty": "0.001", "maxQty": "1000", "stepSize": "0.001"},
+                        {"filterType": "MIN_NOTIONAL", "notional": "100"}
+                    ]
+                }]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // 已是单向持仓模式
+        Mock::given(method("POST"))
+            .and(path("/fapi/v1/positionSide/dual"))
+            .and(body_string_contains("dualSidePosition=false"))
+            .respond_with(
+                ResponseTemplate::new(400).set_body_json(
+                    json!({"code": -4059, "msg": "No need to change position side."}),
+                ),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/fapi/v1/marginType"))
+            .and(body_string_contains("symbol=BTCUSDT&marginType=CROSSED"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"code": 200, "msg": "success"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/fapi/v1/leverage"))
+            .and(body_string_contains("symbol=BTCUSDT&leverage=1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "leverage": 1,
+                "maxNotionalValue": "1000000",
+                "symbol": "BTCUSDT"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/fapi/v1/order"))
+            .and(body_string_contains(
+                "symbol=BTCUSDT&side=SELL&type=LIMIT&quantity=0.012&price=70000.1\
+                 &timeInForce=GTC&newOrderRespType=RESULT",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "orderId": 9,
+                "status": "NEW",
+                "executedQty": "0",
+                "cumQuote": "0"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/fapi/v1/order"))
+            .and(body_string_contains("reduceOnly=true"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "orderId": 10,
+                "status": "FILLED",
+                "executedQty": "0.001",
+                "cumQuote": "70.00"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        // 成交后取回手续费
+        Mock::given(method("GET"))
+            .and(path("/fapi/v1/userTrades"))
+            .and(query_param("orderId", "10"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "orderId": 10,
+                "price": "70000",
+                "qty": "0.001",
+                "commission": "0.028",
+                "commissionAsset": "USDT",
+                "time": 1700000000000i64,
+                "buyer": true,
+                "positionSide": "BOTH"
+            }])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let exchange = exchange(&server);
+        let btc = test_symbol("BTCUSDT");
+
+        // 开空
+        let order = exchange
+            .place_order(&OrderRequest::limit(
+                &btc,
+                Side::Sell,
+                dec!(0.0123),
+                dec!(70000.08),
+            ))
+            .await?;
+        assert_eq!(order.order_id, "9");
+        assert_eq!(order.size, dec!(0.012));
+
+        // 只减仓的小额平空不受最小名义价值限制
+        let order = exchange
+            .place_order(
+                &OrderRequest::limit(&btc, Side::Buy, dec!(0.001), dec!(70000)).with_reduce_only(),
+            )
+            .await?;
+        assert_eq!(order.status, OrderStatus::Filled);
+        assert_eq!(order.average_price, Some(dec!(70000)));
+        assert_eq!(order.fills.len(), 1);
+        assert_eq!(order.fills[0].quote_fee(), dec!(0.028));
+
+        // 手续费随成交进入订单跟踪，不再补出零手续费的成交
+        let fills = OrderManager::new().apply(order)?;
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].fee, dec!(0.028));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_returns_canceled_orders() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        let open = |order_id: u64, status: &str| {
+            json!({
+                "orderId": order_id,
+                "symbol": "BTCUSDT",
+                "side": "BUY",
+                "type": "LIMIT",
+                "origQty": "0.010",
+                "price": "60000",
+                "stopPrice": "0",
+                "timeInForce": "GTC",
+                "status": status,
+                "executedQty": "0",
+                "cumQuote": "0"
+            })
+        };
+        Mock::given(method("GET"))
+            .and(path("/fapi/v1/openOrders"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!([open(1, "NEW"), open(2, "NEW")])),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/fapi/v1/batchOrders"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([
+                open(1, "CANCELED"),
+                {"code": -2011, "msg": "Unknown order sent."}
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let canceled = exchange(&server)
+            .cancel_all(&test_symbol("BTCUSDT"))
+            .await?;
+        // 撤单前已成交的订单不在结果中
+        assert_eq!(canceled.len(), 1);
+        assert_eq!(canceled[0].order_id, "1");
+        assert_eq!(canceled[0].status, OrderStatus::Canceled);
+
+        Ok(())
+    }
+}
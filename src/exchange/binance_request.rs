@@ -0,0 +1,213 @@
+// src/exchange/binance_request.rs
+//! Binance 签名请求：按插入顺序拼接参数、百分号编码取值并计算签名
+
+use reqwest::Method;
+#[cfg(test)]
+use std::ops::Index;
+
+use crate::crypto_tools;
+
+/// 待签名的 Binance 请求，参数保持插入顺序
+#[derive(Debug, Clone)]
+pub(crate) struct BinanceRequest {
+    pub(crate) method: Method,
+    pub(crate) path: &'static str,
+    params: Vec<(String, String)>,
+    // 参数放在表单请求体中而不是 URL 上
+    in_body: bool,
+}
+
+/// 签名后的查询串和请求体，签名附在最后一个非空部分的末尾
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SignedRequest {
+    pub(crate) query: String,
+    pub(crate) body: String,
+}
+
+impl BinanceRequest {
+    pub(crate) fn new(method: Method, path: &'static str) -> Self {
+        Self {
+            method,
+            path,
+            params: Vec::new(),
+            in_body: false,
+        }
+    }
+
+    pub(crate) fn get(path: &'static str) -> Self {
+        Self::new(Method::GET, path)
+    }
+
+    pub(crate) fn post(path: &'static str) -> Self {
+        Self::new(Method::POST, path)
+    }
+
+    pub(crate) fn delete(path: &'static str) -> Self {
+        Self::new(Method::DELETE, path)
+    }
+
+    pub(crate) fn param(mut self, key: &str, value: impl ToString) -> Self {
+        self.params.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    pub(crate) fn param_opt(self, key: &str, value: Option<impl ToString>) -> Self {
+        match value {
+            Some(value) => self.param(key, value),
+            None => self,
+        }
+    }
+
+    /// 参数改为以 `application/x-www-form-urlencoded` 请求体发送（POST、DELETE）
+    pub(crate) fn in_body(mut self) -> Self {
+        self.in_body = true;
+        self
+    }
+
+    #[cfg(test)]
+    pub(crate) fn contains_key(&self, key: &str) -> bool {
+        self.params.iter().any(|(k, _)| k == key)
+    }
+
+    /// 按插入顺序编码的参数串
+    #[cfg(test)]
+    pub(crate) fn encoded_params(&self) -> String {
+        encode_pairs(&self.params)
+    }
+
+    /// 追加 `recvWindow`、`timestamp` 后签名
+    ///
+    /// 签名对象为查询串与请求体直接拼接的结果，与 Binance 服务端的校验方式一致。
+    pub(crate) fn sign(&self, secret: &str, timestamp: i64, recv_window: u64) -> SignedRequest {
+        let mut params = self.params.clone();
+        params.push(("recvWindow".to_string(), recv_window.to_string()));
+        params.push(("timestamp".to_string(), timestamp.to_string()));
+        let encoded = encode_pairs(&params);
+
+        let (query, body) = if self.in_body {
+            (String::new(), encoded)
+        } else {
+            (encoded, String::new())
+        };
+        sign_parts(secret, query, body)
+    }
+}
+
+#[cfg(test)]
+impl Index<&str> for BinanceRequest {
+    type Output = str;
+
+    fn index(&self, key: &str) -> &str {
+        self.params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+            .unwrap_or_else(|| panic!("missing parameter {}", key))
+    }
+}
+
+/// 对已编码的查询串和请求体签名
+fn sign_parts(secret: &str, mut query: String, mut body: String) -> SignedRequest {
+    let signature = crypto_tools::hmac_sha256(secret, format!("{}{}", query, body));
+    let target = if body.is_empty() {
+        &mut query
+    } else {
+        &mut body
+    };
+    if !target.is_empty() {
+        target.push('&');
+    }
+    target.push_str("signature=");
+    target.push_str(&signature);
+    SignedRequest { query, body }
+}
+
+fn encode_pairs(params: &[(String, String)]) -> String {
+    params
+        .iter()
+        .map(|(k, v)| format!("{}={}", percent_encode(k), percent_encode(v)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// 按 RFC 3986 编码，只保留字母、数字和 `-_.~`，空格编码为 `%20`
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Binance API 文档中 HMAC 签名示例使用的密钥
+    const DOC_SECRET: &str = "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j";
+
+    fn doc_order() -> BinanceRequest {
+        BinanceRequest::post("/api/v3/order")
+            .param("symbol", "LTCBTC")
+            .param("side", "BUY")
+            .param("type", "LIMIT")
+            .param("timeInForce", "GTC")
+            .param("quantity", 1)
+            .param("price", "0.1")
+    }
+
+    #[test]
+    fn test_sign_query_string() {
+        let signed = doc_order().sign(DOC_SECRET, 1499827319559, 5000);
+        assert_eq!(
+            signed.query,
+            "symbol=LTCBTC&side=BUY&type=LIMIT&timeInForce=GTC&quantity=1&price=0.1\
+             &recvWindow=5000&timestamp=1499827319559\
+             &signature=c8db56825ae71d6d79447849e617115f4a920fa2acdcab2b053c4b2838bd6b71"
+        );
+        assert!(signed.body.is_empty());
+    }
+
+    #[test]
+    fn test_sign_body() {
+        let signed = doc_order().in_body().sign(DOC_SECRET, 1499827319559, 5000);
+        assert!(signed.query.is_empty());
+        assert!(signed.body.ends_with(
+            "&signature=c8db56825ae71d6d79447849e617115f4a920fa2acdcab2b053c4b2838bd6b71"
+        ));
+
+        // 参数分别放在查询串和请求体时，对两者的直接拼接签名
+        let mixed = sign_parts(
+            DOC_SECRET,
+            "symbol=LTCBTC&side=BUY&type=LIMIT&timeInForce=GTC".to_string(),
+            "quantity=1&price=0.1&recvWindow=5000&timestamp=1499827319559".to_string(),
+        );
+        assert_eq!(
+            mixed.query,
+            "symbol=LTCBTC&side=BUY&type=LIMIT&timeInForce=GTC"
+        );
+        assert!(mixed.body.ends_with(
+            "&signature=0fd168b8ddb4876a0358a8d14d0c9f3da0e9b20c5d52b2a00fcf7d1c602f9a77"
+        ));
+    }
+
+    #[test]
+    fn test_percent_encode() {
+        let request = BinanceRequest::get("/api/v3/order")
+            .param("symbol", "BTCUSDT")
+            .param("origClientOrderId", "a b/c:d&e=f")
+            .param_opt("orderId", None::<u64>);
+        assert_eq!(
+            request.encoded_params(),
+            "symbol=BTCUSDT&origClientOrderId=a%20b%2Fc%3Ad%26e%3Df"
+        );
+        assert_eq!(&request["symbol"], "BTCUSDT");
+        assert!(!request.contains_key("orderId"));
+        assert_eq!(percent_encode("中"), "%E4%B8%AD");
+    }
+}
@@ -0,0 +1,190 @@
+// src/exchange/clock.rs
+//! 交易所服务器时间校准：定期取样本机与服务器的时钟偏差，签名请求使用校准后的时间
+
+use chrono::Utc;
+use reqwest::Client;
+use serde::Deserialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::error::Error;
+use crate::notification::dingtalk::DingTalkBot;
+
+#[derive(Debug, Default)]
+struct ClockState {
+    // 服务器时间 - 本机时间（毫秒）
+    offset_ms: i64,
+    synced_at: Option<Instant>,
+    // 偏差超限已告警，恢复正常前不再重复发送
+    drift_alerted: bool,
+}
+
+/// 按 `sync_interval` 向 `time_url`（如 `/api/v3/time`）取样的服务器时钟
+///
+/// 取样失败时沿用上一次的偏差，下次取时间时再试。
+pub struct ServerClock {
+    client: Client,
+    time_url: String,
+    sync_interval: Duration,
+    max_drift_ms: i64,
+    notifier: Option<DingTalkBot>,
+    state: Mutex<ClockState>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerTime {
+    server_time: i64,
+}
+
+impl ServerClock {
+    pub fn new(time_url: impl Into<String>, sync_interval: Duration, max_drift_ms: i64) -> Self {
+        Self {
+            client: Client::new(),
+            time_url: time_url.into(),
+            sync_interval,
+            max_drift_ms,
+            notifier: None,
+            state: Mutex::new(ClockState::default()),
+        }
+    }
+
+    pub fn with_time_url(mut self, time_url: impl Into<String>) -> Self {
+        self.time_url = time_url.into();
+        self
+    }
+
+    /// 偏差超过 `max_drift_ms` 时通过钉钉告警
+    pub fn with_notifier(mut self, notifier: DingTalkBot) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// 校准后的服务器时间（毫秒时间戳），校准过期时先取样
+    pub async fn now_ms(&self) -> i64 {
+        if self.is_stale() {
+            if let Err(e) = self.sync().await {
+                warn!("Failed to sync server time from {}: {}", self.time_url, e);
+            }
+        }
+        Utc::now().timestamp_millis() + self.offset_ms()
+    }
+
+    /// 当前使用的时钟偏差（毫秒），正数表示本机时钟偏慢
+    pub fn offset_ms(&self) -> i64 {
+        self.state.lock().expect("clock lock poisoned").offset_ms
+    }
+
+    /// 使校准失效，下次取时间时重新取样（如收到 -1021 时间戳错误后）
+    pub fn invalidate(&self) {
+        self.state.lock().expect("clock lock poisoned").synced_at = None;
+    }
+
+    /// 立即取样一次，返回新的偏差
+    ///
+    /// 以请求往返的中点作为服务器时间对应的本机时间。
+    pub async fn sync(&self) -> Result<i64, Error> {
+        let sent_at = Utc::now().timestamp_millis();
+        let response = self.client.get(&self.time_url).send().await?;
+        if !response.status().is_success() {
+            return Err(Error::ApiError(format!(
+                "{}: {}",
+                self.time_url,
+                response.status()
+            )));
+        }
+        let server: ServerTime = response.json().await?;
+        let received_at = Utc::now().timestamp_millis();
+        let offset_ms = server.server_time - (sent_at + received_at) / 2;
+
+        let alert = {
+            let mut state = self.state.lock().expect("clock lock poisoned");
+            state.offset_ms = offset_ms;
+            state.synced_at = Some(Instant::now());
+            let drifted = offset_ms.abs() > self.max_drift_ms;
+            let alert = drifted && !state.drift_alerted;
+            state.drift_alerted = drifted;
+            alert
+        };
+
+        if alert {
+            let message = format!(
+                "本机时钟与交易所相差 {} ms，超过 {} ms，请检查系统时间同步",
+                offset_ms, self.max_drift_ms
+            );
+            warn!("{}", message);
+            if let Some(notifier) = &self.notifier {
+                if let Err(e) = notifier.send_text(message).await {
+                    warn!("Failed to send clock drift notification: {}", e);
+                }
+            }
+        } else {
+            info!("Server time offset: {} ms", offset_ms);
+        }
+
+        Ok(offset_ms)
+    }
+
+    fn is_stale(&self) -> bool {
+        let state = self.state.lock().expect("clock lock poisoned");
+        state
+            .synced_at
+            .is_none_or(|synced_at| synced_at.elapsed() >= self.sync_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_sync_offset() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        // 服务器比本机快 5 秒
+        let server_time = Utc::now().timestamp_millis() + 5_000;
+        Mock::given(method("GET"))
+            .and(path("/api/v3/time"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "serverTime": server_time
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let clock = ServerClock::new(
+            format!("{}/api/v3/time", server.uri()),
+            Duration::from_secs(60),
+            1_000,
+        );
+        let now = clock.now_ms().await;
+        assert!((clock.offset_ms() - 5_000).abs() < 1_000);
+        assert!((now - Utc::now().timestamp_millis() - 5_000).abs() < 1_000);
+        assert!(clock.state.lock().unwrap().drift_alerted);
+
+        // 校准未过期时不再请求
+        clock.now_ms().await;
+        clock.invalidate();
+        clock.now_ms().await;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_failure_keeps_local_time() {
+        let server = MockServer::start().await;
+        let clock = ServerClock::new(
+            format!("{}/api/v3/time", server.uri()),
+            Duration::from_secs(60),
+            1_000,
+        );
+
+        assert!(clock.sync().await.is_err());
+        let now = clock.now_ms().await;
+        assert_eq!(clock.offset_ms(), 0);
+        assert!((now - Utc::now().timestamp_millis()).abs() < 1_000);
+    }
+}
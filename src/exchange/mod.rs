@@ -1,29 +1,103 @@
 mod binance;
+pub(crate) use binance::read_response;
 pub use binance::BinanceExchange;
 
+mod binance_futures;
+pub use binance_futures::{
+    BinanceFuturesExchange, BINANCE_FUTURES_API_URL, BINANCE_FUTURES_TESTNET_API_URL,
+};
+
+mod binance_request;
+
+mod clock;
+pub use clock::ServerClock;
+
+mod paper;
+pub use paper::PaperExchange;
+
 mod tushare;
 pub use tushare::TushareExchange;
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use std::collections::HashMap;
+use std::sync::Arc;
 
+use crate::config::{PositionMode, TradingConfig};
 use crate::error::Error;
-use crate::types::{Order, OrderType, Position};
+use crate::notification::dingtalk::DingTalkBot;
+use crate::retry::RetryExchange;
+use crate::types::{Fill, Order, OrderRequest, Position, Symbol, SymbolInfo};
 
 #[async_trait]
 pub trait Exchange: Send + Sync {
-    async fn place_order(
+    /// 下单；适配器不支持的订单类型或参数组合返回 `Error::InvalidOrder`
+    async fn place_order(&self, request: &OrderRequest) -> Result<Order, Error>;
+
+    async fn cancel_order(&self, symbol: &Symbol, order_id: &str) -> Result<(), Error>;
+
+    /// 查询订单的最新状态和累计成交
+    async fn get_order(&self, symbol: &Symbol, order_id: &str) -> Result<Order, Error>;
+
+    /// 按客户端订单号查找订单，交易所没有该订单时返回 `None`
+    async fn find_order(
         &self,
-        symbol: &str,
-        order_type: OrderType,
-        size: Decimal,
-        price: Option<Decimal>,
-    ) -> Result<Order, Error>;
+        symbol: &Symbol,
+        client_order_id: &str,
+    ) -> Result<Option<Order>, Error>;
+
+    /// 交易对当前未完结的订单
+    async fn get_open_orders(&self, symbol: &Symbol) -> Result<Vec<Order>, Error>;
 
-    async fn cancel_order(&self, order_id: &str) -> Result<(), Error>;
+    /// 撤销交易对的全部挂单，返回被撤销的订单
+    async fn cancel_all(&self, symbol: &Symbol) -> Result<Vec<Order>, Error>;
 
+    /// 交易对自 `since` 起的成交记录，手续费按 `Fill::fee_asset` 计价
+    async fn get_my_trades(
+        &self,
+        symbol: &Symbol,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Fill>, Error>;
+
+    /// 交易对的下单规则
+    async fn get_exchange_info(&self, symbol: &Symbol) -> Result<SymbolInfo, Error>;
+
+    /// 交易对的下单规则，已载入过的直接返回缓存，不再请求交易所
+    async fn symbol_info(&self, symbol: &Symbol) -> Result<SymbolInfo, Error> {
+        self.get_exchange_info(symbol).await
+    }
+
+    /// 按交易对返回持仓，按资产计的余额由适配器换算为交易对
     async fn get_positions(&self) -> Result<Vec<Position>, Error>;
 
+    /// 按资产返回可用余额，如 `BTC`、`USDT`
     async fn get_balance(&self) -> Result<HashMap<String, Decimal>, Error>;
+
+    /// 合约交易所配置的持仓模式；现货返回 `None`，不能开空
+    fn futures_mode(&self) -> Option<PositionMode> {
+        None
+    }
+}
+
+/// 按配置中的 `exchange` 创建交易所连接：`binance` 为现货，`binance_futures` 为 U 本位合约
+pub fn create_exchange(config: Arc<TradingConfig>) -> Result<Arc<dyn Exchange>, Error> {
+    let notifier = DingTalkBot::new(
+        config.notification.dingtalk.webhook.clone(),
+        config.notification.dingtalk.secret.clone(),
+    );
+    match config.exchange.as_str() {
+        "binance" => {
+            let exchange = BinanceExchange::new(config).with_notifier(notifier);
+            Ok(Arc::new(RetryExchange::new(exchange)))
+        }
+        "binance_futures" => {
+            let exchange = BinanceFuturesExchange::new(config).with_notifier(notifier);
+            Ok(Arc::new(RetryExchange::new(exchange)))
+        }
+        other => Err(Error::ConfigError(format!(
+            "Unsupported exchange: {}",
+            other
+        ))),
+    }
 }
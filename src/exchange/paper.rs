@@ -0,0 +1,755 @@
+// src/exchange/paper.rs
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::warn;
+
+use super::Exchange;
+use crate::error::Error;
+use crate::types::{
+    EquityPoint, FeeAsset, Fill, Kline, Order, OrderKind, OrderRequest, OrderStatus, Position,
+    PositionSide, Side, Symbol, SymbolInfo, TimeInForce,
+};
+
+/// 支持的订单类型，触发后再挂限价的类型不支持
+const SUPPORTED_KINDS: [OrderKind; 5] = [
+    OrderKind::Market,
+    OrderKind::Limit,
+    OrderKind::StopLoss,
+    OrderKind::TakeProfit,
+    OrderKind::LimitMaker,
+];
+
+/// 模拟撮合的纸面交易所
+///
+/// 市价单按最新K线收盘价或最新报价成交，并计入滑点和手续费；
+/// 限价单挂起直到价格穿越，止损、止盈单触发后按市价成交。
+/// 不支持触发后再挂限价的 `StopLossLimit` 和 `TakeProfitLimit`。
+pub struct PaperExchange {
+    quote_asset: String,
+    fee_rate: Decimal,
+    slippage: Decimal,
+    state: Mutex<PaperState>,
+}
+
+#[derive(Default)]
+struct PaperState {
+    cash: Decimal,
+    positions: HashMap<Symbol, PaperPosition>,
+    open_orders: Vec<RestingOrder>,
+    /// 全部订单的最新状态，供 `get_order` 查询
+    orders: HashMap<String, Order>,
+    last_bars: HashMap<Symbol, Kline>,
+    fills: Vec<Fill>,
+    next_order_id: u64,
+}
+
+struct PaperPosition {
+    size: Decimal,
+    entry_price: Decimal,
+}
+
+#[derive(Clone)]
+struct RestingOrder {
+    order_id: String,
+    request: OrderRequest,
+}
+
+impl RestingOrder {
+    /// 按K线价格区间判断是否触发，返回成交价（未计滑点）
+    fn trigger_price(&self, kline: &Kline) -> Option<Decimal> {
+        let request = &self.request;
+        // 限价单看限价，止损止盈单看触发价
+        let price = request.price.or(request.trigger_price)?;
+        // 价格下行时触发：买入限价、卖出止损、买入止盈
+        let falling = match request.kind {
+            OrderKind::Limit | OrderKind::LimitMaker | OrderKind::TakeProfit => {
+                request.side == Side::Buy
+            }
+            OrderKind::StopLoss => request.side == Side::Sell,
+            _ => return None,
+        };
+
+        // 跳空时按开盘价成交
+        if falling && kline.low <= price {
+            Some(price.min(kline.open))
+        } else if !falling && kline.high >= price {
+            Some(price.max(kline.open))
+        } else {
+            None
+        }
+    }
+}
+
+impl PaperExchange {
+    pub fn new(initial_cash: Decimal) -> Self {
+        Self {
+            quote_asset: "USDT".to_string(),
+            fee_rate: Decimal::ZERO,
+            slippage: Decimal::ZERO,
+            state: Mutex::new(PaperState {
+                cash: initial_cash,
+                ..Default::default()
+            }),
+        }
+    }
+
+    pub fn with_quote_asset(mut self, quote_asset: impl Into<String>) -> Self {
+        self.quote_asset = quote_asset.into();
+        self
+    }
+
+    /// 按成交额收取的手续费率
+    pub fn with_fee_rate(mut self, fee_rate: Decimal) -> Self {
+        self.fee_rate = fee_rate;
+        self
+    }
+
+    /// 市价单和止损止盈单的滑点比例：买入价上浮、卖出价下调
+    pub fn with_slippage(mut self, slippage: Decimal) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    /// 推进到新的一根K线：先按该K线的价格区间撮合挂单，再更新最新价
+    ///
+    /// 返回本次触发的成交。
+    pub fn on_kline(&self, symbol: &Symbol, kline: &Kline) -> Vec<Fill> {
+        let mut state = self.state.lock().expect("paper exchange lock poisoned");
+
+        let (triggered, resting): (Vec<_>, Vec<_>) = state
+            .open_orders
+            .drain(..)
+            .partition(|o| &o.request.symbol == symbol && o.trigger_price(kline).is_some());
+        state.open_orders = resting;
+
+        let mut fills = Vec::new();
+        for order in triggered {
+            let request = &order.request;
+            let Some(mut price) = order.trigger_price(kline) else {
+                continue;
+            };
+            let mut size = request.size;
+            if request.trigger_price.is_some() {
+                // 止损止盈触发后按市价成交
+                price = self.slipped(request.side, price);
+                if request.side == Side::Sell {
+                    // 最多卖出当前持仓
+                    size = size.min(Self::held(&state, symbol));
+                }
+            }
+
+            let status = if size <= Decimal::ZERO {
+                OrderStatus::Expired
+            } else {
+                let fill = self.new_fill(
+                    &order.order_id,
+                    symbol,
+                    request.side,
+                    size,
+                    price,
+                    kline.timestamp,
+                );
+                match Self::apply_fill(&mut state, fill.clone()) {
+                    Ok(()) => {
+                        if let Some(o) = state.orders.get_mut(&order.order_id) {
+                            o.record_fill(fill.clone());
+                        }
+                        fills.push(fill);
+                        // 持仓不足时剩余部分过期
+                        if size < request.size {
+                            OrderStatus::Expired
+                        } else {
+                            OrderStatus::Filled
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Paper order {} rejected on trigger: {}", order.order_id, e);
+                        OrderStatus::Rejected
+                    }
+                }
+            };
+            if let Some(o) = state.orders.get_mut(&order.order_id) {
+                o.status = status;
+            }
+        }
+
+        state.last_bars.insert(symbol.clone(), kline.clone());
+        fills
+    }
+
+    /// 以最新成交价推进行情，等价于一根开高低收相同的K线
+    pub fn on_ticker(
+        &self,
+        symbol: &Symbol,
+        price: Decimal,
+        timestamp: DateTime<Utc>,
+    ) -> Vec<Fill> {
+        self.on_kline(
+            symbol,
+            &Kline {
+                timestamp,
+                open: price,
+                high: price,
+                low: price,
+                close: price,
+                volume: Decimal::ZERO,
+            },
+        )
+    }
+
+    /// 以最新价格计算的账户权益
+    pub fn equity_point(&self, timestamp: DateTime<Utc>) -> EquityPoint {
+        let state = self.state.lock().expect("paper exchange lock poisoned");
+        let position_value: Decimal = state
+            .positions
+            .iter()
+            .map(|(symbol, p)| p.size * Self::last_price(&state, symbol).unwrap_or(p.entry_price))
+            .sum();
+
+        EquityPoint {
+            timestamp,
+            equity: state.cash + position_value,
+            cash: state.cash,
+            position_value,
+        }
+    }
+
+    pub fn fills(&self) -> Vec<Fill> {
+        let state = self.state.lock().expect("paper exchange lock poisoned");
+        state.fills.clone()
+    }
+
+    /// 尚未成交的挂单
+    pub fn open_orders(&self) -> Vec<Order> {
+        let state = self.state.lock().expect("paper exchange lock poisoned");
+        state
+            .open_orders
+            .iter()
+            .filter_map(|o| state.orders.get(&o.order_id).cloned())
+            .collect()
+    }
+
+    /// 撤销满足条件的挂单，返回被撤销的订单
+    fn cancel_where(&self, predicate: impl Fn(&RestingOrder) -> bool) -> Vec<Order> {
+        let mut state = self.state.lock().expect("paper exchange lock poisoned");
+        let (canceled, resting): (Vec<_>, Vec<_>) =
+            state.open_orders.drain(..).partition(|o| predicate(o));
+        state.open_orders = resting;
+
+        canceled
+            .iter()
+            .filter_map(|o| {
+                let order = state.orders.get_mut(&o.order_id)?;
+                order.status = OrderStatus::Canceled;
+                Some(order.clone())
+            })
+            .collect()
+    }
+
+    fn last_price(state: &PaperState, symbol: &Symbol) -> Option<Decimal> {
+        state.last_bars.get(symbol).map(|k| k.close)
+    }
+
+    fn held(state: &PaperState, symbol: &Symbol) -> Decimal {
+        state
+            .positions
+            .get(symbol)
+            .map(|p| p.size)
+            .unwrap_or_default()
+    }
+
+    fn slipped(&self, side: Side, price: Decimal) -> Decimal {
+        match side {
+            Side::Buy => price * (Decimal::ONE + self.slippage),
+            Side::Sell => price * (Decimal::ONE - self.slippage),
+        }
+    }
+
+    fn new_fill(
+        &self,
+        order_id: &str,
+        symbol: &Symbol,
+        side: Side,
+        size: Decimal,
+        price: Decimal,
+        timestamp: DateTime<Utc>,
+    ) -> Fill {
+        Fill {
+            order_id: order_id.to_string(),
+            symbol: symbol.clone(),
+            side,
+            price,
+            size,
+            fee: size * price * self.fee_rate,
+            fee_asset: FeeAsset::Quote,
+            position_side: PositionSide::Both,
+            timestamp,
+        }
+    }
+
+    fn apply_fill(state: &mut PaperState, fill: Fill) -> Result<(), Error> {
+        let Fill {
+            ref symbol,
+            side,
+            size,
+            price,
+            fee,
+            ..
+        } = fill;
+        let notional = size * price;
+
+        match side {
+            Side::Buy => {
+                if state.cash < notional + fee {
+                    return Err(Error::ApiError(format!(
+                        "insufficient balance: need {}, have {}",
+                        notional + fee,
+                        state.cash
+                    )));
+                }
+                state.cash -= notional + fee;
+
+                let position = state
+                    .positions
+                    .entry(symbol.clone())
+                    .or_insert(PaperPosition {
+                        size: Decimal::ZERO,
+                        entry_price: Decimal::ZERO,
+                    });
+                let total = position.size + size;
+                position.entry_price = (position.entry_price * position.size + notional) / total;
+                position.size = total;
+            }
+            Side::Sell => {
+                let held = Self::held(state, symbol);
+                if size > held {
+                    return Err(Error::ApiError(format!(
+                        "insufficient position: sell {}, have {}",
+                        size, held
+                    )));
+                }
+                state.cash += notional - fee;
+
+                if size == held {
+                    state.positions.remove(symbol);
+                } else if let Some(position) = state.positions.get_mut(symbol) {
+                    position.size -= size;
+                }
+            }
+        }
+
+        state.fills.push(fill);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Exchange for PaperExchange {
+    async fn place_order(&self, request: &OrderRequest) -> Result<Order, Error> {
+        request.validate()?;
+        if !SUPPORTED_KINDS.contains(&request.kind) {
+            return Err(Error::invalid_order(format!(
+                "paper exchange does not support {} orders",
+                request.kind
+            )));
+        }
+        if request.has_futures_options() {
+            return Err(Error::invalid_order(
+                "paper exchange does not support futures order options",
+            ));
+        }
+
+        let symbol = &request.symbol;
+        let mut state = self.state.lock().expect("paper exchange lock poisoned");
+        let bar = state
+            .last_bars
+            .get(symbol)
+            .cloned()
+            .ok_or_else(|| Error::ApiError(format!("no market data for {}", symbol)))?;
+
+        // 限价单价格已优于最新价时可按最新价立即成交
+        let marketable = match (request.price, request.side) {
+            (Some(limit), Side::Buy) => limit >= bar.close,
+            (Some(limit), Side::Sell) => limit <= bar.close,
+            (None, _) => false,
+        };
+        if request.kind == OrderKind::LimitMaker && marketable {
+            return Err(Error::invalid_order(format!(
+                "limit maker order at {} would immediately match",
+                bar.close
+            )));
+        }
+
+        state.next_order_id += 1;
+        let order_id = format!("paper-{}", state.next_order_id);
+
+        // 可立即成交的价格；None 表示不立即成交
+        let fill_price = match request.kind {
+            OrderKind::Market => Some(self.slipped(request.side, bar.close)),
+            OrderKind::Limit if marketable => Some(bar.close),
+            _ => None,
+        };
+
+        let mut order = Order::new(order_id.clone(), request, OrderStatus::New);
+        match fill_price {
+            Some(fill_price) => {
+                let fill = self.new_fill(
+                    &order_id,
+                    symbol,
+                    request.side,
+                    request.size,
+                    fill_price,
+                    bar.timestamp,
+                );
+                Self::apply_fill(&mut state, fill.clone())?;
+                order.price = Some(fill_price);
+                order.record_fill(fill);
+            }
+            // IOC、FOK 不能立即成交时直接过期
+            None if matches!(
+                request.time_in_force,
+                Some(TimeInForce::Ioc | TimeInForce::Fok)
+            ) =>
+            {
+                order.status = OrderStatus::Expired;
+            }
+            None => state.open_orders.push(RestingOrder {
+                order_id: order_id.clone(),
+                request: request.clone(),
+            }),
+        }
+
+        state.orders.insert(order_id, order.clone());
+        Ok(order)
+    }
+
+    async fn cancel_order(&self, symbol: &Symbol, order_id: &str) -> Result<(), Error> {
+        let canceled = self.cancel_where(|o| &o.request.symbol == symbol && o.order_id == order_id);
+        if canceled.is_empty() {
+            return Err(Error::ApiError(format!("unknown order {}", order_id)));
+        }
+        Ok(())
+    }
+
+    async fn get_order(&self, symbol: &Symbol, order_id: &str) -> Result<Order, Error> {
+        let state = self.state.lock().expect("paper exchange lock poisoned");
+        state
+            .orders
+            .get(order_id)
+            .filter(|o| &o.symbol == symbol)
+            .cloned()
+            .ok_or_else(|| Error::ApiError(format!("unknown order {}", order_id)))
+    }
+
+    async fn find_order(
+        &self,
+        symbol: &Symbol,
+        client_order_id: &str,
+    ) -> Result<Option<Order>, Error> {
+        let state = self.state.lock().expect("paper exchange lock poisoned");
+        Ok(state
+            .orders
+            .values()
+            .find(|o| &o.symbol == symbol && o.client_order_id.as_deref() == Some(client_order_id))
+            .cloned())
+    }
+
+    async fn get_open_orders(&self, symbol: &Symbol) -> Result<Vec<Order>, Error> {
+        Ok(self
+            .open_orders()
+            .into_iter()
+            .filter(|o| &o.symbol == symbol)
+            .collect())
+    }
+
+    async fn cancel_all(&self, symbol: &Symbol) -> Result<Vec<Order>, Error> {
+        Ok(self.cancel_where(|o| &o.request.symbol == symbol))
+    }
+
+    async fn get_my_trades(
+        &self,
+        symbol: &Symbol,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Fill>, Error> {
+        let state = self.state.lock().expect("paper exchange lock poisoned");
+        Ok(state
+            .fills
+            .iter()
+            .filter(|f| &f.symbol == symbol && since.is_none_or(|t| f.timestamp >= t))
+            .cloned()
+            .collect())
+    }
+
+    /// 模拟撮合不限制数量和价格精度
+    async fn get_exchange_info(&self, symbol: &Symbol) -> Result<SymbolInfo, Error> {
+        Ok(SymbolInfo {
+            order_kinds: SUPPORTED_KINDS.to_vec(),
+            ..SymbolInfo::new(symbol.clone())
+        })
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>, Error> {
+        let state = self.state.lock().expect("paper exchange lock poisoned");
+        Ok(state
+            .positions
+            .iter()
+            .map(|(symbol, p)| {
+                let current_price = Self::last_price(&state, symbol).unwrap_or(p.entry_price);
+                Position {
+                    symbol: symbol.clone(),
+                    size: p.size,
+                    entry_price: p.entry_price,
+                    current_price,
+                    unrealized_pnl: (current_price - p.entry_price) * p.size,
+                    position_side: PositionSide::Both,
+                    liquidation_price: None,
+                }
+            })
+            .collect())
+    }
+
+    /// 余额按资产计：持仓以基础资产为键，现金以计价资产为键
+    async fn get_balance(&self) -> Result<HashMap<String, Decimal>, Error> {
+        let state = self.state.lock().expect("paper exchange lock poisoned");
+        let mut balances: HashMap<String, Decimal> = state
+            .positions
+            .iter()
+            .map(|(symbol, p)| (symbol.base().to_string(), p.size))
+            .collect();
+        balances.insert(self.quote_asset.clone(), state.cash);
+        Ok(balances)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_symbol;
+    use chrono::TimeZone;
+    use rust_decimal_macros::dec;
+
+    fn bar(open: Decimal, high: Decimal, low: Decimal, close: Decimal) -> Kline {
+        Kline {
+            timestamp: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            open,
+            high,
+            low,
+            close,
+            volume: dec!(1),
+        }
+    }
+
+    fn exchange() -> PaperExchange {
+        let exchange = PaperExchange::new(dec!(1000))
+            .with_fee_rate(dec!(0.001))
+            .with_slippage(dec!(0.01));
+        exchange.on_kline(
+            &test_symbol("BTCUSDT"),
+            &bar(dec!(100), dec!(100), dec!(100), dec!(100)),
+        );
+        exchange
+    }
+
+    #[tokio::test]
+    async fn test_market_order_slippage_and_fees() -> Result<(), Error> {
+        let exchange = exchange();
+        let btc = test_symbol("BTCUSDT");
+
+        let buy = exchange
+            .place_order(&OrderRequest::market(&btc, Side::Buy, dec!(2)))
+            .await?;
+        assert_eq!(buy.status, OrderStatus::Filled);
+        assert_eq!(buy.price, Some(dec!(101)));
+
+        // 202 成交额 + 0.202 手续费
+        let balance = exchange.get_balance().await?;
+        assert_eq!(balance["USDT"], dec!(797.798));
+        assert_eq!(balance["BTC"], dec!(2));
+
+        exchange.on_ticker(&btc, dec!(110), Utc::now());
+        let sell = exchange
+            .place_order(&OrderRequest::market(&btc, Side::Sell, dec!(2)))
+            .await?;
+        assert_eq!(sell.price, Some(dec!(108.9)));
+        assert!(exchange.get_positions().await?.is_empty());
+        assert_eq!(exchange.fills().len(), 2);
+
+        // 持仓不足时拒绝卖出
+        assert!(exchange
+            .place_order(&OrderRequest::market(&btc, Side::Sell, dec!(1)))
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resting_orders_fill_when_crossed() -> Result<(), Error> {
+        let exchange = exchange();
+        let btc = test_symbol("BTCUSDT");
+
+        let limit = exchange
+            .place_order(&OrderRequest::limit(&btc, Side::Buy, dec!(1), dec!(95)))
+            .await?;
+        assert_eq!(limit.status, OrderStatus::New);
+
+        // 未触及限价
+        exchange.on_kline(
+            &test_symbol("BTCUSDT"),
+            &bar(dec!(99), dec!(101), dec!(96), dec!(97)),
+        );
+        assert_eq!(exchange.open_orders().len(), 1);
+
+        exchange.on_kline(
+            &test_symbol("BTCUSDT"),
+            &bar(dec!(97), dec!(98), dec!(94), dec!(96)),
+        );
+        assert!(exchange.open_orders().is_empty());
+        let fills = exchange.fills();
+        assert_eq!(fills[0].order_id, limit.order_id);
+        assert_eq!(fills[0].price, dec!(95));
+
+        let stop = exchange
+            .place_order(&OrderRequest::stop_loss(
+                &btc,
+                Side::Sell,
+                dec!(1),
+                dec!(90),
+            ))
+            .await?;
+        assert_ne!(stop.order_id, limit.order_id);
+
+        // 跳空低开，止损按开盘价减滑点成交
+        exchange.on_kline(
+            &test_symbol("BTCUSDT"),
+            &bar(dec!(85), dec!(86), dec!(84), dec!(85)),
+        );
+        let fills = exchange.fills();
+        assert_eq!(fills[1].side, Side::Sell);
+        assert_eq!(fills[1].price, dec!(84.15));
+        assert!(exchange.get_positions().await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancel_removes_order() -> Result<(), Error> {
+        let exchange = exchange();
+        let btc = test_symbol("BTCUSDT");
+
+        let order = exchange
+            .place_order(&OrderRequest::limit(&btc, Side::Sell, dec!(1), dec!(120)))
+            .await?;
+        exchange.cancel_order(&btc, &order.order_id).await?;
+        assert!(exchange.open_orders().is_empty());
+
+        // 已撤销的订单不会再成交，也不能重复撤销
+        exchange.on_kline(
+            &test_symbol("BTCUSDT"),
+            &bar(dec!(120), dec!(130), dec!(119), dec!(125)),
+        );
+        assert!(exchange.fills().is_empty());
+        assert!(exchange.cancel_order(&btc, &order.order_id).await.is_err());
+
+        // 按交易对撤销全部挂单，不影响其他交易对
+        let eth = test_symbol("ETHUSDT");
+        exchange.on_kline(&eth, &bar(dec!(10), dec!(10), dec!(10), dec!(10)));
+        exchange
+            .place_order(&OrderRequest::limit(&btc, Side::Buy, dec!(1), dec!(90)))
+            .await?;
+        exchange
+            .place_order(&OrderRequest::limit(&btc, Side::Buy, dec!(1), dec!(80)))
+            .await?;
+        exchange
+            .place_order(&OrderRequest::limit(&eth, Side::Buy, dec!(1), dec!(9)))
+            .await?;
+        assert_eq!(exchange.get_open_orders(&btc).await?.len(), 2);
+
+        let canceled = exchange.cancel_all(&btc).await?;
+        assert_eq!(canceled.len(), 2);
+        assert!(canceled.iter().all(|o| o.status == OrderStatus::Canceled));
+        assert!(exchange.get_open_orders(&btc).await?.is_empty());
+        assert_eq!(exchange.get_open_orders(&eth).await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_order_kinds() -> Result<(), Error> {
+        let exchange = exchange();
+        let btc = test_symbol("BTCUSDT");
+
+        // 会立即成交的 LIMIT_MAKER 被拒绝，不可立即成交的 IOC 直接过期
+        assert!(matches!(
+            exchange
+                .place_order(&OrderRequest::limit_maker(
+                    &btc,
+                    Side::Buy,
+                    dec!(1),
+                    dec!(101)
+                ))
+                .await,
+            Err(Error::InvalidOrder(_))
+        ));
+        let ioc = exchange
+            .place_order(
+                &OrderRequest::limit(&btc, Side::Buy, dec!(1), dec!(95))
+                    .with_time_in_force(TimeInForce::Ioc),
+            )
+            .await?;
+        assert_eq!(ioc.status, OrderStatus::Expired);
+        assert!(exchange.open_orders().is_empty());
+
+        // 触发后再挂限价的订单不支持
+        assert!(matches!(
+            exchange
+                .place_order(&OrderRequest::stop_loss_limit(
+                    &btc,
+                    Side::Sell,
+                    dec!(1),
+                    dec!(90),
+                    dec!(89)
+                ))
+                .await,
+            Err(Error::InvalidOrder(_))
+        ));
+
+        // 买入止盈在价格回落时触发，按市价加滑点成交
+        let take_profit = exchange
+            .place_order(&OrderRequest::take_profit(
+                &btc,
+                Side::Buy,
+                dec!(1),
+                dec!(90),
+            ))
+            .await?;
+        assert_eq!(take_profit.trigger_price, Some(dec!(90)));
+        exchange.on_kline(&btc, &bar(dec!(99), dec!(120), dec!(91), dec!(110)));
+        assert_eq!(exchange.open_orders().len(), 1);
+        exchange.on_kline(&btc, &bar(dec!(95), dec!(96), dec!(89), dec!(90)));
+        let fills = exchange.fills();
+        assert_eq!(fills[0].side, Side::Buy);
+        assert_eq!(fills[0].price, dec!(90.9));
+
+        // 卖出止盈在价格上涨时触发
+        exchange
+            .place_order(&OrderRequest::take_profit(
+                &btc,
+                Side::Sell,
+                dec!(1),
+                dec!(100),
+            ))
+            .await?;
+        exchange.on_kline(&btc, &bar(dec!(95), dec!(101), dec!(94), dec!(100)));
+        let fills = exchange.fills();
+        assert_eq!(fills[1].side, Side::Sell);
+        assert_eq!(fills[1].price, dec!(99));
+        assert!(exchange.get_positions().await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -1,64 +1,100 @@
 // src/exchange/tushare.rs
-use super::Exchange;
+use super::{Exchange, PaperExchange};
 
-use crate::data::TushareDataFetcher;
+use crate::data::{DataFetcher, TushareDataFetcher};
 use crate::error::Error; // 自定义错误类型
-use crate::types::{Kline, Order, OrderType, Position}; // 假设这些类型在 types.rs 中定义
+use crate::types::{Fill, Order, OrderRequest, Position, Symbol, SymbolInfo};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use std::collections::HashMap;
 
+/// A 股模拟账户：行情来自 Tushare，撮合交给 `PaperExchange`
 pub struct TushareExchange {
     data_fetcher: TushareDataFetcher,
-    balances: HashMap<String, Decimal>,   // 模拟账户余额
-    positions: HashMap<String, Position>, // 模拟持仓
+    paper: PaperExchange,
 }
 
 impl TushareExchange {
-    pub fn new(api_key: String) -> Self {
+    pub fn new(api_key: String, initial_cash: Decimal) -> Self {
         TushareExchange {
             data_fetcher: TushareDataFetcher::new(api_key),
-            balances: HashMap::new(),
-            positions: HashMap::new(),
+            paper: PaperExchange::new(initial_cash).with_quote_asset("CNY"),
         }
     }
+
+    /// 自定义撮合参数（手续费、滑点等）
+    pub fn with_paper_exchange(mut self, paper: PaperExchange) -> Self {
+        self.paper = paper;
+        self
+    }
+
+    /// 拉取最新收盘价推进模拟行情，触发已触及价格的挂单
+    pub async fn refresh_price(&self, symbol: &Symbol) -> Result<Decimal, Error> {
+        let price = self.data_fetcher.fetch_ticker(symbol).await?;
+        self.paper.on_ticker(symbol, price, Utc::now());
+        Ok(price)
+    }
 }
 
 #[async_trait]
 impl Exchange for TushareExchange {
-    // 模拟下单方法
-    async fn place_order(
+    async fn place_order(&self, request: &OrderRequest) -> Result<Order, Error> {
+        // 下单前用最新收盘价更新行情
+        self.refresh_price(&request.symbol).await?;
+        self.paper.place_order(request).await
+    }
+
+    async fn cancel_order(&self, symbol: &Symbol, order_id: &str) -> Result<(), Error> {
+        self.paper.cancel_order(symbol, order_id).await
+    }
+
+    async fn get_order(&self, symbol: &Symbol, order_id: &str) -> Result<Order, Error> {
+        self.paper.get_order(symbol, order_id).await
+    }
+
+    async fn find_order(
         &self,
-        symbol: &str,
-        order_type: OrderType,
-        size: Decimal,
-        price: Option<Decimal>,
-    ) -> Result<Order, Error> {
-        // 模拟生成一个订单
-        Ok(Order {
-            order_id: "simulated_order_id".to_string(),
-            symbol: symbol.to_string(),
-            order_type,
-            size,
-            price: price, // 如果没有指定价格，使用默认值
-            status: "filled".to_string(),
-        })
+        symbol: &Symbol,
+        client_order_id: &str,
+    ) -> Result<Option<Order>, Error> {
+        self.paper.find_order(symbol, client_order_id).await
+    }
+
+    async fn get_open_orders(&self, symbol: &Symbol) -> Result<Vec<Order>, Error> {
+        self.paper.get_open_orders(symbol).await
+    }
+
+    async fn cancel_all(&self, symbol: &Symbol) -> Result<Vec<Order>, Error> {
+        self.paper.cancel_all(symbol).await
     }
 
-    // 模拟取消订单
-    async fn cancel_order(&self, order_id: &str) -> Result<(), Error> {
-        // 这里模拟取消订单逻辑，可以记录日志等操作
-        println!("Order {} canceled.", order_id);
-        Ok(())
+    async fn get_my_trades(
+        &self,
+        symbol: &Symbol,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Fill>, Error> {
+        self.paper.get_my_trades(symbol, since).await
+    }
+
+    /// A 股按手（100 股）买入，价格最小变动 0.01 元
+    async fn get_exchange_info(&self, symbol: &Symbol) -> Result<SymbolInfo, Error> {
+        let lot = Decimal::ONE_HUNDRED;
+        Ok(SymbolInfo {
+            tick_size: Some(Decimal::new(1, 2)),
+            step_size: Some(lot),
+            min_size: Some(lot),
+            ..self.paper.get_exchange_info(symbol).await?
+        })
     }
 
     // 返回模拟的持仓信息
     async fn get_positions(&self) -> Result<Vec<Position>, Error> {
-        Ok(self.positions.values().cloned().collect())
+        self.paper.get_positions().await
     }
 
     // 返回模拟的账户余额
     async fn get_balance(&self) -> Result<HashMap<String, Decimal>, Error> {
-        Ok(self.balances.clone())
+        self.paper.get_balance().await
     }
 }
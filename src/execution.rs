@@ -0,0 +1,758 @@
+// src/execution.rs
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use tracing::{error, info, warn};
+
+use crate::config::PositionMode;
+use crate::error::Error;
+use crate::exchange::Exchange;
+use crate::notification::dingtalk::DingTalkBot;
+use crate::risk::{RiskDecision, RiskManager};
+use crate::strategy::Strategy;
+use crate::types::{Order, OrderKind, OrderRequest, Position, PositionSide, Side, Signal, Symbol};
+
+// 交易对不支持市价止损时改挂止损限价单，限价低于触发价的比例，留出成交空间
+const STOP_LIMIT_OFFSET: Decimal = dec!(0.005);
+
+/// 将策略信号转换为订单，实盘循环和回测共用同一条决策路径
+pub struct SignalExecutor<'a> {
+    exchange: &'a dyn Exchange,
+    strategy: &'a dyn Strategy,
+    risk_manager: &'a RiskManager,
+    notifier: Option<DingTalkBot>,
+    signal_time: Option<DateTime<Utc>>,
+}
+
+impl<'a> SignalExecutor<'a> {
+    pub fn new(
+        exchange: &'a dyn Exchange,
+        strategy: &'a dyn Strategy,
+        risk_manager: &'a RiskManager,
+    ) -> Self {
+        Self {
+            exchange,
+            strategy,
+            risk_manager,
+            notifier: None,
+            signal_time: None,
+        }
+    }
+
+    /// 止损单下单失败时通过钉钉告警
+    pub fn with_notifier(mut self, notifier: DingTalkBot) -> Self {
+        self.notifier = Some(notifier);
+        self
+    }
+
+    /// 触发信号的K线时间，订单按下单意图生成客户端订单号，重启后重发可在交易所查重
+    pub fn with_signal_time(mut self, signal_time: DateTime<Utc>) -> Self {
+        self.signal_time = Some(signal_time);
+        self
+    }
+
+    pub async fn execute(
+        &self,
+        symbol: &Symbol,
+        signal: Signal,
+        current_price: Decimal,
+    ) -> Result<(), Error> {
+        // 以最新价格标记持仓，风控按市值计算敞口
+        self.risk_manager.portfolio().mark(symbol, current_price);
+
+        // 上次止损单下单失败的持仓先补挂止损
+        self.retry_pending_stop(symbol, &signal).await?;
+
+        // 根据信号执行交易
+        match signal {
+            Signal::Buy => {
+                self.handle_buy_signal(symbol, current_price).await?;
+            }
+            Signal::Sell => {
+                self.handle_sell_signal(symbol, current_price).await?;
+            }
+            Signal::Hold => {
+                info!("Hold position for {}", symbol);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_buy_signal(&self, symbol: &Symbol, price: Decimal) -> Result<(), Error> {
+        // 持有空头时先平空
+        if let Some(position) = self.position(symbol, Side::Sell) {
+            self.close(symbol, position.size).await?;
+        }
+        self.open(symbol, Side::Buy, price).await
+    }
+
+    async fn handle_sell_signal(&self, symbol: &Symbol, price: Decimal) -> Result<(), Error> {
+        // 从持仓账本获取当前多头持仓
+        match self.position(symbol, Side::Buy) {
+            Some(position) => self.close(symbol, position.size).await?,
+            None => info!("No position to sell for {}", symbol),
+        }
+
+        // 合约平多后反手开空，现货只平仓
+        if self.exchange.futures_mode().is_some() {
+            self.open(symbol, Side::Sell, price).await?;
+        }
+        Ok(())
+    }
+
+    /// `entry` 方向开出的持仓：双向持仓模式按持仓方向分开记账，其余看净持仓的正负
+    fn position(&self, symbol: &Symbol, entry: Side) -> Option<Position> {
+        let portfolio = self.risk_manager.portfolio();
+        match self.exchange.futures_mode() {
+            Some(PositionMode::Hedge) => portfolio.position_on(symbol, hedge_side(entry)),
+            _ => portfolio
+                .position_on(symbol, PositionSide::Both)
+                .filter(|p| match entry {
+                    Side::Buy => p.size > Decimal::ZERO,
+                    Side::Sell => p.size < Decimal::ZERO,
+                }),
+        }
+    }
+
+    /// 按 `entry` 方向开仓（买入开多、卖出开空），成交后挂止损
+    async fn open(&self, symbol: &Symbol, entry: Side, price: Decimal) -> Result<(), Error> {
+        // 计算建议仓位大小
+        let size = self.strategy.calculate_position_size(price);
+
+        // 风险检查
+        if let RiskDecision::Rejected(reason) = self.risk_manager.check_risk(symbol, size, price)? {
+            info!(
+                "{} signal on {} rejected by risk check: {}",
+                entry, symbol, reason
+            );
+            return Ok(());
+        }
+
+        let request = self.futures_order(OrderRequest::market(symbol, entry, size), entry, false);
+        let order = self.place(request, "open").await?;
+
+        info!("Open {} order executed: {:?}", entry, order);
+        // 交易所可能按步长取整数量，且可能以基础资产扣手续费，止损以实际到账数量为准
+        let filled = order.net_filled_size();
+        self.track(order)?;
+        if filled.is_zero() {
+            return Ok(());
+        }
+
+        // 设置止损：多头跌破、空头涨破止损价后平仓
+        let stop_loss = self.risk_manager.calculate_stop_loss(price, entry);
+        self.protect(symbol, entry, filled, stop_loss, "stop").await
+    }
+
+    /// 市价平掉账本中的持仓，`size` 为带符号的持仓数量
+    async fn close(&self, symbol: &Symbol, size: Decimal) -> Result<(), Error> {
+        let entry = if size > Decimal::ZERO {
+            Side::Buy
+        } else {
+            Side::Sell
+        };
+
+        // 先撤掉挂着的止损等平仓方向的挂单，避免平仓后再次成交
+        self.cancel_open(symbol, entry.opposite()).await?;
+        self.risk_manager.take_pending_stop(symbol);
+
+        let request = self.futures_order(
+            OrderRequest::market(symbol, entry.opposite(), size.abs()),
+            entry,
+            true,
+        );
+        let order = self.place(request, "close").await?;
+
+        info!("Close order executed: {:?}", order);
+        self.track(order)
+    }
+
+    /// 合约按配置的持仓模式补全订单：双向持仓标明持仓方向，单向持仓的平仓单只减仓
+    fn futures_order(&self, request: OrderRequest, entry: Side, closing: bool) -> OrderRequest {
+        match self.exchange.futures_mode() {
+            None => request,
+            Some(PositionMode::Hedge) => request.with_position_side(hedge_side(entry)),
+            Some(PositionMode::OneWay) if closing => request.with_reduce_only(),
+            Some(PositionMode::OneWay) => request,
+        }
+    }
+
+    /// 有信号K线时间时按下单意图附上客户端订单号再下单
+    async fn place(&self, request: OrderRequest, purpose: &str) -> Result<Order, Error> {
+        let request = match self.signal_time {
+            Some(signal_time) => request.with_intent(signal_time, purpose),
+            None => request,
+        };
+        self.exchange.place_order(&request).await
+    }
+
+    /// 为持仓挂止损单；下单失败时告警并记下，下个周期重试
+    async fn protect(
+        &self,
+        symbol: &Symbol,
+        entry: Side,
+        size: Decimal,
+        trigger_price: Decimal,
+        purpose: &str,
+    ) -> Result<(), Error> {
+        match self
+            .place_stop_loss(symbol, entry, size, trigger_price, purpose)
+            .await
+        {
+            Ok(order) => {
+                info!("Stop loss order placed at {}", trigger_price);
+                self.track(order)
+            }
+            Err(e) => {
+                self.risk_manager
+                    .add_pending_stop(symbol, entry.opposite(), size, trigger_price);
+                self.alert(format!(
+                    "{} 止损单下单失败：{}，{} 持仓暂无止损保护，将在下个周期重试",
+                    symbol, e, size
+                ))
+                .await;
+                Ok(())
+            }
+        }
+    }
+
+    /// 交易对不支持市价止损时（如 Binance 现货的大部分交易对）改挂止损限价单，
+    /// 限价比触发价让出 `STOP_LIMIT_OFFSET`
+    async fn place_stop_loss(
+        &self,
+        symbol: &Symbol,
+        entry: Side,
+        size: Decimal,
+        trigger_price: Decimal,
+        purpose: &str,
+    ) -> Result<Order, Error> {
+        let side = entry.opposite();
+        let info = self.exchange.symbol_info(symbol).await?;
+        let request = if info.order_kinds.contains(&OrderKind::StopLoss) {
+            OrderRequest::stop_loss(symbol, side, size, trigger_price)
+        } else {
+            let price = match side {
+                Side::Sell => trigger_price * (Decimal::ONE - STOP_LIMIT_OFFSET),
+                Side::Buy => trigger_price * (Decimal::ONE + STOP_LIMIT_OFFSET),
+            };
+            OrderRequest::stop_loss_limit(symbol, side, size, trigger_price, price)
+        };
+        let request = self.futures_order(request, entry, true);
+        self.place(request, purpose).await
+    }
+
+    /// 重试之前失败的止损单，期间已平掉的部分不再保护
+    async fn retry_pending_stop(&self, symbol: &Symbol, signal: &Signal) -> Result<(), Error> {
+        let Some(pending) = self.risk_manager.take_pending_stop(symbol) else {
+            return Ok(());
+        };
+        let entry = pending.side.opposite();
+        // 本次信号会平掉这一方向的持仓，无需再补挂
+        let closing = matches!(
+            (entry, signal),
+            (Side::Buy, Signal::Sell) | (Side::Sell, Signal::Buy)
+        );
+        if closing {
+            return Ok(());
+        }
+
+        let held = self.position(symbol, entry).map(|p| p.size.abs());
+        let size = pending.size.min(held.unwrap_or_default());
+        if size <= Decimal::ZERO {
+            return Ok(());
+        }
+
+        info!("Retrying stop loss for {} {}", size, symbol);
+        // 与本次开仓的止损区分开，避免订单号重复
+        self.protect(symbol, entry, size, pending.trigger_price, "retry-stop")
+            .await
+    }
+
+    async fn alert(&self, message: String) {
+        error!("{}", message);
+        if let Some(notifier) = &self.notifier {
+            if let Err(e) = notifier.send_text(message).await {
+                warn!("Failed to send stop loss notification: {}", e);
+            }
+        }
+    }
+
+    /// 撤销交易对上 `side` 方向的挂单
+    async fn cancel_open(&self, symbol: &Symbol, side: Side) -> Result<(), Error> {
+        let orders = self.risk_manager.orders();
+        for order in orders.open_orders(symbol) {
+            if order.side != side {
+                continue;
+            }
+            if let Err(e) = self.exchange.cancel_order(symbol, &order.order_id).await {
+                warn!("Failed to cancel order {}: {}", order.order_id, e);
+                continue;
+            }
+            // 撤单前可能已部分成交，以交易所的最新状态为准
+            let latest = self.exchange.get_order(symbol, &order.order_id).await?;
+            self.track(latest)?;
+        }
+        Ok(())
+    }
+
+    /// 交给订单跟踪，新增成交计入持仓账本
+    fn track(&self, order: Order) -> Result<(), Error> {
+        let portfolio = self.risk_manager.portfolio();
+        for fill in self.risk_manager.orders().apply(order)? {
+            if let Some(pnl) = portfolio.record_fill(&fill) {
+                info!("Realized PnL on {}: {}", fill.symbol, pnl);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 双向持仓模式下 `entry` 方向对应的持仓方向
+fn hedge_side(entry: Side) -> PositionSide {
+    match entry {
+        Side::Buy => PositionSide::Long,
+        Side::Sell => PositionSide::Short,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+    use crate::exchange::BinanceExchange;
+    use crate::portfolio::{CostBasis, Portfolio};
+    use crate::rate_limit::RateLimiter;
+    use crate::types::{test_symbol, FeeAsset, Fill, Kline, OrderStatus, SymbolInfo};
+    use async_trait::async_trait;
+    use chrono::{DateTime, Utc};
+    use serde_json::json;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use wiremock::matchers::{body_string_contains, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    /// 每次买入固定数量的测试策略
+    struct FixedSize;
+
+    #[async_trait]
+    impl Strategy for FixedSize {
+        async fn generate_signal(
+            &self,
+            _symbol: &Symbol,
+            _data: &[Kline],
+        ) -> Result<Signal, Error> {
+            Ok(Signal::Hold)
+        }
+
+        fn calculate_position_size(&self, _price: Decimal) -> Decimal {
+            dec!(1)
+        }
+    }
+
+    /// 与 BTCUSDT 现货一致：只支持止损限价单，不支持 STOP_LOSS
+    async fn mock_spot(server: &MockServer) {
+        // 交易对规则只载入一次，下单取整和止损下单共用
+        Mock::given(method("GET"))
+            .and(path("/api/v3/exchangeInfo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "symbols": [{
+                    "symbol": "BTCUSDT",
+                    "status": "TRADING",
+                    "orderTypes": ["LIMIT", "LIMIT_MAKER", "MARKET", "STOP_LOSS_LIMIT", "TAKE_PROFIT_LIMIT"],
+                    "filters": [
+                        {"filterType": "PRICE_FILTER", "tickSize": "0.01000000"},
+                        {"filterType": "LOT_SIZE", "minQty": "0.00100000", "maxQty": "9000.00000000", "stepSize": "0.00100000"}
+                    ]
+                }]
+            })))
+            .expect(1)
+            .mount(server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order"))
+            .and(body_string_contains("type=MARKET"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "orderId": 1,
+                "status": "FILLED",
+                "executedQty": "1.00000000",
+                "cummulativeQuoteQty": "100.00000000",
+                "fills": [{
+                    "price": "100.00000000",
+                    "qty": "1.00000000",
+                    "commission": "0.00100000",
+                    "commissionAsset": "BTC"
+                }]
+            })))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    fn stop_order_response() -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(json!({
+            "orderId": 2,
+            "status": "NEW",
+            "executedQty": "0.00000000",
+            "cummulativeQuoteQty": "0.00000000",
+            "fills": []
+        }))
+    }
+
+    fn setup(server: &MockServer) -> (BinanceExchange, RiskManager) {
+        let exchange = BinanceExchange::new(Arc::new(test_config()))
+            .with_base_url(server.uri())
+            .with_rate_limiter(Arc::new(RateLimiter::binance_spot()));
+        let mut config = test_config();
+        config.risk_limits.stop_loss_rate = dec!(0.05);
+        let portfolio = Arc::new(Portfolio::new(CostBasis::AverageCost));
+        (exchange, RiskManager::new(Arc::new(config), portfolio))
+    }
+
+    #[tokio::test]
+    async fn test_buy_falls_back_to_stop_loss_limit() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        mock_spot(&server).await;
+        // 扣除 BTC 手续费后到账 0.999，止损以此为数量
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order"))
+            .and(body_string_contains(
+                "side=SELL&type=STOP_LOSS_LIMIT&quantity=0.999",
+            ))
+            .and(body_string_contains(
+                "&price=94.52&stopPrice=95&timeInForce=GTC",
+            ))
+            .respond_with(stop_order_response())
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let (exchange, risk_manager) = setup(&server);
+        let btc = test_symbol("BTCUSDT");
+        SignalExecutor::new(&exchange, &FixedSize, &risk_manager)
+            .execute(&btc, Signal::Buy, dec!(100))
+            .await?;
+
+        assert_eq!(
+            risk_manager.portfolio().position(&btc).map(|p| p.size),
+            Some(dec!(0.999))
+        );
+        let stops = risk_manager.orders().open_orders(&btc);
+        assert_eq!(stops.len(), 1);
+        assert_eq!(stops[0].kind, OrderKind::StopLossLimit);
+        assert_eq!(stops[0].status, OrderStatus::New);
+        assert!(risk_manager.take_pending_stop(&btc).is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_stop_loss_is_retried() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        mock_spot(&server).await;
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order"))
+            .and(body_string_contains("type=STOP_LOSS_LIMIT"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("Service Unavailable"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let (exchange, risk_manager) = setup(&server);
+        let btc = test_symbol("BTCUSDT");
+        let executor = SignalExecutor::new(&exchange, &FixedSize, &risk_manager);
+
+        // 买入成交后止损下单失败，信号本身不报错，持仓记为待保护
+        executor.execute(&btc, Signal::Buy, dec!(100)).await?;
+        assert!(risk_manager.orders().open_orders(&btc).is_empty());
+
+        // 下个周期补挂止损
+        Mock::given(method("POST"))
+            .and(path("/api/v3/order"))
+            .and(body_string_contains("type=STOP_LOSS_LIMIT&quantity=0.999"))
+            .respond_with(stop_order_response())
+            .expect(1)
+            .mount(&server)
+            .await;
+        executor.execute(&btc, Signal::Hold, dec!(101)).await?;
+        assert_eq!(risk_manager.orders().open_orders(&btc).len(), 1);
+
+        // 已补挂后不再重复下单
+        executor.execute(&btc, Signal::Hold, dec!(102)).await?;
+        assert_eq!(risk_manager.orders().open_orders(&btc).len(), 1);
+
+        Ok(())
+    }
+
+    /// 记录下单请求的合约交易所：市价单按固定价格立即成交，其余订单挂单
+    struct FuturesDouble {
+        mode: PositionMode,
+        price: Mutex<Decimal>,
+        requests: Mutex<Vec<OrderRequest>>,
+        orders: Mutex<HashMap<String, Order>>,
+        positions: Mutex<Vec<Position>>,
+    }
+
+    impl FuturesDouble {
+        fn new(mode: PositionMode) -> Self {
+            Self {
+                mode,
+                price: Mutex::new(dec!(100)),
+                requests: Mutex::new(Vec::new()),
+                orders: Mutex::new(HashMap::new()),
+                positions: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn set_price(&self, price: Decimal) {
+            *self.price.lock().unwrap() = price;
+        }
+
+        fn requests(&self) -> Vec<OrderRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait]
+    impl Exchange for FuturesDouble {
+        async fn place_order(&self, request: &OrderRequest) -> Result<Order, Error> {
+            request.validate()?;
+            let mut orders = self.orders.lock().unwrap();
+            let order_id = (orders.len() + 1).to_string();
+            let mut order = Order::new(order_id.clone(), request, OrderStatus::New);
+            if request.kind == OrderKind::Market {
+                order.record_fill(Fill {
+                    order_id: order_id.clone(),
+                    symbol: request.symbol.clone(),
+                    side: request.side,
+                    price: *self.price.lock().unwrap(),
+                    size: request.size,
+                    fee: Decimal::ZERO,
+                    fee_asset: FeeAsset::Quote,
+                    position_side: request.position_side.unwrap_or_default(),
+                    timestamp: Utc::now(),
+                });
+            }
+            orders.insert(order_id, order.clone());
+            self.requests.lock().unwrap().push(request.clone());
+            Ok(order)
+        }
+
+        async fn cancel_order(&self, _symbol: &Symbol, order_id: &str) -> Result<(), Error> {
+            let mut orders = self.orders.lock().unwrap();
+            if let Some(order) = orders.get_mut(order_id) {
+                order.status = OrderStatus::Canceled;
+            }
+            Ok(())
+        }
+
+        async fn get_order(&self, _symbol: &Symbol, order_id: &str) -> Result<Order, Error> {
+            let orders = self.orders.lock().unwrap();
+            orders
+                .get(order_id)
+                .cloned()
+                .ok_or_else(|| Error::invalid_order(format!("unknown order {}", order_id)))
+        }
+
+        async fn find_order(
+            &self,
+            _symbol: &Symbol,
+            _client_order_id: &str,
+        ) -> Result<Option<Order>, Error> {
+            Ok(None)
+        }
+
+        async fn get_open_orders(&self, _symbol: &Symbol) -> Result<Vec<Order>, Error> {
+            Ok(Vec::new())
+        }
+
+        async fn cancel_all(&self, _symbol: &Symbol) -> Result<Vec<Order>, Error> {
+            Ok(Vec::new())
+        }
+
+        async fn get_my_trades(
+            &self,
+            _symbol: &Symbol,
+            _since: Option<DateTime<Utc>>,
+        ) -> Result<Vec<Fill>, Error> {
+            Ok(Vec::new())
+        }
+
+        async fn get_exchange_info(&self, symbol: &Symbol) -> Result<SymbolInfo, Error> {
+            Ok(SymbolInfo::new(symbol.clone()))
+        }
+
+        async fn get_positions(&self) -> Result<Vec<Position>, Error> {
+            Ok(self.positions.lock().unwrap().clone())
+        }
+
+        async fn get_balance(&self) -> Result<HashMap<String, Decimal>, Error> {
+            Ok(HashMap::new())
+        }
+
+        fn futures_mode(&self) -> Option<PositionMode> {
+            Some(self.mode)
+        }
+    }
+
+    fn futures_risk_manager() -> RiskManager {
+        let mut config = test_config();
+        config.risk_limits.stop_loss_rate = dec!(0.05);
+        let portfolio = Arc::new(Portfolio::new(CostBasis::AverageCost).with_short_selling(true));
+        RiskManager::new(Arc::new(config), portfolio)
+    }
+
+    #[tokio::test]
+    async fn test_hedge_mode_reverses_into_short() -> Result<(), Error> {
+        let exchange = FuturesDouble::new(PositionMode::Hedge);
+        let risk_manager = futures_risk_manager();
+        let executor = SignalExecutor::new(&exchange, &FixedSize, &risk_manager);
+        let btc = test_symbol("BTCUSDT");
+
+        executor.execute(&btc, Signal::Buy, dec!(100)).await?;
+        exchange.set_price(dec!(110));
+        executor.execute(&btc, Signal::Sell, dec!(110)).await?;
+
+        let requests = exchange.requests();
+        let summary: Vec<_> = requests
+            .iter()
+            .map(|r| (r.side, r.kind, r.position_side, r.trigger_price))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Side::Buy, OrderKind::Market, Some(PositionSide::Long), None),
+                (
+                    Side::Sell,
+                    OrderKind::StopLoss,
+                    Some(PositionSide::Long),
+                    Some(dec!(95))
+                ),
+                // 撤掉多头止损后平多，再开空并在上方挂止损
+                (
+                    Side::Sell,
+                    OrderKind::Market,
+                    Some(PositionSide::Long),
+                    None
+                ),
+                (
+                    Side::Sell,
+                    OrderKind::Market,
+                    Some(PositionSide::Short),
+                    None
+                ),
+                (
+                    Side::Buy,
+                    OrderKind::StopLoss,
+                    Some(PositionSide::Short),
+                    Some(dec!(115.5))
+                ),
+            ]
+        );
+        // 双向持仓模式不使用只减仓
+        assert!(requests.iter().all(|r| !r.reduce_only));
+
+        let portfolio = risk_manager.portfolio();
+        assert_eq!(portfolio.position(&btc).map(|p| p.size), Some(dec!(-1)));
+        assert_eq!(portfolio.realized_pnl(), dec!(10));
+        let open = risk_manager.orders().open_orders(&btc);
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].side, Side::Buy);
+
+        // 价格回落后平空，空头盈利计入已实现盈亏
+        exchange.set_price(dec!(100));
+        executor.execute(&btc, Signal::Buy, dec!(100)).await?;
+        assert_eq!(portfolio.realized_pnl(), dec!(20));
+        assert_eq!(portfolio.position(&btc).map(|p| p.size), Some(dec!(1)));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hedge_mode_seeds_both_sides() -> Result<(), Error> {
+        let exchange = FuturesDouble::new(PositionMode::Hedge);
+        let btc = test_symbol("BTCUSDT");
+        let row = |position_side, size, entry_price| Position {
+            symbol: btc.clone(),
+            size,
+            entry_price,
+            current_price: dec!(100),
+            unrealized_pnl: Decimal::ZERO,
+            position_side,
+            liquidation_price: None,
+        };
+        // 同一交易对多空两边同时持仓，交易所分两行返回
+        *exchange.positions.lock().unwrap() = vec![
+            row(PositionSide::Long, dec!(1), dec!(90)),
+            row(PositionSide::Short, dec!(-2), dec!(110)),
+        ];
+
+        let risk_manager = futures_risk_manager();
+        let positions = exchange.get_positions().await?;
+        assert_eq!(risk_manager.mismatches(&positions).len(), 2);
+        risk_manager.seed(&exchange).await?;
+        assert!(risk_manager.mismatches(&positions).is_empty());
+
+        let portfolio = risk_manager.portfolio();
+        let long = portfolio.position_on(&btc, PositionSide::Long).unwrap();
+        let short = portfolio.position_on(&btc, PositionSide::Short).unwrap();
+        assert_eq!((long.size, long.entry_price), (dec!(1), dec!(90)));
+        assert_eq!((short.size, short.entry_price), (dec!(-2), dec!(110)));
+        assert_eq!(portfolio.position(&btc).map(|p| p.size), Some(dec!(-1)));
+
+        // 卖出信号只平多头一侧，空头持仓不受影响
+        let executor = SignalExecutor::new(&exchange, &FixedSize, &risk_manager);
+        executor.execute(&btc, Signal::Sell, dec!(100)).await?;
+        assert_eq!(
+            exchange.requests()[0],
+            OrderRequest::market(&btc, Side::Sell, dec!(1)).with_position_side(PositionSide::Long)
+        );
+        assert!(portfolio.position_on(&btc, PositionSide::Long).is_none());
+        assert_eq!(
+            portfolio
+                .position_on(&btc, PositionSide::Short)
+                .map(|p| p.size),
+            Some(dec!(-3))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_one_way_mode_closes_with_reduce_only() -> Result<(), Error> {
+        let exchange = FuturesDouble::new(PositionMode::OneWay);
+        let risk_manager = futures_risk_manager();
+        let executor = SignalExecutor::new(&exchange, &FixedSize, &risk_manager);
+        let btc = test_symbol("BTCUSDT");
+
+        // 空仓时卖出信号直接开空
+        executor.execute(&btc, Signal::Sell, dec!(100)).await?;
+        exchange.set_price(dec!(90));
+        executor.execute(&btc, Signal::Buy, dec!(90)).await?;
+
+        let summary: Vec<_> = exchange
+            .requests()
+            .iter()
+            .map(|r| (r.side, r.kind, r.reduce_only))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Side::Sell, OrderKind::Market, false),
+                // 止损和平仓单只减仓，不会开出反向仓位
+                (Side::Buy, OrderKind::StopLoss, true),
+                (Side::Buy, OrderKind::Market, true),
+                (Side::Buy, OrderKind::Market, false),
+                (Side::Sell, OrderKind::StopLoss, true),
+            ]
+        );
+        assert!(exchange
+            .requests()
+            .iter()
+            .all(|r| r.position_side.is_none()));
+        assert_eq!(risk_manager.portfolio().realized_pnl(), dec!(10));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+// src/indicators/atr.rs
+use rust_decimal::Decimal;
+
+use super::Indicator;
+use crate::types::Kline;
+
+/// 平均真实波幅（Wilder 平滑）
+#[derive(Debug, Clone)]
+pub struct Atr {
+    period: usize,
+    prev_close: Option<Decimal>,
+    count: usize,
+    value: Decimal,
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "ATR period must be positive");
+        Self {
+            period,
+            prev_close: None,
+            count: 0,
+            value: Decimal::ZERO,
+        }
+    }
+}
+
+impl Indicator for Atr {
+    type Output = Decimal;
+
+    fn update(&mut self, kline: &Kline) -> Option<Decimal> {
+        let range = kline.high - kline.low;
+        let true_range = match self.prev_close.replace(kline.close) {
+            Some(prev) => range
+                .max((kline.high - prev).abs())
+                .max((kline.low - prev).abs()),
+            None => range,
+        };
+
+        let period = Decimal::from(self.period);
+        self.count += 1;
+        if self.count < self.period {
+            self.value += true_range;
+            return None;
+        }
+
+        self.value = if self.count == self.period {
+            (self.value + true_range) / period
+        } else {
+            (self.value * (period - Decimal::ONE) + true_range) / period
+        };
+        Some(self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::test_support::{assert_close, klines};
+
+    #[test]
+    fn test_atr() {
+        let data = klines(&[
+            ("48.70", "47.79", "48.16", "1"),
+            ("48.72", "48.14", "48.61", "1"),
+            ("48.90", "48.39", "48.75", "1"),
+            ("48.87", "48.37", "48.63", "1"),
+            ("48.82", "48.24", "48.74", "1"),
+            ("49.05", "48.64", "49.03", "1"),
+            // 跳空：真实波幅取与前收盘价的差
+            ("50.19", "49.20", "49.50", "1"),
+        ]);
+        let values = Atr::new(3).batch(&data);
+
+        assert!(values[1].is_none());
+        // (0.91 + 0.58 + 0.51) / 3
+        assert_close(values[2].unwrap(), "0.6667");
+        assert_close(values[3].unwrap(), "0.6111");
+        assert_close(values[6].unwrap(), "0.7448");
+    }
+}
@@ -0,0 +1,93 @@
+// src/indicators/bollinger.rs
+use rust_decimal::{Decimal, MathematicalOps};
+use std::collections::VecDeque;
+
+use super::Indicator;
+use crate::types::Kline;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerOutput {
+    pub upper: Decimal,
+    pub middle: Decimal,
+    pub lower: Decimal,
+}
+
+/// 布林带：SMA 加减 `k` 倍总体标准差
+#[derive(Debug, Clone)]
+pub struct BollingerBands {
+    period: usize,
+    k: Decimal,
+    window: VecDeque<Decimal>,
+    sum: Decimal,
+    sum_sq: Decimal,
+}
+
+impl BollingerBands {
+    pub fn new(period: usize, k: Decimal) -> Self {
+        assert!(period > 0, "Bollinger period must be positive");
+        Self {
+            period,
+            k,
+            window: VecDeque::with_capacity(period + 1),
+            sum: Decimal::ZERO,
+            sum_sq: Decimal::ZERO,
+        }
+    }
+
+    pub fn update_value(&mut self, close: Decimal) -> Option<BollingerOutput> {
+        self.window.push_back(close);
+        self.sum += close;
+        self.sum_sq += close * close;
+        if self.window.len() > self.period {
+            let old = self.window.pop_front().unwrap_or_default();
+            self.sum -= old;
+            self.sum_sq -= old * old;
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let n = Decimal::from(self.period);
+        let mean = self.sum / n;
+        let variance = (self.sum_sq / n - mean * mean).max(Decimal::ZERO);
+        let deviation = self.k * variance.sqrt().unwrap_or_default();
+
+        Some(BollingerOutput {
+            upper: mean + deviation,
+            middle: mean,
+            lower: mean - deviation,
+        })
+    }
+}
+
+impl Indicator for BollingerBands {
+    type Output = BollingerOutput;
+
+    fn update(&mut self, kline: &Kline) -> Option<BollingerOutput> {
+        self.update_value(kline.close)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::test_support::{assert_close, closes};
+
+    #[test]
+    fn test_bollinger_bands() {
+        let data = closes(&["2", "4", "4", "4", "5", "5", "7", "9", "1"]);
+        let values = BollingerBands::new(8, Decimal::TWO).batch(&data);
+
+        assert!(values[6].is_none());
+        // 经典示例：均值 5，总体标准差 2
+        let bands = values[7].unwrap();
+        assert_eq!(bands.middle, Decimal::from(5));
+        assert_close(bands.upper, "9");
+        assert_close(bands.lower, "1");
+
+        let bands = values[8].unwrap();
+        assert_close(bands.middle, "4.875");
+        assert_close(bands.upper, "9.2838");
+        assert_close(bands.lower, "0.4662");
+    }
+}
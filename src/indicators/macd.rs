@@ -0,0 +1,83 @@
+// src/indicators/macd.rs
+use rust_decimal::Decimal;
+
+use super::{Ema, Indicator};
+use crate::types::Kline;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacdOutput {
+    pub macd: Decimal,
+    pub signal: Decimal,
+    pub histogram: Decimal,
+}
+
+/// MACD：快慢 EMA 之差及其信号线
+#[derive(Debug, Clone)]
+pub struct Macd {
+    fast: Ema,
+    slow: Ema,
+    signal: Ema,
+}
+
+impl Macd {
+    pub fn new(fast_period: usize, slow_period: usize, signal_period: usize) -> Self {
+        Self {
+            fast: Ema::new(fast_period),
+            slow: Ema::new(slow_period),
+            signal: Ema::new(signal_period),
+        }
+    }
+
+    pub fn update_value(&mut self, close: Decimal) -> Option<MacdOutput> {
+        let fast = self.fast.update_value(close);
+        let slow = self.slow.update_value(close);
+        let macd = fast? - slow?;
+        let signal = self.signal.update_value(macd)?;
+
+        Some(MacdOutput {
+            macd,
+            signal,
+            histogram: macd - signal,
+        })
+    }
+}
+
+impl Default for Macd {
+    fn default() -> Self {
+        Self::new(12, 26, 9)
+    }
+}
+
+impl Indicator for Macd {
+    type Output = MacdOutput;
+
+    fn update(&mut self, kline: &Kline) -> Option<MacdOutput> {
+        self.update_value(kline.close)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::test_support::{assert_close, closes};
+
+    #[test]
+    fn test_macd() {
+        let data = closes(&[
+            "10", "11", "12", "13", "12", "11", "12", "14", "15", "14", "13", "15",
+        ]);
+        let values = Macd::new(3, 6, 3).batch(&data);
+
+        // 慢线需要 6 根，信号线再需要 3 个 MACD 值
+        assert!(values[..7].iter().all(Option::is_none));
+        let first = values[7].unwrap();
+        assert_close(first.macd, "0.5587");
+        assert_close(first.signal, "0.2219");
+        assert_close(first.histogram, "0.3367");
+
+        let last = values[11].unwrap();
+        assert_close(last.macd, "0.4947");
+        assert_close(last.signal, "0.4521");
+        assert_close(last.histogram, "0.0426");
+    }
+}
@@ -0,0 +1,79 @@
+// src/indicators/mod.rs
+//! 技术指标库
+//!
+//! 每个指标都实现 [`Indicator`]：流式场景下逐根调用 `update`，每次更新为 O(1)；
+//! 批量场景下调用 `batch` 得到与输入等长的序列，预热阶段为 `None`。
+//! 所有计算都使用 `Decimal`，不经过浮点转换。
+
+mod atr;
+mod bollinger;
+mod macd;
+mod moving_average;
+mod rsi;
+mod stochastic;
+mod volume;
+
+pub use atr::Atr;
+pub use bollinger::{BollingerBands, BollingerOutput};
+pub use macd::{Macd, MacdOutput};
+pub use moving_average::{Ema, Sma, Wma};
+pub use rsi::Rsi;
+pub use stochastic::{Stochastic, StochasticOutput};
+pub use volume::{Obv, Vwap};
+
+use crate::types::Kline;
+
+pub trait Indicator {
+    type Output;
+
+    /// 推入一根新K线，预热完成后返回最新指标值
+    fn update(&mut self, kline: &Kline) -> Option<Self::Output>;
+
+    /// 对整段K线计算指标，结果与输入一一对应
+    fn batch(mut self, data: &[Kline]) -> Vec<Option<Self::Output>>
+    where
+        Self: Sized,
+    {
+        data.iter().map(|kline| self.update(kline)).collect()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    /// 由 (high, low, close, volume) 构造K线，开盘价取收盘价
+    pub fn klines(rows: &[(&str, &str, &str, &str)]) -> Vec<Kline> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        rows.iter()
+            .enumerate()
+            .map(|(i, (high, low, close, volume))| Kline {
+                timestamp: start + Duration::hours(i as i64),
+                open: Decimal::from_str(close).unwrap(),
+                high: Decimal::from_str(high).unwrap(),
+                low: Decimal::from_str(low).unwrap(),
+                close: Decimal::from_str(close).unwrap(),
+                volume: Decimal::from_str(volume).unwrap(),
+            })
+            .collect()
+    }
+
+    pub fn closes(values: &[&str]) -> Vec<Kline> {
+        let rows: Vec<(&str, &str, &str, &str)> =
+            values.iter().map(|v| (*v, *v, *v, "1")).collect();
+        klines(&rows)
+    }
+
+    pub fn assert_close(actual: Decimal, expected: &str) {
+        let expected = Decimal::from_str(expected).unwrap();
+        assert!(
+            (actual - expected).abs() < Decimal::from_str("0.0001").unwrap(),
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+}
@@ -0,0 +1,170 @@
+// src/indicators/moving_average.rs
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+
+use super::Indicator;
+use crate::types::Kline;
+
+/// 简单移动平均（收盘价）
+#[derive(Debug, Clone)]
+pub struct Sma {
+    period: usize,
+    window: VecDeque<Decimal>,
+    sum: Decimal,
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "SMA period must be positive");
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: Decimal::ZERO,
+        }
+    }
+
+    pub fn update_value(&mut self, value: Decimal) -> Option<Decimal> {
+        self.window.push_back(value);
+        self.sum += value;
+        if self.window.len() > self.period {
+            self.sum -= self.window.pop_front().unwrap_or_default();
+        }
+
+        (self.window.len() == self.period).then(|| self.sum / Decimal::from(self.period))
+    }
+}
+
+impl Indicator for Sma {
+    type Output = Decimal;
+
+    fn update(&mut self, kline: &Kline) -> Option<Decimal> {
+        self.update_value(kline.close)
+    }
+}
+
+/// 指数移动平均，以前 `period` 个值的 SMA 作为初值
+#[derive(Debug, Clone)]
+pub struct Ema {
+    alpha: Decimal,
+    seed: Sma,
+    value: Option<Decimal>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        Self {
+            alpha: Decimal::TWO / Decimal::from(period + 1),
+            seed: Sma::new(period),
+            value: None,
+        }
+    }
+
+    pub fn update_value(&mut self, value: Decimal) -> Option<Decimal> {
+        self.value = match self.value {
+            Some(prev) => Some(prev + self.alpha * (value - prev)),
+            None => self.seed.update_value(value),
+        };
+        self.value
+    }
+}
+
+impl Indicator for Ema {
+    type Output = Decimal;
+
+    fn update(&mut self, kline: &Kline) -> Option<Decimal> {
+        self.update_value(kline.close)
+    }
+}
+
+/// 线性加权移动平均，最新值权重为 `period`
+#[derive(Debug, Clone)]
+pub struct Wma {
+    period: usize,
+    window: VecDeque<Decimal>,
+    sum: Decimal,
+    weighted_sum: Decimal,
+}
+
+impl Wma {
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "WMA period must be positive");
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: Decimal::ZERO,
+            weighted_sum: Decimal::ZERO,
+        }
+    }
+
+    pub fn update_value(&mut self, value: Decimal) -> Option<Decimal> {
+        let n = Decimal::from(self.period);
+
+        if self.window.len() == self.period {
+            // 所有旧值权重减一，最旧的值权重降为零后移出
+            self.weighted_sum += n * value - self.sum;
+            self.sum += value - self.window.pop_front().unwrap_or_default();
+        } else {
+            self.weighted_sum += Decimal::from(self.window.len() + 1) * value;
+            self.sum += value;
+        }
+        self.window.push_back(value);
+
+        let denominator = n * (n + Decimal::ONE) / Decimal::TWO;
+        (self.window.len() == self.period).then(|| self.weighted_sum / denominator)
+    }
+}
+
+impl Indicator for Wma {
+    type Output = Decimal;
+
+    fn update(&mut self, kline: &Kline) -> Option<Decimal> {
+        self.update_value(kline.close)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::test_support::{assert_close, closes};
+
+    const PRICES: [&str; 10] = [
+        "22.27", "22.19", "22.08", "22.17", "22.18", "22.13", "22.23", "22.43", "22.24", "22.29",
+    ];
+
+    #[test]
+    fn test_sma() {
+        let values = Sma::new(5).batch(&closes(&PRICES));
+        assert!(values[..4].iter().all(Option::is_none));
+        assert_close(values[4].unwrap(), "22.178");
+        assert_close(values[9].unwrap(), "22.264");
+    }
+
+    #[test]
+    fn test_ema() {
+        let values = Ema::new(5).batch(&closes(&PRICES));
+        assert!(values[3].is_none());
+        // 初值为前 5 个收盘价的 SMA
+        assert_close(values[4].unwrap(), "22.178");
+        assert_close(values[5].unwrap(), "22.162");
+        assert_close(values[9].unwrap(), "22.2684");
+    }
+
+    #[test]
+    fn test_wma() {
+        let values = Wma::new(5).batch(&closes(&PRICES));
+        assert!(values[3].is_none());
+        assert_close(values[4].unwrap(), "22.1647");
+        assert_close(values[9].unwrap(), "22.286");
+
+        // 流式更新与逐窗口直接计算一致
+        let mut wma = Wma::new(3);
+        let direct = |w: &[i64]| Decimal::from(w[0] + 2 * w[1] + 3 * w[2]) / Decimal::from(6);
+        let data = [3i64, 8, 1, 9, 4, 7];
+        for (i, v) in data.iter().enumerate() {
+            let out = wma.update_value(Decimal::from(*v));
+            if i >= 2 {
+                assert_eq!(out, Some(direct(&data[i - 2..=i])));
+            }
+        }
+    }
+}
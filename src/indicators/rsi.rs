@@ -0,0 +1,101 @@
+// src/indicators/rsi.rs
+use rust_decimal::Decimal;
+
+use super::Indicator;
+use crate::types::Kline;
+
+/// 相对强弱指数（Wilder 平滑）
+#[derive(Debug, Clone)]
+pub struct Rsi {
+    period: usize,
+    prev_close: Option<Decimal>,
+    changes: usize,
+    avg_gain: Decimal,
+    avg_loss: Decimal,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        assert!(period > 0, "RSI period must be positive");
+        Self {
+            period,
+            prev_close: None,
+            changes: 0,
+            avg_gain: Decimal::ZERO,
+            avg_loss: Decimal::ZERO,
+        }
+    }
+
+    pub fn update_value(&mut self, close: Decimal) -> Option<Decimal> {
+        let prev = self.prev_close.replace(close)?;
+        let change = close - prev;
+        let gain = change.max(Decimal::ZERO);
+        let loss = (-change).max(Decimal::ZERO);
+
+        let period = Decimal::from(self.period);
+        self.changes += 1;
+        if self.changes <= self.period {
+            // 预热期先累加，第 period 个变化时取简单平均
+            self.avg_gain += gain;
+            self.avg_loss += loss;
+            if self.changes < self.period {
+                return None;
+            }
+            self.avg_gain /= period;
+            self.avg_loss /= period;
+        } else {
+            self.avg_gain = (self.avg_gain * (period - Decimal::ONE) + gain) / period;
+            self.avg_loss = (self.avg_loss * (period - Decimal::ONE) + loss) / period;
+        }
+
+        let hundred = Decimal::ONE_HUNDRED;
+        Some(if self.avg_loss.is_zero() {
+            if self.avg_gain.is_zero() {
+                hundred / Decimal::TWO
+            } else {
+                hundred
+            }
+        } else {
+            hundred - hundred / (Decimal::ONE + self.avg_gain / self.avg_loss)
+        })
+    }
+}
+
+impl Indicator for Rsi {
+    type Output = Decimal;
+
+    fn update(&mut self, kline: &Kline) -> Option<Decimal> {
+        self.update_value(kline.close)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::test_support::{assert_close, closes};
+
+    #[test]
+    fn test_rsi() {
+        // StockCharts 的 RSI(14) 示例数据
+        let data = closes(&[
+            "44.34", "44.09", "44.15", "43.61", "44.33", "44.83", "45.10", "45.42", "45.84",
+            "46.08", "45.89", "46.03", "45.61", "46.28", "46.28", "46.00", "46.03", "46.41",
+            "46.22", "45.64",
+        ]);
+        let values = Rsi::new(14).batch(&data);
+
+        assert!(values[..14].iter().all(Option::is_none));
+        assert_close(values[14].unwrap(), "70.4641");
+        assert_close(values[15].unwrap(), "66.2496");
+        assert_close(values[19].unwrap(), "57.9150");
+    }
+
+    #[test]
+    fn test_rsi_flat_and_rising() {
+        let flat = Rsi::new(3).batch(&closes(&["10", "10", "10", "10"]));
+        assert_eq!(flat[3], Some(Decimal::from(50)));
+
+        let rising = Rsi::new(3).batch(&closes(&["1", "2", "3", "4"]));
+        assert_eq!(rising[3], Some(Decimal::ONE_HUNDRED));
+    }
+}
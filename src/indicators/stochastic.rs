@@ -0,0 +1,108 @@
+// src/indicators/stochastic.rs
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+
+use super::{Indicator, Sma};
+use crate::types::Kline;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StochasticOutput {
+    pub k: Decimal,
+    pub d: Decimal,
+}
+
+/// 随机指标：%K 为收盘价在近 `k_period` 根高低区间中的位置，%D 为 %K 的 SMA
+///
+/// 区间最高/最低价用单调队列维护，每次更新均摊 O(1)。
+#[derive(Debug, Clone)]
+pub struct Stochastic {
+    k_period: usize,
+    index: usize,
+    highs: VecDeque<(usize, Decimal)>,
+    lows: VecDeque<(usize, Decimal)>,
+    d: Sma,
+}
+
+impl Stochastic {
+    pub fn new(k_period: usize, d_period: usize) -> Self {
+        assert!(k_period > 0, "Stochastic period must be positive");
+        Self {
+            k_period,
+            index: 0,
+            highs: VecDeque::new(),
+            lows: VecDeque::new(),
+            d: Sma::new(d_period),
+        }
+    }
+}
+
+impl Indicator for Stochastic {
+    type Output = StochasticOutput;
+
+    fn update(&mut self, kline: &Kline) -> Option<StochasticOutput> {
+        let index = self.index;
+        self.index += 1;
+
+        while matches!(self.highs.back(), Some(&(_, h)) if h <= kline.high) {
+            self.highs.pop_back();
+        }
+        self.highs.push_back((index, kline.high));
+        while matches!(self.lows.back(), Some(&(_, l)) if l >= kline.low) {
+            self.lows.pop_back();
+        }
+        self.lows.push_back((index, kline.low));
+
+        // 移除窗口外的极值
+        let oldest = (index + 1).saturating_sub(self.k_period);
+        while matches!(self.highs.front(), Some(&(i, _)) if i < oldest) {
+            self.highs.pop_front();
+        }
+        while matches!(self.lows.front(), Some(&(i, _)) if i < oldest) {
+            self.lows.pop_front();
+        }
+
+        if self.index < self.k_period {
+            return None;
+        }
+
+        let highest = self.highs.front()?.1;
+        let lowest = self.lows.front()?.1;
+        let k = if highest == lowest {
+            Decimal::ZERO
+        } else {
+            Decimal::ONE_HUNDRED * (kline.close - lowest) / (highest - lowest)
+        };
+
+        let d = self.d.update_value(k)?;
+        Some(StochasticOutput { k, d })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::test_support::{assert_close, klines};
+
+    #[test]
+    fn test_stochastic() {
+        let data = klines(&[
+            ("10", "8", "9", "1"),
+            ("12", "9", "11", "1"),
+            ("11", "7", "8", "1"),
+            ("13", "10", "12", "1"),
+            ("14", "11", "13", "1"),
+            ("12", "10", "11", "1"),
+        ]);
+        let values = Stochastic::new(3, 2).batch(&data);
+
+        assert!(values[..3].iter().all(Option::is_none));
+        // %K: 20, 5/6*100, 5/7*100, 1/4*100
+        let first = values[3].unwrap();
+        assert_close(first.k, "83.3333");
+        assert_close(first.d, "51.6667");
+
+        let last = values[5].unwrap();
+        assert_close(last.k, "25");
+        assert_close(last.d, "55.3571");
+    }
+}
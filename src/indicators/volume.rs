@@ -0,0 +1,126 @@
+// src/indicators/volume.rs
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use super::Indicator;
+use crate::types::Kline;
+
+/// 能量潮：收涨累加成交量，收跌扣减成交量
+#[derive(Debug, Clone, Default)]
+pub struct Obv {
+    prev_close: Option<Decimal>,
+    value: Decimal,
+}
+
+impl Obv {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Indicator for Obv {
+    type Output = Decimal;
+
+    fn update(&mut self, kline: &Kline) -> Option<Decimal> {
+        if let Some(prev) = self.prev_close.replace(kline.close) {
+            if kline.close > prev {
+                self.value += kline.volume;
+            } else if kline.close < prev {
+                self.value -= kline.volume;
+            }
+        }
+        Some(self.value)
+    }
+}
+
+/// 成交量加权平均价，典型价格取 (high + low + close) / 3
+#[derive(Debug, Clone, Default)]
+pub struct Vwap {
+    daily_reset: bool,
+    session: Option<NaiveDate>,
+    price_volume: Decimal,
+    volume: Decimal,
+}
+
+impl Vwap {
+    /// 从第一根K线开始累计
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 每个 UTC 自然日重新累计
+    pub fn daily() -> Self {
+        Self {
+            daily_reset: true,
+            ..Self::default()
+        }
+    }
+}
+
+impl Indicator for Vwap {
+    type Output = Decimal;
+
+    fn update(&mut self, kline: &Kline) -> Option<Decimal> {
+        let day = kline.timestamp.date_naive();
+        if self.daily_reset && self.session != Some(day) {
+            self.price_volume = Decimal::ZERO;
+            self.volume = Decimal::ZERO;
+        }
+        self.session = Some(day);
+
+        let typical = (kline.high + kline.low + kline.close) / Decimal::from(3);
+        self.price_volume += typical * kline.volume;
+        self.volume += kline.volume;
+
+        (!self.volume.is_zero()).then(|| self.price_volume / self.volume)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::test_support::{assert_close, klines};
+    use chrono::Duration;
+
+    #[test]
+    fn test_obv() {
+        let data = klines(&[
+            ("10", "10", "10", "25200"),
+            ("10.15", "10.15", "10.15", "30000"),
+            ("10.17", "10.17", "10.17", "25600"),
+            ("10.13", "10.13", "10.13", "32000"),
+            ("10.11", "10.11", "10.11", "23000"),
+            ("10.11", "10.11", "10.11", "40000"),
+            ("10.15", "10.15", "10.15", "36000"),
+        ]);
+        let values: Vec<Decimal> = Obv::new().batch(&data).into_iter().flatten().collect();
+        let expected: Vec<Decimal> = [0, 30000, 55600, 23600, 600, 600, 36600]
+            .into_iter()
+            .map(Decimal::from)
+            .collect();
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn test_vwap() {
+        let mut data = klines(&[
+            ("12", "9", "9", "100"),
+            ("13", "10", "13", "300"),
+            ("11", "8", "8", "0"),
+            ("21", "18", "21", "50"),
+        ]);
+        // 最后一根放到第二天
+        data[3].timestamp += Duration::days(1);
+
+        let cumulative = Vwap::new().batch(&data);
+        assert_close(cumulative[0].unwrap(), "10");
+        // (10 * 100 + 12 * 300) / 400
+        assert_close(cumulative[1].unwrap(), "11.5");
+        assert_close(cumulative[2].unwrap(), "11.5");
+        assert_close(cumulative[3].unwrap(), "12.4444");
+
+        let daily = Vwap::daily().batch(&data);
+        assert_close(daily[1].unwrap(), "11.5");
+        assert_close(daily[3].unwrap(), "20");
+    }
+}
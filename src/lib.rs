@@ -1,10 +1,19 @@
+pub mod analytics;
+pub mod backtest;
 pub mod config;
 pub mod crypto_tools;
 pub mod data;
 pub mod error;
 pub mod exchange;
+pub mod execution;
+pub mod indicators;
 pub mod notification;
+pub mod orders;
+pub mod portfolio;
+pub mod rate_limit;
+pub mod retry;
 pub mod risk;
+pub mod store;
 pub mod strategy;
 pub mod types;
 
@@ -1,32 +1,15 @@
-use tokio;
-use tracing::{error, info};
+mod cli;
 
-mod config;
-pub mod crypto_tools;
-mod data;
-mod error;
-mod exchange;
-mod notification;
-mod risk;
-mod strategy;
-mod trading_system;
-mod types;
+use clap::Parser;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // 初始化日志
-    tracing_subscriber::fmt::init();
-    info!("Application started");
+    let cli = cli::Cli::parse();
 
-    // 加载配置
-    let config = config::TradingConfig::load_trading_config("config.json").await?;
-    info!("Configuration loaded successfully");
+    // 初始化日志，输出到标准错误以免干扰命令结果
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
 
-    // 初始化交易系统
-    let trading_system = trading_system::TradingSystem::new(config).await?;
-
-    // 启动交易循环
-    trading_system.run().await?;
-
-    Ok(())
+    cli.execute().await
 }
@@ -0,0 +1,271 @@
+// src/orders/mod.rs
+//! 订单跟踪：记录下出的每张订单，轮询交易所推进状态，并把新增成交交给持仓账本
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::warn;
+
+use crate::error::Error;
+use crate::exchange::Exchange;
+use crate::types::{FeeAsset, Fill, Order, Side, Symbol};
+
+/// 按订单号跟踪订单的最新状态
+///
+/// 状态只能按 `OrderStatus::can_transition_to` 向前推进，累计成交数量不会减少。
+#[derive(Default)]
+pub struct OrderManager {
+    orders: Mutex<HashMap<String, Order>>,
+}
+
+impl OrderManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 跟踪新订单或应用订单的最新快照，返回此前未记录的成交
+    ///
+    /// 快照不带逐笔成交（如 Binance 查询订单接口）时，按累计成交数量和成交额的增量
+    /// 补出一笔成交，手续费记为零。
+    pub fn apply(&self, update: Order) -> Result<Vec<Fill>, Error> {
+        let mut orders = self.orders.lock().expect("order manager lock poisoned");
+
+        let (filled, notional) = match orders.get(&update.order_id) {
+            Some(known) => {
+                if !known.status.can_transition_to(update.status) {
+                    return Err(Error::invalid_order(format!(
+                        "order {} cannot go from {} to {}",
+                        known.order_id, known.status, update.status
+                    )));
+                }
+                if update.filled_size < known.filled_size {
+                    return Err(Error::invalid_order(format!(
+                        "order {} filled size went back from {} to {}",
+                        known.order_id, known.filled_size, update.filled_size
+                    )));
+                }
+                (known.filled_size, known.filled_notional())
+            }
+            None => (Decimal::ZERO, Decimal::ZERO),
+        };
+
+        // 逐笔成交按时间排列，累计数量超过已记录部分的即为新增
+        let mut seen = Decimal::ZERO;
+        let mut fills = Vec::new();
+        for fill in &update.fills {
+            if seen >= filled {
+                fills.push(fill.clone());
+            }
+            seen += fill.size;
+        }
+
+        let listed: Decimal = fills.iter().map(|f| f.size).sum();
+        let missing = update.filled_size - filled - listed;
+        if missing > Decimal::ZERO {
+            let listed_notional: Decimal = fills.iter().map(|f| f.price * f.size).sum();
+            let missing_notional = update.filled_notional() - notional - listed_notional;
+            fills.push(Fill {
+                order_id: update.order_id.clone(),
+                symbol: update.symbol.clone(),
+                side: update.side,
+                price: missing_notional / missing,
+                size: missing,
+                fee: Decimal::ZERO,
+                fee_asset: FeeAsset::Quote,
+                position_side: update.position_side,
+                timestamp: Utc::now(),
+            });
+        }
+
+        orders.insert(update.order_id.clone(), update);
+        Ok(fills)
+    }
+
+    /// 向交易所查询所有未完结订单，返回新增成交
+    ///
+    /// 订单有新成交但查询结果不带明细时，从成交记录中取回实际价格和手续费。
+    /// 单个订单查询失败只记录日志，不影响其他订单。
+    pub async fn sync(&self, exchange: &dyn Exchange) -> Result<Vec<Fill>, Error> {
+        let mut fills = Vec::new();
+        for known in self.all_open_orders() {
+            let (symbol, order_id) = (&known.symbol, &known.order_id);
+            let mut order = match exchange.get_order(symbol, order_id).await {
+                Ok(order) => order,
+                Err(e) => {
+                    warn!("Failed to query order {} on {}: {}", order_id, symbol, e);
+                    continue;
+                }
+            };
+
+            if order.fills.is_empty() && order.filled_size > known.filled_size {
+                match exchange.get_my_trades(symbol, None).await {
+                    Ok(trades) => {
+                        order.fills = trades
+                            .into_iter()
+                            .filter(|t| &t.order_id == order_id)
+                            .collect()
+                    }
+                    Err(e) => warn!("Failed to fetch trades for order {}: {}", order_id, e),
+                }
+            }
+            fills.extend(self.apply(order)?);
+        }
+        Ok(fills)
+    }
+
+    /// 重启后从交易所载入各交易对的未完结订单
+    ///
+    /// 载入前已发生的成交不再返回，避免重复记账。
+    pub async fn reconcile(
+        &self,
+        exchange: &dyn Exchange,
+        symbols: &[Symbol],
+    ) -> Result<Vec<Order>, Error> {
+        let mut loaded = Vec::new();
+        for symbol in symbols {
+            for order in exchange.get_open_orders(symbol).await? {
+                if self.get(&order.order_id).is_none() {
+                    self.apply(order.clone())?;
+                    loaded.push(order);
+                }
+            }
+        }
+        Ok(loaded)
+    }
+
+    pub fn get(&self, order_id: &str) -> Option<Order> {
+        let orders = self.orders.lock().expect("order manager lock poisoned");
+        orders.get(order_id).cloned()
+    }
+
+    /// 某个交易对的未完结订单
+    pub fn open_orders(&self, symbol: &Symbol) -> Vec<Order> {
+        self.filter(|o| o.is_open() && &o.symbol == symbol)
+    }
+
+    pub fn all_open_orders(&self) -> Vec<Order> {
+        self.filter(Order::is_open)
+    }
+
+    /// 未完结的开仓单中某方向尚未成交部分的金额，按限价估算
+    ///
+    /// 只减仓单和止损、止盈等保护单不计入，没有价格的市价单也不计入。
+    pub fn pending_notional(&self, side: Side) -> Decimal {
+        self.filter(|o| o.is_open() && o.side == side && o.is_entry())
+            .iter()
+            .filter_map(|o| Some(o.price? * o.remaining_size()))
+            .sum()
+    }
+
+    fn filter(&self, predicate: impl Fn(&Order) -> bool) -> Vec<Order> {
+        let orders = self.orders.lock().expect("order manager lock poisoned");
+        let mut matched: Vec<Order> = orders.values().filter(|o| predicate(o)).cloned().collect();
+        matched.sort_by(|a, b| a.order_id.cmp(&b.order_id));
+        matched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exchange::PaperExchange;
+    use crate::types::{test_symbol, Kline, OrderRequest, OrderStatus};
+    use rust_decimal_macros::dec;
+
+    fn limit_order(status: OrderStatus) -> Order {
+        let request = OrderRequest::limit(&test_symbol("BTCUSDT"), Side::Buy, dec!(3), dec!(100));
+        Order::new("1", &request, status)
+    }
+
+    #[test]
+    fn test_partial_fills_without_details() -> Result<(), Error> {
+        let manager = OrderManager::new();
+        assert!(manager.apply(limit_order(OrderStatus::New))?.is_empty());
+        assert_eq!(manager.pending_notional(Side::Buy), dec!(300));
+
+        // 查询接口只给出累计数量和成交额
+        let mut update = limit_order(OrderStatus::PartiallyFilled);
+        update.set_filled(dec!(1), dec!(99));
+        let fills = manager.apply(update.clone())?;
+        assert_eq!(fills.len(), 1);
+        assert_eq!((fills[0].size, fills[0].price), (dec!(1), dec!(99)));
+        assert_eq!(manager.pending_notional(Side::Buy), dec!(200));
+
+        // 重复的快照不产生新成交
+        assert!(manager.apply(update)?.is_empty());
+
+        let mut update = limit_order(OrderStatus::Filled);
+        update.set_filled(dec!(3), dec!(299));
+        let fills = manager.apply(update)?;
+        assert_eq!((fills[0].size, fills[0].price), (dec!(2), dec!(100)));
+        assert!(manager.all_open_orders().is_empty());
+        assert_eq!(manager.get("1").map(|o| o.filled_size), Some(dec!(3)));
+
+        // 终态不能回退，累计成交不能减少
+        assert!(manager.apply(limit_order(OrderStatus::New)).is_err());
+        let mut update = limit_order(OrderStatus::Filled);
+        update.set_filled(dec!(2), dec!(200));
+        assert!(manager.apply(update).is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_with_paper_exchange() -> Result<(), Error> {
+        let btc = test_symbol("BTCUSDT");
+        let exchange = PaperExchange::new(dec!(1000));
+        let bar = |low: Decimal| Kline {
+            timestamp: Utc::now(),
+            open: dec!(100),
+            high: dec!(100),
+            low,
+            close: dec!(100),
+            volume: dec!(1),
+        };
+        exchange.on_kline(&btc, &bar(dec!(100)));
+
+        let manager = OrderManager::new();
+        let market = exchange
+            .place_order(&OrderRequest::market(&btc, Side::Buy, dec!(1)))
+            .await?;
+        assert_eq!(manager.apply(market)?.len(), 1);
+
+        let limit = exchange
+            .place_order(&OrderRequest::limit(&btc, Side::Buy, dec!(2), dec!(95)))
+            .await?;
+        let canceled = exchange
+            .place_order(&OrderRequest::limit(&btc, Side::Buy, dec!(1), dec!(90)))
+            .await?;
+        manager.apply(limit.clone())?;
+        manager.apply(canceled.clone())?;
+        assert_eq!(manager.open_orders(&btc).len(), 2);
+
+        // 挂单在交易所侧成交或撤销后由轮询同步
+        exchange.on_kline(&btc, &bar(dec!(94)));
+        exchange.cancel_order(&btc, &canceled.order_id).await?;
+        let fills = manager.sync(&exchange).await?;
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].order_id, limit.order_id);
+        assert_eq!(fills[0].size, dec!(2));
+        assert!(manager.open_orders(&btc).is_empty());
+        assert_eq!(
+            manager.get(&canceled.order_id).map(|o| o.status),
+            Some(OrderStatus::Canceled)
+        );
+        assert!(manager.sync(&exchange).await?.is_empty());
+
+        // 重启后的新实例接管交易所上的挂单
+        let resting = exchange
+            .place_order(&OrderRequest::limit(&btc, Side::Sell, dec!(1), dec!(120)))
+            .await?;
+        let restarted = OrderManager::new();
+        let loaded = restarted
+            .reconcile(&exchange, std::slice::from_ref(&btc))
+            .await?;
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(restarted.open_orders(&btc)[0].order_id, resting.order_id);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,590 @@
+// src/portfolio/mod.rs
+//! 持仓账本：记录每笔成交，按平均成本或先进先出计算持仓成本与盈亏
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use tracing::warn;
+
+use crate::types::{FeeAsset, Fill, Position, PositionSide, Side, Symbol};
+
+/// 持仓成本的计算方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostBasis {
+    #[default]
+    AverageCost,
+    Fifo,
+}
+
+/// 按成交逐笔记账的组合
+///
+/// 买入手续费计入持仓成本，卖出手续费从已实现盈亏中扣除。
+/// 以基础资产收取的买入手续费从持仓数量中扣除；以第三方资产（如 BNB）
+/// 收取的手续费无法按本交易对折算，单独按资产累计。
+/// 允许做空时（合约），超出持仓的卖出开出空头，空头数量为负数。
+/// 合约双向持仓模式下多头和空头按成交的持仓方向分开记账。
+pub struct Portfolio {
+    cost_basis: CostBasis,
+    short_selling: bool,
+    state: Mutex<PortfolioState>,
+}
+
+#[derive(Default)]
+struct PortfolioState {
+    books: HashMap<(Symbol, PositionSide), Book>,
+    marks: HashMap<Symbol, Decimal>,
+    realized: HashMap<Symbol, Decimal>,
+    other_fees: HashMap<String, Decimal>,
+    fills: Vec<Fill>,
+}
+
+/// 单个交易对的持仓批次，平均成本法下始终只有一批
+///
+/// 空头批次的数量和成本（开仓所得）均为负数。
+#[derive(Default)]
+struct Book {
+    lots: VecDeque<Lot>,
+}
+
+struct Lot {
+    size: Decimal,
+    cost: Decimal,
+}
+
+impl Book {
+    fn size(&self) -> Decimal {
+        self.lots.iter().map(|l| l.size).sum()
+    }
+
+    fn cost(&self) -> Decimal {
+        self.lots.iter().map(|l| l.cost).sum()
+    }
+
+    /// 加仓，`size` 和 `cost` 的符号与持仓方向一致
+    fn open(&mut self, cost_basis: CostBasis, size: Decimal, cost: Decimal) {
+        match (cost_basis, self.lots.front_mut()) {
+            (CostBasis::AverageCost, Some(lot)) => {
+                lot.size += size;
+                lot.cost += cost;
+            }
+            _ => self.lots.push_back(Lot { size, cost }),
+        }
+    }
+
+    /// 从最早的批次开始平仓，返回实际平仓数量及其成本（与持仓同号）
+    fn close(&mut self, size: Decimal) -> (Decimal, Decimal) {
+        let mut remaining = size;
+        let mut closed_cost = Decimal::ZERO;
+
+        while remaining > Decimal::ZERO {
+            let Some(lot) = self.lots.front_mut() else {
+                break;
+            };
+            let lot_size = lot.size.abs();
+            if lot_size <= remaining {
+                remaining -= lot_size;
+                closed_cost += lot.cost;
+                self.lots.pop_front();
+            } else {
+                let cost = lot.cost * remaining / lot_size;
+                lot.size -= lot.size / lot_size * remaining;
+                lot.cost -= cost;
+                closed_cost += cost;
+                remaining = Decimal::ZERO;
+            }
+        }
+
+        (size - remaining, closed_cost)
+    }
+
+    /// 按成交记账，返回平仓部分的已实现盈亏
+    ///
+    /// 与持仓方向相反的成交先平仓，剩余部分按成交方向开仓：买入开多，
+    /// 卖出在允许做空时开空，否则视为账本之外的持仓，不计入。
+    fn apply(
+        &mut self,
+        cost_basis: CostBasis,
+        short_selling: bool,
+        fill: &Fill,
+    ) -> Option<Decimal> {
+        let size = fill.net_size();
+        if size.is_zero() {
+            return None;
+        }
+        let direction = match fill.side {
+            Side::Buy => Decimal::ONE,
+            Side::Sell => Decimal::NEGATIVE_ONE,
+        };
+        // 部分平仓时按比例分摊手续费
+        let fee = fill.quote_fee();
+
+        let mut pnl = None;
+        let mut remaining = size;
+        if self.size() * direction < Decimal::ZERO {
+            let (closed, closed_cost) = self.close(size);
+            pnl = Some(-direction * fill.price * closed - closed_cost - fee * closed / size);
+            remaining -= closed;
+        }
+        if remaining.is_zero() {
+            return pnl;
+        }
+
+        let opened_fee = fee * remaining / size;
+        match fill.side {
+            // 买入成本为支付的全部计价资产，数量为扣除手续费后实际到账的部分
+            Side::Buy if remaining == size => {
+                let fee = match fill.fee_asset {
+                    FeeAsset::Quote => fill.fee,
+                    _ => Decimal::ZERO,
+                };
+                self.open(cost_basis, size, fill.price * fill.size + fee);
+            }
+            Side::Buy => self.open(cost_basis, remaining, fill.price * remaining + opened_fee),
+            // 空头成本为扣除手续费后的开仓所得
+            Side::Sell if short_selling => self.open(
+                cost_basis,
+                -remaining,
+                -(fill.price * remaining - opened_fee),
+            ),
+            Side::Sell => warn!(
+                "Sell of {} {} exceeds ledger position, {} unmatched",
+                fill.size, fill.symbol, remaining
+            ),
+        }
+        pnl
+    }
+}
+
+impl Portfolio {
+    pub fn new(cost_basis: CostBasis) -> Self {
+        Self {
+            cost_basis,
+            short_selling: false,
+            state: Mutex::new(PortfolioState::default()),
+        }
+    }
+
+    /// 允许卖出超过持仓的部分开空，用于合约
+    pub fn with_short_selling(mut self, short_selling: bool) -> Self {
+        self.short_selling = short_selling;
+        self
+    }
+
+    pub fn cost_basis(&self) -> CostBasis {
+        self.cost_basis
+    }
+
+    /// 记录一笔成交；平仓时返回本次平仓的已实现盈亏
+    pub fn record_fill(&self, fill: &Fill) -> Option<Decimal> {
+        let mut state = self.state.lock().expect("portfolio lock poisoned");
+        state.fills.push(fill.clone());
+        state.marks.insert(fill.symbol.clone(), fill.price);
+
+        if let FeeAsset::Other(asset) = &fill.fee_asset {
+            *state.other_fees.entry(asset.clone()).or_default() += fill.fee;
+        }
+
+        let key = (fill.symbol.clone(), fill.position_side);
+        let book = state.books.entry(key.clone()).or_default();
+        let pnl = book.apply(
+            self.cost_basis,
+            self.short_selling(fill.position_side),
+            fill,
+        );
+
+        if book.lots.is_empty() {
+            state.books.remove(&key);
+        }
+        if let Some(pnl) = pnl {
+            *state.realized.entry(fill.symbol.clone()).or_default() += pnl;
+        }
+        pnl
+    }
+
+    /// 用交易所的持仓初始化账本，用于启动时接管已有持仓
+    ///
+    /// 按时间重放 `trades` 能还原出相同数量时沿用其成本批次，返回 true；
+    /// 否则（如成交记录不完整）按单一批次记账，成本价依次取重放结果、
+    /// 交易所返回的开仓价和现价，返回 false。账本已有该交易对该方向的持仓时不做改动。
+    pub fn seed(&self, position: &Position, trades: &[Fill]) -> bool {
+        let mut trades: Vec<&Fill> = trades
+            .iter()
+            .filter(|f| f.symbol == position.symbol && f.position_side == position.position_side)
+            .collect();
+        trades.sort_by_key(|f| f.timestamp);
+
+        let short_selling = self.short_selling(position.position_side);
+        let mut replayed = Book::default();
+        for fill in trades {
+            replayed.apply(self.cost_basis, short_selling, fill);
+        }
+
+        let key = (position.symbol.clone(), position.position_side);
+        let mut state = self.state.lock().expect("portfolio lock poisoned");
+        if state.books.contains_key(&key) {
+            return true;
+        }
+        if position.current_price > Decimal::ZERO {
+            state
+                .marks
+                .insert(position.symbol.clone(), position.current_price);
+        }
+
+        let matched = replayed.size() == position.size;
+        let book = if matched {
+            replayed
+        } else {
+            let entry_price = [
+                // 重放结果与持仓同向时才可参考
+                (replayed.size() * position.size > Decimal::ZERO)
+                    .then(|| replayed.cost() / replayed.size()),
+                Some(position.entry_price),
+                Some(position.current_price),
+            ]
+            .into_iter()
+            .flatten()
+            .find(|p| *p > Decimal::ZERO)
+            .unwrap_or_default();
+            let mut book = Book::default();
+            book.open(self.cost_basis, position.size, position.size * entry_price);
+            book
+        };
+        if !book.lots.is_empty() {
+            state.books.insert(key, book);
+        }
+        matched
+    }
+
+    /// 以最新价格标记持仓
+    pub fn mark(&self, symbol: &Symbol, price: Decimal) {
+        let mut state = self.state.lock().expect("portfolio lock poisoned");
+        state.marks.insert(symbol.clone(), price);
+    }
+
+    /// 交易对的净持仓，双向持仓模式下为多空两边合计
+    pub fn position(&self, symbol: &Symbol) -> Option<Position> {
+        let state = self.state.lock().expect("portfolio lock poisoned");
+        let books: Vec<(PositionSide, &Book)> = state
+            .books
+            .iter()
+            .filter(|((held, _), _)| held == symbol)
+            .map(|((_, side), book)| (*side, book))
+            .collect();
+        let side = match books.as_slice() {
+            [] => return None,
+            [(side, _)] => *side,
+            _ => PositionSide::Both,
+        };
+        let size = books.iter().map(|(_, b)| b.size()).sum();
+        let cost = books.iter().map(|(_, b)| b.cost()).sum();
+        Some(Self::to_position(&state, symbol, side, size, cost))
+    }
+
+    /// 交易对某一持仓方向的持仓，现货和单向持仓模式为 `Both`
+    pub fn position_on(&self, symbol: &Symbol, position_side: PositionSide) -> Option<Position> {
+        let state = self.state.lock().expect("portfolio lock poisoned");
+        state
+            .books
+            .get(&(symbol.clone(), position_side))
+            .map(|book| Self::to_position(&state, symbol, position_side, book.size(), book.cost()))
+    }
+
+    /// 全部持仓，双向持仓模式下多空两边分别列出
+    pub fn positions(&self) -> Vec<Position> {
+        let state = self.state.lock().expect("portfolio lock poisoned");
+        state
+            .books
+            .iter()
+            .map(|((symbol, side), book)| {
+                Self::to_position(&state, symbol, *side, book.size(), book.cost())
+            })
+            .collect()
+    }
+
+    /// 已实现盈亏合计
+    pub fn realized_pnl(&self) -> Decimal {
+        let state = self.state.lock().expect("portfolio lock poisoned");
+        state.realized.values().sum()
+    }
+
+    pub fn realized_pnl_for(&self, symbol: &Symbol) -> Decimal {
+        let state = self.state.lock().expect("portfolio lock poisoned");
+        state.realized.get(symbol).copied().unwrap_or_default()
+    }
+
+    /// 按最新标记价计算的未实现盈亏合计
+    pub fn unrealized_pnl(&self) -> Decimal {
+        self.positions().iter().map(|p| p.unrealized_pnl).sum()
+    }
+
+    /// 按最新标记价计算的持仓市值合计，空头按绝对值计
+    pub fn exposure(&self) -> Decimal {
+        self.positions()
+            .iter()
+            .map(|p| p.size.abs() * p.current_price)
+            .sum()
+    }
+
+    /// 以第三方资产收取的手续费合计，按资产区分
+    pub fn other_fees(&self) -> HashMap<String, Decimal> {
+        let state = self.state.lock().expect("portfolio lock poisoned");
+        state.other_fees.clone()
+    }
+
+    pub fn fills(&self) -> Vec<Fill> {
+        let state = self.state.lock().expect("portfolio lock poisoned");
+        state.fills.clone()
+    }
+
+    /// 双向持仓的多头一侧不开空，空头一侧总是允许卖出开空
+    fn short_selling(&self, position_side: PositionSide) -> bool {
+        match position_side {
+            PositionSide::Both => self.short_selling,
+            PositionSide::Long => false,
+            PositionSide::Short => true,
+        }
+    }
+
+    fn to_position(
+        state: &PortfolioState,
+        symbol: &Symbol,
+        position_side: PositionSide,
+        size: Decimal,
+        cost: Decimal,
+    ) -> Position {
+        let entry_price = if size.is_zero() {
+            Decimal::ZERO
+        } else {
+            cost / size
+        };
+        let current_price = state.marks.get(symbol).copied().unwrap_or(entry_price);
+
+        Position {
+            symbol: symbol.clone(),
+            size,
+            entry_price,
+            current_price,
+            unrealized_pnl: current_price * size - cost,
+            position_side,
+            liquidation_price: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_symbol;
+    use chrono::Utc;
+    use rust_decimal_macros::dec;
+
+    fn fill(side: Side, price: Decimal, size: Decimal, fee: Decimal) -> Fill {
+        Fill {
+            order_id: "1".to_string(),
+            symbol: test_symbol("BTCUSDT"),
+            side,
+            price,
+            size,
+            fee,
+            fee_asset: FeeAsset::Quote,
+            position_side: PositionSide::Both,
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn buy_twice(portfolio: &Portfolio) {
+        portfolio.record_fill(&fill(Side::Buy, dec!(100), dec!(1), dec!(0)));
+        portfolio.record_fill(&fill(Side::Buy, dec!(120), dec!(1), dec!(0)));
+    }
+
+    #[test]
+    fn test_average_cost() {
+        let portfolio = Portfolio::new(CostBasis::AverageCost);
+        buy_twice(&portfolio);
+
+        let position = portfolio.position(&test_symbol("BTCUSDT")).unwrap();
+        assert_eq!(position.size, dec!(2));
+        assert_eq!(position.entry_price, dec!(110));
+
+        let pnl = portfolio.record_fill(&fill(Side::Sell, dec!(130), dec!(1), dec!(1)));
+        assert_eq!(pnl, Some(dec!(19)));
+
+        portfolio.mark(&test_symbol("BTCUSDT"), dec!(100));
+        assert_eq!(portfolio.unrealized_pnl(), dec!(-10));
+        assert_eq!(portfolio.exposure(), dec!(100));
+        assert_eq!(portfolio.realized_pnl(), dec!(19));
+    }
+
+    #[test]
+    fn test_fifo() {
+        let portfolio = Portfolio::new(CostBasis::Fifo);
+        buy_twice(&portfolio);
+
+        // 先平掉 100 的批次
+        let pnl = portfolio.record_fill(&fill(Side::Sell, dec!(130), dec!(1), dec!(0)));
+        assert_eq!(pnl, Some(dec!(30)));
+        assert_eq!(
+            portfolio
+                .position(&test_symbol("BTCUSDT"))
+                .unwrap()
+                .entry_price,
+            dec!(120)
+        );
+
+        let pnl = portfolio.record_fill(&fill(Side::Sell, dec!(110), dec!(1), dec!(0)));
+        assert_eq!(pnl, Some(dec!(-10)));
+        assert!(portfolio.position(&test_symbol("BTCUSDT")).is_none());
+        assert_eq!(
+            portfolio.realized_pnl_for(&test_symbol("BTCUSDT")),
+            dec!(20)
+        );
+    }
+
+    #[test]
+    fn test_buy_fee_in_cost_and_oversell() {
+        let portfolio = Portfolio::new(CostBasis::AverageCost);
+        portfolio.record_fill(&fill(Side::Buy, dec!(100), dec!(2), dec!(2)));
+        assert_eq!(
+            portfolio
+                .position(&test_symbol("BTCUSDT"))
+                .unwrap()
+                .entry_price,
+            dec!(101)
+        );
+
+        // 超出持仓的部分不计入盈亏
+        let pnl = portfolio.record_fill(&fill(Side::Sell, dec!(110), dec!(4), dec!(4)));
+        assert_eq!(pnl, Some(dec!(16)));
+        assert!(portfolio.positions().is_empty());
+    }
+
+    fn held(size: Decimal, entry_price: Decimal) -> Position {
+        Position {
+            symbol: test_symbol("BTCUSDT"),
+            size,
+            entry_price,
+            current_price: dec!(130),
+            unrealized_pnl: Decimal::ZERO,
+            position_side: PositionSide::Both,
+            liquidation_price: None,
+        }
+    }
+
+    #[test]
+    fn test_seed_from_trades() {
+        let portfolio = Portfolio::new(CostBasis::Fifo);
+        let trades = vec![
+            fill(Side::Buy, dec!(100), dec!(1), dec!(0)),
+            fill(Side::Buy, dec!(120), dec!(1), dec!(0)),
+            fill(Side::Sell, dec!(130), dec!(1), dec!(0)),
+        ];
+        assert!(portfolio.seed(&held(dec!(1), Decimal::ZERO), &trades));
+
+        // 先进先出，剩下 120 的批次
+        let position = portfolio.position(&test_symbol("BTCUSDT")).unwrap();
+        assert_eq!(position.entry_price, dec!(120));
+        assert_eq!(position.current_price, dec!(130));
+        assert_eq!(portfolio.realized_pnl(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_seed_with_incomplete_trades() {
+        let portfolio = Portfolio::new(CostBasis::AverageCost);
+        let trades = vec![fill(Side::Buy, dec!(100), dec!(1), dec!(0))];
+
+        // 余额多于成交记录，按重放出的成本价记账
+        assert!(!portfolio.seed(&held(dec!(3), Decimal::ZERO), &trades));
+        let position = portfolio.position(&test_symbol("BTCUSDT")).unwrap();
+        assert_eq!(position.size, dec!(3));
+        assert_eq!(position.entry_price, dec!(100));
+
+        // 没有成交记录时取交易所的开仓价
+        let portfolio = Portfolio::new(CostBasis::AverageCost);
+        assert!(!portfolio.seed(&held(dec!(2), dec!(90)), &[]));
+        assert_eq!(
+            portfolio
+                .position(&test_symbol("BTCUSDT"))
+                .unwrap()
+                .entry_price,
+            dec!(90)
+        );
+    }
+
+    #[test]
+    fn test_short_selling() {
+        let portfolio = Portfolio::new(CostBasis::AverageCost).with_short_selling(true);
+        assert_eq!(
+            portfolio.record_fill(&fill(Side::Sell, dec!(100), dec!(2), dec!(2))),
+            None
+        );
+
+        // 空头数量为负，成本价为扣除手续费后的开仓均价
+        let position = portfolio.position(&test_symbol("BTCUSDT")).unwrap();
+        assert_eq!((position.size, position.entry_price), (dec!(-2), dec!(99)));
+        portfolio.mark(&test_symbol("BTCUSDT"), dec!(90));
+        assert_eq!(portfolio.unrealized_pnl(), dec!(18));
+        assert_eq!(portfolio.exposure(), dec!(180));
+
+        // 买入 3 先平掉 2 的空头，剩余 1 开多，手续费按比例分摊
+        let pnl = portfolio.record_fill(&fill(Side::Buy, dec!(90), dec!(3), dec!(3)));
+        assert_eq!(pnl, Some(dec!(16)));
+        let position = portfolio.position(&test_symbol("BTCUSDT")).unwrap();
+        assert_eq!((position.size, position.entry_price), (dec!(1), dec!(91)));
+        assert_eq!(portfolio.realized_pnl(), dec!(16));
+    }
+
+    #[test]
+    fn test_seed_short_position() {
+        let portfolio = Portfolio::new(CostBasis::Fifo).with_short_selling(true);
+        let trades = vec![fill(Side::Sell, dec!(120), dec!(1), dec!(0))];
+        assert!(portfolio.seed(&held(dec!(-1), dec!(120)), &trades));
+        assert_eq!(
+            portfolio.position(&test_symbol("BTCUSDT")).map(|p| p.size),
+            Some(dec!(-1))
+        );
+    }
+
+    #[test]
+    fn test_base_asset_fee_reduces_size() {
+        let portfolio = Portfolio::new(CostBasis::AverageCost);
+        let buy = Fill {
+            fee_asset: FeeAsset::Base,
+            position_side: PositionSide::Both,
+            ..fill(Side::Buy, dec!(100), dec!(1), dec!(0.001))
+        };
+        portfolio.record_fill(&buy);
+
+        // 到账 0.999，成本仍为支付的 100
+        let position = portfolio.position(&test_symbol("BTCUSDT")).unwrap();
+        assert_eq!(position.size, dec!(0.999));
+        assert_eq!(position.size * position.entry_price, dec!(100));
+
+        let pnl = portfolio.record_fill(&fill(Side::Sell, dec!(110), dec!(0.999), dec!(0)));
+        assert_eq!(pnl, Some(dec!(9.890)));
+        assert!(portfolio.positions().is_empty());
+    }
+
+    #[test]
+    fn test_third_asset_fee_tracked_separately() {
+        let portfolio = Portfolio::new(CostBasis::AverageCost);
+        let bnb = |f: Fill| Fill {
+            fee_asset: FeeAsset::Other("BNB".to_string()),
+            position_side: PositionSide::Both,
+            ..f
+        };
+        portfolio.record_fill(&bnb(fill(Side::Buy, dec!(100), dec!(1), dec!(0.02))));
+        assert_eq!(
+            portfolio
+                .position(&test_symbol("BTCUSDT"))
+                .unwrap()
+                .entry_price,
+            dec!(100)
+        );
+
+        let pnl = portfolio.record_fill(&bnb(fill(Side::Sell, dec!(110), dec!(1), dec!(0.03))));
+        assert_eq!(pnl, Some(dec!(10)));
+        assert_eq!(portfolio.other_fees().get("BNB"), Some(&dec!(0.05)));
+    }
+}
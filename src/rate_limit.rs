@@ -0,0 +1,380 @@
+// src/rate_limit.rs
+//! Binance REST 限速：按请求权重和下单数预先节流，并以响应头中的实际用量校正
+
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::warn;
+
+/// 未给出 `Retry-After` 时的退避时间
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(60);
+
+const USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-";
+const ORDER_COUNT_HEADER: &str = "x-mbx-order-count-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitKind {
+    /// 按 IP 计算的请求权重
+    RequestWeight,
+    /// 按账户计算的下单数
+    Orders,
+}
+
+impl fmt::Display for RateLimitKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateLimitKind::RequestWeight => write!(f, "REQUEST_WEIGHT"),
+            RateLimitKind::Orders => write!(f, "ORDERS"),
+        }
+    }
+}
+
+/// 一个限速窗口，窗口按 UTC 时间对齐（如每分钟整点重置）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub kind: RateLimitKind,
+    pub interval: Duration,
+    pub limit: u32,
+}
+
+/// 当前窗口的用量
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitUsage {
+    pub kind: RateLimitKind,
+    pub interval: Duration,
+    pub used: u32,
+    pub limit: u32,
+}
+
+impl fmt::Display for RateLimitUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}s: {}/{}",
+            self.kind,
+            self.interval.as_secs(),
+            self.used,
+            self.limit
+        )
+    }
+}
+
+#[derive(Debug)]
+struct Window {
+    limit: RateLimit,
+    // 当前窗口的序号（UTC 毫秒时间戳 / 窗口长度）
+    index: i64,
+    used: u32,
+}
+
+impl Window {
+    fn interval_ms(&self) -> i64 {
+        self.limit.interval.as_millis() as i64
+    }
+
+    fn used_at(&self, now_ms: i64) -> u32 {
+        if now_ms / self.interval_ms() == self.index {
+            self.used
+        } else {
+            0
+        }
+    }
+
+    fn cost(&self, weight: u32, orders: u32) -> u32 {
+        match self.limit.kind {
+            RateLimitKind::RequestWeight => weight,
+            RateLimitKind::Orders => orders,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    windows: Vec<Window>,
+    blocked_until: Option<Instant>,
+}
+
+/// 请求前预留额度，超出任一窗口的上限时等待到窗口重置
+///
+/// 本地计数只是估计，每次响应后以 `X-MBX-USED-WEIGHT-*`、`X-MBX-ORDER-COUNT-*`
+/// 的值为准；收到 429/418 时按 `Retry-After` 暂停全部请求。
+#[derive(Debug)]
+pub struct RateLimiter {
+    state: Mutex<LimiterState>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: Vec<RateLimit>) -> Self {
+        let windows = limits
+            .into_iter()
+            .map(|limit| Window {
+                limit,
+                index: 0,
+                used: 0,
+            })
+            .collect();
+        Self {
+            state: Mutex::new(LimiterState {
+                windows,
+                blocked_until: None,
+            }),
+        }
+    }
+
+    /// Binance 现货的默认限额
+    pub fn binance_spot() -> Self {
+        Self::new(vec![
+            RateLimit {
+                kind: RateLimitKind::RequestWeight,
+                interval: Duration::from_secs(60),
+                limit: 6000,
+            },
+            RateLimit {
+                kind: RateLimitKind::Orders,
+                interval: Duration::from_secs(10),
+                limit: 100,
+            },
+            RateLimit {
+                kind: RateLimitKind::Orders,
+                interval: Duration::from_secs(86400),
+                limit: 200_000,
+            },
+        ])
+    }
+
+    /// Binance U 本位合约的默认限额，与现货分别计算
+    pub fn binance_futures() -> Self {
+        Self::new(vec![
+            RateLimit {
+                kind: RateLimitKind::RequestWeight,
+                interval: Duration::from_secs(60),
+                limit: 2400,
+            },
+            RateLimit {
+                kind: RateLimitKind::Orders,
+                interval: Duration::from_secs(10),
+                limit: 300,
+            },
+            RateLimit {
+                kind: RateLimitKind::Orders,
+                interval: Duration::from_secs(60),
+                limit: 1200,
+            },
+        ])
+    }
+
+    /// 预留 `weight` 请求权重和 `orders` 个下单数，额度不足时等待
+    pub async fn acquire(&self, weight: u32, orders: u32) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().expect("rate limiter lock poisoned");
+                match state.blocked_until {
+                    Some(until) if until > Instant::now() => Some(until - Instant::now()),
+                    _ => reserve(
+                        &mut state.windows,
+                        Utc::now().timestamp_millis(),
+                        weight,
+                        orders,
+                    ),
+                }
+            };
+            match wait {
+                Some(wait) => {
+                    warn!("Rate limit reached, waiting {:?}", wait);
+                    tokio::time::sleep(wait).await;
+                }
+                None => return,
+            }
+        }
+    }
+
+    /// 以响应头中交易所统计的用量覆盖本地计数
+    pub fn update_from_headers(&self, headers: &HeaderMap) {
+        let now_ms = Utc::now().timestamp_millis();
+        let mut state = self.state.lock().expect("rate limiter lock poisoned");
+        for (name, value) in headers {
+            let name = name.as_str();
+            let (kind, suffix) = if let Some(suffix) = name.strip_prefix(USED_WEIGHT_HEADER) {
+                (RateLimitKind::RequestWeight, suffix)
+            } else if let Some(suffix) = name.strip_prefix(ORDER_COUNT_HEADER) {
+                (RateLimitKind::Orders, suffix)
+            } else {
+                continue;
+            };
+            let (Some(interval), Some(used)) = (
+                parse_interval(suffix),
+                value.to_str().ok().and_then(|v| v.parse().ok()),
+            ) else {
+                continue;
+            };
+
+            for window in state.windows.iter_mut() {
+                if window.limit.kind == kind && window.limit.interval == interval {
+                    window.index = now_ms / window.interval_ms();
+                    window.used = used;
+                }
+            }
+        }
+    }
+
+    /// 收到 429/418 后暂停请求，`retry_after` 缺省时等待一分钟
+    pub fn back_off(&self, retry_after: Option<Duration>) {
+        let until = Instant::now() + retry_after.unwrap_or(DEFAULT_BACKOFF);
+        let mut state = self.state.lock().expect("rate limiter lock poisoned");
+        if state.blocked_until.is_none_or(|blocked| blocked < until) {
+            state.blocked_until = Some(until);
+        }
+    }
+
+    /// 各窗口的当前用量
+    pub fn usage(&self) -> Vec<RateLimitUsage> {
+        let now_ms = Utc::now().timestamp_millis();
+        let state = self.state.lock().expect("rate limiter lock poisoned");
+        state
+            .windows
+            .iter()
+            .map(|window| RateLimitUsage {
+                kind: window.limit.kind,
+                interval: window.limit.interval,
+                used: window.used_at(now_ms),
+                limit: window.limit.limit,
+            })
+            .collect()
+    }
+}
+
+/// 进程内共享的 Binance 现货限速器
+///
+/// 请求权重按 IP 计算，同一进程内的行情和交易适配器默认共用这一个实例。
+pub fn binance_spot_limiter() -> Arc<RateLimiter> {
+    static LIMITER: OnceLock<Arc<RateLimiter>> = OnceLock::new();
+    LIMITER
+        .get_or_init(|| Arc::new(RateLimiter::binance_spot()))
+        .clone()
+}
+
+/// 进程内共享的 Binance U 本位合约限速器
+pub fn binance_futures_limiter() -> Arc<RateLimiter> {
+    static LIMITER: OnceLock<Arc<RateLimiter>> = OnceLock::new();
+    LIMITER
+        .get_or_init(|| Arc::new(RateLimiter::binance_futures()))
+        .clone()
+}
+
+/// 额度足够时记入用量并返回 `None`，否则返回等到超限窗口全部重置的时间
+fn reserve(windows: &mut [Window], now_ms: i64, weight: u32, orders: u32) -> Option<Duration> {
+    let wait_ms = windows
+        .iter()
+        .filter(|w| {
+            let cost = w.cost(weight, orders);
+            // 单次请求超过上限时在空窗口放行，避免无限等待
+            cost > 0 && w.used_at(now_ms) > 0 && w.used_at(now_ms) + cost > w.limit.limit
+        })
+        .map(|w| (now_ms / w.interval_ms() + 1) * w.interval_ms() - now_ms)
+        .max();
+    if let Some(wait_ms) = wait_ms {
+        return Some(Duration::from_millis(wait_ms as u64));
+    }
+
+    for window in windows.iter_mut() {
+        let index = now_ms / window.interval_ms();
+        if window.index != index {
+            window.index = index;
+            window.used = 0;
+        }
+        window.used += window.cost(weight, orders);
+    }
+    None
+}
+
+/// 解析响应头中的窗口后缀，如 `1m`、`10s`、`1d`
+fn parse_interval(suffix: &str) -> Option<Duration> {
+    let (count, unit) = suffix.split_at(suffix.len().checked_sub(1)?);
+    let count: u64 = count.parse().ok()?;
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return None,
+    };
+    Some(Duration::from_secs(count * unit_secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn limiter() -> RateLimiter {
+        RateLimiter::new(vec![
+            RateLimit {
+                kind: RateLimitKind::RequestWeight,
+                interval: Duration::from_secs(60),
+                limit: 10,
+            },
+            RateLimit {
+                kind: RateLimitKind::Orders,
+                interval: Duration::from_secs(10),
+                limit: 2,
+            },
+        ])
+    }
+
+    #[test]
+    fn test_reserve_within_windows() {
+        let limiter = limiter();
+        let mut state = limiter.state.lock().unwrap();
+        // 窗口内第 30 秒
+        let now_ms = 1_700_000_010_000;
+
+        assert_eq!(reserve(&mut state.windows, now_ms, 6, 1), None);
+        assert_eq!(reserve(&mut state.windows, now_ms, 1, 1), None);
+        // 下单数已满，等到 10 秒窗口重置
+        assert_eq!(
+            reserve(&mut state.windows, now_ms + 4_000, 1, 1),
+            Some(Duration::from_secs(6))
+        );
+        // 权重不足，等到分钟窗口重置
+        assert_eq!(
+            reserve(&mut state.windows, now_ms + 12_000, 4, 0),
+            Some(Duration::from_secs(18))
+        );
+        // 新窗口清零
+        assert_eq!(reserve(&mut state.windows, now_ms + 30_000, 4, 1), None);
+        assert_eq!(state.windows[0].used, 4);
+    }
+
+    #[test]
+    fn test_usage_from_headers() {
+        let limiter = limiter();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-mbx-used-weight-1m", HeaderValue::from_static("7"));
+        headers.insert("x-mbx-order-count-10s", HeaderValue::from_static("1"));
+        headers.insert("x-mbx-order-count-1d", HeaderValue::from_static("40"));
+        headers.insert("x-mbx-used-weight", HeaderValue::from_static("7"));
+        limiter.update_from_headers(&headers);
+
+        let usage = limiter.usage();
+        assert_eq!(usage[0].used, 7);
+        assert_eq!(usage[1].used, 1);
+        assert_eq!(usage[0].to_string(), "REQUEST_WEIGHT 60s: 7/10");
+
+        assert_eq!(parse_interval("10s"), Some(Duration::from_secs(10)));
+        assert_eq!(parse_interval("1d"), Some(Duration::from_secs(86400)));
+        assert_eq!(parse_interval(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_back_off() {
+        let limiter = limiter();
+        limiter.back_off(Some(Duration::from_millis(50)));
+
+        let started = Instant::now();
+        limiter.acquire(1, 0).await;
+        assert!(started.elapsed() >= Duration::from_millis(50));
+    }
+}
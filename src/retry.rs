@@ -0,0 +1,530 @@
+// src/retry.rs
+//! 重试层：可重试的错误按带抖动的指数退避重试，包装交易所和数据源的调用
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::config::PositionMode;
+use crate::data::DataFetcher;
+use crate::error::Error;
+use crate::exchange::Exchange;
+use crate::types::{Fill, Interval, Kline, Order, OrderRequest, Position, Symbol, SymbolInfo};
+
+/// 重试次数和退避时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// 包括首次请求在内的最多尝试次数
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            base_delay,
+            max_delay,
+        }
+    }
+
+    /// 第 `attempt` 次失败后的等待时间
+    ///
+    /// 按指数增长并限制在 `max_delay` 内，再在一半到全部之间抖动，
+    /// 避免多个任务同时重试；交易所要求的 `Retry-After` 优先。
+    pub fn delay(&self, attempt: u32, error: &Error) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(1 << attempt.saturating_sub(1).min(16))
+            .min(self.max_delay);
+        let half = exponential / 2;
+        // 用当前时间的纳秒部分作为抖动来源
+        let jitter = half.mul_f64(Utc::now().timestamp_subsec_nanos() as f64 / 1e9);
+        let delay = half + jitter;
+
+        match error.exchange_error().and_then(|e| e.retry_after) {
+            Some(retry_after) => delay.max(retry_after),
+            None => delay,
+        }
+    }
+
+    /// 执行 `call`，可重试的错误在退避后重试，其他错误直接返回
+    pub async fn run<T, F, Fut>(&self, operation: &str, mut call: F) -> Result<T, Error>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, Error>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match call().await {
+                Ok(value) => return Ok(value),
+                Err(e) if is_retryable(&e) && attempt < self.max_attempts => {
+                    let delay = self.delay(attempt, &e);
+                    warn!(
+                        "{} failed (attempt {}/{}): {}, retrying in {:?}",
+                        operation, attempt, self.max_attempts, e, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// 稍后重试可能成功的错误：交易所标记为可重试或限速，以及连接失败和超时
+pub fn is_retryable(error: &Error) -> bool {
+    match error {
+        Error::ExchangeError(e) => e.is_retryable(),
+        Error::RequestError(e) => e.is_timeout() || e.is_connect(),
+        _ => false,
+    }
+}
+
+/// 为交易所调用加上重试
+///
+/// 调用方按下单意图给定客户端订单号（见 `OrderRequest::with_intent`）时，首次下单前先查询，
+/// 重启前已下出的同一订单直接返回；没有给定时按 `rqt-<启动时间>-<序号>` 生成，只在本进程内唯一。
+/// 重试沿用同一个订单号，下单失败后先按订单号查询，已被受理的订单直接返回，不再重发。
+pub struct RetryExchange<E> {
+    inner: E,
+    policy: RetryPolicy,
+    id_prefix: String,
+    next_id: AtomicU64,
+}
+
+impl<E: Exchange> RetryExchange<E> {
+    pub fn new(inner: E) -> Self {
+        Self {
+            inner,
+            policy: RetryPolicy::default(),
+            id_prefix: format!("rqt-{}", Utc::now().timestamp_millis()),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    fn next_client_order_id(&self) -> String {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        format!("{}-{}", self.id_prefix, id)
+    }
+}
+
+#[async_trait]
+impl<E: Exchange> Exchange for RetryExchange<E> {
+    async fn place_order(&self, request: &OrderRequest) -> Result<Order, Error> {
+        let client_order_id = match &request.client_order_id {
+            Some(id) => {
+                let found = self
+                    .policy
+                    .run("find_order", || self.inner.find_order(&request.symbol, id))
+                    .await?;
+                if let Some(order) = found {
+                    info!(
+                        "Order {} was already placed as {}, not resending",
+                        id, order.order_id
+                    );
+                    return Ok(order);
+                }
+                id.clone()
+            }
+            None => self.next_client_order_id(),
+        };
+        let request = request
+            .clone()
+            .with_client_order_id(client_order_id.clone());
+
+        let mut attempt = 1;
+        loop {
+            let error = match self.inner.place_order(&request).await {
+                Ok(order) => return Ok(order),
+                Err(e) => e,
+            };
+            if !is_retryable(&error) || attempt >= self.policy.max_attempts {
+                return Err(error);
+            }
+
+            let delay = self.policy.delay(attempt, &error);
+            warn!(
+                "Order {} failed (attempt {}/{}): {}, retrying in {:?}",
+                client_order_id, attempt, self.policy.max_attempts, error, delay
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+
+            // 上次请求可能已被交易所受理，查到就不再重发
+            let found = self
+                .policy
+                .run("find_order", || {
+                    self.inner.find_order(&request.symbol, &client_order_id)
+                })
+                .await?;
+            if let Some(order) = found {
+                info!(
+                    "Order {} was accepted as {}, not resending",
+                    client_order_id, order.order_id
+                );
+                return Ok(order);
+            }
+        }
+    }
+
+    async fn cancel_order(&self, symbol: &Symbol, order_id: &str) -> Result<(), Error> {
+        self.policy
+            .run("cancel_order", || self.inner.cancel_order(symbol, order_id))
+            .await
+    }
+
+    async fn get_order(&self, symbol: &Symbol, order_id: &str) -> Result<Order, Error> {
+        self.policy
+            .run("get_order", || self.inner.get_order(symbol, order_id))
+            .await
+    }
+
+    async fn find_order(
+        &self,
+        symbol: &Symbol,
+        client_order_id: &str,
+    ) -> Result<Option<Order>, Error> {
+        self.policy
+            .run("find_order", || {
+                self.inner.find_order(symbol, client_order_id)
+            })
+            .await
+    }
+
+    async fn get_open_orders(&self, symbol: &Symbol) -> Result<Vec<Order>, Error> {
+        self.policy
+            .run("get_open_orders", || self.inner.get_open_orders(symbol))
+            .await
+    }
+
+    async fn cancel_all(&self, symbol: &Symbol) -> Result<Vec<Order>, Error> {
+        self.policy
+            .run("cancel_all", || self.inner.cancel_all(symbol))
+            .await
+    }
+
+    async fn get_my_trades(
+        &self,
+        symbol: &Symbol,
+        since: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Fill>, Error> {
+        self.policy
+            .run("get_my_trades", || self.inner.get_my_trades(symbol, since))
+            .await
+    }
+
+    async fn get_exchange_info(&self, symbol: &Symbol) -> Result<SymbolInfo, Error> {
+        self.policy
+            .run("get_exchange_info", || self.inner.get_exchange_info(symbol))
+            .await
+    }
+
+    async fn symbol_info(&self, symbol: &Symbol) -> Result<SymbolInfo, Error> {
+        self.policy
+            .run("symbol_info", || self.inner.symbol_info(symbol))
+            .await
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>, Error> {
+        self.policy
+            .run("get_positions", || self.inner.get_positions())
+            .await
+    }
+
+    async fn get_balance(&self) -> Result<HashMap<String, Decimal>, Error> {
+        self.policy
+            .run("get_balance", || self.inner.get_balance())
+            .await
+    }
+
+    fn futures_mode(&self) -> Option<PositionMode> {
+        self.inner.futures_mode()
+    }
+}
+
+/// 为数据源调用加上重试
+pub struct RetryDataFetcher<F> {
+    inner: F,
+    policy: RetryPolicy,
+}
+
+impl<F: DataFetcher> RetryDataFetcher<F> {
+    pub fn new(inner: F) -> Self {
+        Self {
+            inner,
+            policy: RetryPolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RetryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+#[async_trait]
+impl<F: DataFetcher> DataFetcher for RetryDataFetcher<F> {
+    async fn fetch(&self) -> Result<Vec<Kline>, Error> {
+        self.policy.run("fetch", || self.inner.fetch()).await
+    }
+
+    async fn fetch_klines(
+        &self,
+        symbol: &Symbol,
+        interval: Interval,
+        limit: u32,
+    ) -> Result<Vec<Kline>, Error> {
+        self.policy
+            .run("fetch_klines", || {
+                self.inner.fetch_klines(symbol, interval, limit)
+            })
+            .await
+    }
+
+    async fn fetch_klines_since(
+        &self,
+        symbol: &Symbol,
+        interval: Interval,
+        start: DateTime<Utc>,
+        limit: u32,
+    ) -> Result<Vec<Kline>, Error> {
+        self.policy
+            .run("fetch_klines_since", || {
+                self.inner
+                    .fetch_klines_since(symbol, interval, start, limit)
+            })
+            .await
+    }
+
+    async fn fetch_ticker(&self, symbol: &Symbol) -> Result<Decimal, Error> {
+        self.policy
+            .run("fetch_ticker", || self.inner.fetch_ticker(symbol))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ExchangeError, ExchangeErrorKind};
+    use crate::exchange::PaperExchange;
+    use crate::types::{test_symbol, Side};
+    use rust_decimal_macros::dec;
+    use std::sync::atomic::AtomicU32;
+    use std::sync::Arc;
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy::new(3, Duration::from_millis(1), Duration::from_millis(5))
+    }
+
+    fn exchange_error(kind: ExchangeErrorKind) -> Error {
+        ExchangeError {
+            kind,
+            status: Some(503),
+            code: None,
+            message: "test".to_string(),
+            retry_after: None,
+        }
+        .into()
+    }
+
+    /// 下单请求送达后返回超时，模拟响应丢失
+    struct TimeoutAfterPlacing {
+        paper: Arc<PaperExchange>,
+        timeouts: AtomicU32,
+    }
+
+    #[async_trait]
+    impl Exchange for TimeoutAfterPlacing {
+        async fn place_order(&self, request: &OrderRequest) -> Result<Order, Error> {
+            let order = self.paper.place_order(request).await?;
+            if self.timeouts.load(Ordering::SeqCst) > 0 {
+                self.timeouts.fetch_sub(1, Ordering::SeqCst);
+                return Err(exchange_error(ExchangeErrorKind::Retryable));
+            }
+            Ok(order)
+        }
+
+        async fn cancel_order(&self, symbol: &Symbol, order_id: &str) -> Result<(), Error> {
+            self.paper.cancel_order(symbol, order_id).await
+        }
+
+        async fn get_order(&self, symbol: &Symbol, order_id: &str) -> Result<Order, Error> {
+            self.paper.get_order(symbol, order_id).await
+        }
+
+        async fn find_order(
+            &self,
+            symbol: &Symbol,
+            client_order_id: &str,
+        ) -> Result<Option<Order>, Error> {
+            self.paper.find_order(symbol, client_order_id).await
+        }
+
+        async fn get_open_orders(&self, symbol: &Symbol) -> Result<Vec<Order>, Error> {
+            self.paper.get_open_orders(symbol).await
+        }
+
+        async fn cancel_all(&self, symbol: &Symbol) -> Result<Vec<Order>, Error> {
+            self.paper.cancel_all(symbol).await
+        }
+
+        async fn get_my_trades(
+            &self,
+            symbol: &Symbol,
+            since: Option<DateTime<Utc>>,
+        ) -> Result<Vec<Fill>, Error> {
+            self.paper.get_my_trades(symbol, since).await
+        }
+
+        async fn get_exchange_info(&self, symbol: &Symbol) -> Result<SymbolInfo, Error> {
+            self.paper.get_exchange_info(symbol).await
+        }
+
+        async fn get_positions(&self) -> Result<Vec<Position>, Error> {
+            self.paper.get_positions().await
+        }
+
+        async fn get_balance(&self) -> Result<HashMap<String, Decimal>, Error> {
+            self.paper.get_balance().await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timed_out_order_is_not_resent() -> Result<(), Error> {
+        let btc = test_symbol("BTCUSDT");
+        let paper = PaperExchange::new(dec!(1000));
+        paper.on_ticker(&btc, dec!(100), Utc::now());
+        let exchange = RetryExchange::new(TimeoutAfterPlacing {
+            paper: Arc::new(paper),
+            timeouts: AtomicU32::new(1),
+        })
+        .with_policy(fast_policy());
+
+        let order = exchange
+            .place_order(&OrderRequest::market(&btc, Side::Buy, dec!(1)))
+            .await?;
+        let client_order_id = order.client_order_id.clone().expect("client order id");
+        assert!(client_order_id.starts_with("rqt-"));
+        // 只成交了一次
+        assert_eq!(exchange.get_my_trades(&btc, None).await?.len(), 1);
+
+        // 调用方指定的客户端订单号保持不变
+        let order = exchange
+            .place_order(
+                &OrderRequest::limit(&btc, Side::Buy, dec!(1), dec!(90))
+                    .with_client_order_id("manual-1"),
+            )
+            .await?;
+        assert_eq!(order.client_order_id.as_deref(), Some("manual-1"));
+        assert!(exchange.find_order(&btc, "manual-1").await?.is_some());
+        assert!(exchange.find_order(&btc, "missing").await?.is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_intent_order_is_not_resent_after_restart() -> Result<(), Error> {
+        let btc = test_symbol("BTCUSDT");
+        let paper = Arc::new(PaperExchange::new(dec!(1000)));
+        paper.on_ticker(&btc, dec!(100), Utc::now());
+        let request =
+            OrderRequest::market(&btc, Side::Buy, dec!(1)).with_intent(Utc::now(), "open");
+        let restart = || {
+            RetryExchange::new(TimeoutAfterPlacing {
+                paper: paper.clone(),
+                timeouts: AtomicU32::new(0),
+            })
+            .with_policy(fast_policy())
+        };
+
+        let first = restart().place_order(&request).await?;
+
+        // 重启后的新实例重发同一意图，查到之前的订单，不再成交第二次
+        let resent = restart().place_order(&request).await?;
+        assert_eq!(resent.order_id, first.order_id);
+        assert_eq!(paper.get_my_trades(&btc, None).await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retry_only_retryable_errors() {
+        let policy = fast_policy();
+        let calls = AtomicU32::new(0);
+
+        let result = policy
+            .run("flaky", || async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(exchange_error(ExchangeErrorKind::RateLimited)),
+                    _ => Ok(42),
+                }
+            })
+            .await;
+        assert_eq!(result.ok(), Some(42));
+        assert_eq!(calls.swap(0, Ordering::SeqCst), 2);
+
+        let result: Result<(), Error> = policy
+            .run("rejected", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(exchange_error(ExchangeErrorKind::InsufficientBalance))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.swap(0, Ordering::SeqCst), 1);
+
+        // 达到最多尝试次数后返回最后一次的错误
+        let result: Result<(), Error> = policy
+            .run("down", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(exchange_error(ExchangeErrorKind::Retryable))
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(100), Duration::from_millis(300));
+        let error = exchange_error(ExchangeErrorKind::Retryable);
+
+        let first = policy.delay(1, &error);
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+        let capped = policy.delay(4, &error);
+        assert!(capped >= Duration::from_millis(150) && capped <= Duration::from_millis(300));
+
+        let limited: Error = ExchangeError {
+            kind: ExchangeErrorKind::RateLimited,
+            status: Some(429),
+            code: Some(-1003),
+            message: "Too many requests.".to_string(),
+            retry_after: Some(Duration::from_secs(2)),
+        }
+        .into();
+        assert_eq!(policy.delay(1, &limited), Duration::from_secs(2));
+    }
+}
@@ -1,47 +1,537 @@
 use crate::config::TradingConfig;
 use crate::error::Error;
-use crate::types::Position;
+use crate::exchange::Exchange;
+use crate::orders::OrderManager;
+use crate::portfolio::Portfolio;
+use crate::types::{Position, PositionSide, Side, Symbol};
 
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+
+/// 风控检查结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskDecision {
+    Approved,
+    Rejected(RiskRejection),
+}
+
+impl RiskDecision {
+    pub fn is_approved(&self) -> bool {
+        matches!(self, RiskDecision::Approved)
+    }
+}
+
+/// 风控拒绝开仓的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskRejection {
+    /// 开仓后总持仓市值超过上限
+    PositionLimit {
+        exposure: Decimal,
+        requested: Decimal,
+        limit: Decimal,
+    },
+    /// 当日亏损达到上限
+    DailyLoss { loss: Decimal, limit: Decimal },
+    /// 自权益高点的回撤达到上限
+    MaxDrawdown { drawdown: Decimal, limit: Decimal },
+}
+
+impl fmt::Display for RiskRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiskRejection::PositionLimit {
+                exposure,
+                requested,
+                limit,
+            } => write!(
+                f,
+                "position limit: exposure {} + requested {} exceeds {}",
+                exposure, requested, limit
+            ),
+            RiskRejection::DailyLoss { loss, limit } => {
+                write!(f, "daily loss {} reached limit {}", loss, limit)
+            }
+            RiskRejection::MaxDrawdown { drawdown, limit } => {
+                write!(f, "drawdown {} from peak reached limit {}", drawdown, limit)
+            }
+        }
+    }
+}
+
+/// 交易所持仓与账本数量不一致
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionMismatch {
+    pub symbol: Symbol,
+    /// 双向持仓模式下多头和空头分别比较
+    pub position_side: PositionSide,
+    pub exchange: Decimal,
+    pub ledger: Decimal,
+}
+
+impl fmt::Display for PositionMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "position mismatch on {} {}: exchange {} vs ledger {}",
+            self.symbol, self.position_side, self.exchange, self.ledger
+        )
+    }
+}
+
+/// 止损单下单失败、尚未受保护的持仓，等待下个周期重试
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingStop {
+    /// 止损单方向，多头为卖出、空头为买入
+    pub side: Side,
+    pub size: Decimal,
+    pub trigger_price: Decimal,
+}
 
 pub struct RiskManager {
     config: Arc<TradingConfig>,
-    positions: HashMap<String, Position>,
+    portfolio: Arc<Portfolio>,
+    orders: Arc<OrderManager>,
+    state: Mutex<RiskState>,
+    pending_stops: Mutex<HashMap<Symbol, PendingStop>>,
+}
+
+/// 盈亏均以账本的已实现加未实现盈亏计
+#[derive(Default)]
+struct RiskState {
+    /// 当前交易日，以配置的重置时间划分
+    trading_day: Option<NaiveDate>,
+    /// 交易日开始时的累计盈亏
+    day_start_pnl: Decimal,
+    /// 累计盈亏的历史高点
+    peak_pnl: Decimal,
 }
 
 impl RiskManager {
-    pub fn new(config: Arc<TradingConfig>) -> Self {
+    pub fn new(config: Arc<TradingConfig>, portfolio: Arc<Portfolio>) -> Self {
         Self {
             config,
-            positions: HashMap::new(),
+            portfolio,
+            orders: Arc::new(OrderManager::new()),
+            state: Mutex::new(RiskState::default()),
+            pending_stops: Mutex::new(HashMap::new()),
         }
     }
 
-    pub fn check_risk(&self, symbol: &str, size: Decimal, price: Decimal) -> Result<bool, Error> {
-        // 检查持仓限制
-        let total_exposure: Decimal = self
-            .positions
-            .values()
-            .map(|p| p.size * p.current_price)
-            .sum();
+    pub fn portfolio(&self) -> &Arc<Portfolio> {
+        &self.portfolio
+    }
 
-        if total_exposure + (size * price) > self.config.risk_limits.max_position_size {
-            return Ok(false);
+    /// 已下出的订单，未成交的开仓买单计入持仓敞口
+    pub fn orders(&self) -> &Arc<OrderManager> {
+        &self.orders
+    }
+
+    /// 记录止损单下单失败的持仓，同一交易对同方向的数量累加，触发价以最新一次为准
+    pub fn add_pending_stop(
+        &self,
+        symbol: &Symbol,
+        side: Side,
+        size: Decimal,
+        trigger_price: Decimal,
+    ) {
+        let mut pending = self
+            .pending_stops
+            .lock()
+            .expect("pending stops lock poisoned");
+        let size = match pending.get(symbol) {
+            Some(existing) if existing.side == side => existing.size + size,
+            _ => size,
+        };
+        pending.insert(
+            symbol.clone(),
+            PendingStop {
+                side,
+                size,
+                trigger_price,
+            },
+        );
+    }
+
+    /// 取出等待重试的止损，取出后由调用方负责重新下单或再次记录
+    pub fn take_pending_stop(&self, symbol: &Symbol) -> Option<PendingStop> {
+        let mut pending = self
+            .pending_stops
+            .lock()
+            .expect("pending stops lock poisoned");
+        pending.remove(symbol)
+    }
+
+    /// 启动时用交易所的持仓初始化账本，成本价从成交记录中还原
+    ///
+    /// 单个交易对的成交记录查询失败时按交易所返回的开仓价记账。
+    pub async fn seed(&self, exchange: &dyn Exchange) -> Result<(), Error> {
+        for position in exchange.get_positions().await? {
+            if position.size.is_zero()
+                || self
+                    .portfolio
+                    .position_on(&position.symbol, position.position_side)
+                    .is_some()
+            {
+                continue;
+            }
+            let trades = match exchange.get_my_trades(&position.symbol, None).await {
+                Ok(trades) => trades,
+                Err(e) => {
+                    warn!("Failed to fetch trades for {}: {}", position.symbol, e);
+                    Vec::new()
+                }
+            };
+            if !self.portfolio.seed(&position, &trades) {
+                warn!(
+                    "Trade history of {} does not add up to {}, cost basis is estimated",
+                    position.symbol, position.size
+                );
+            }
+            info!("Seeded ledger with {} {}", position.size, position.symbol);
         }
+        Ok(())
+    }
 
-        // 检查回撤限制
-        let total_pnl: Decimal = self.positions.values().map(|p| p.unrealized_pnl).sum();
+    /// 用交易所返回的最新持仓价格标记账本，再推进风控状态
+    ///
+    /// 交易所持仓与账本数量不一致时记录告警，账本本身不做改动。
+    pub async fn refresh(&self, exchange: &dyn Exchange, now: DateTime<Utc>) -> Result<(), Error> {
+        let positions = exchange.get_positions().await?;
+        for position in &positions {
+            // 部分交易所不返回现价
+            if position.current_price > Decimal::ZERO {
+                self.portfolio
+                    .mark(&position.symbol, position.current_price);
+            }
+        }
+        for mismatch in self.mismatches(&positions) {
+            warn!("{}", mismatch);
+        }
+        self.update(now);
+        Ok(())
+    }
 
-        if total_pnl < -self.config.risk_limits.max_drawdown {
-            return Ok(false);
+    /// 交易所持仓与账本数量不一致的交易对，按持仓方向比较
+    pub fn mismatches(&self, positions: &[Position]) -> Vec<PositionMismatch> {
+        let mut mismatches: Vec<PositionMismatch> = positions
+            .iter()
+            .filter_map(|position| {
+                let ledger = self
+                    .portfolio
+                    .position_on(&position.symbol, position.position_side)
+                    .map(|p| p.size)
+                    .unwrap_or_default();
+                (ledger != position.size).then(|| PositionMismatch {
+                    symbol: position.symbol.clone(),
+                    position_side: position.position_side,
+                    exchange: position.size,
+                    ledger,
+                })
+            })
+            .collect();
+
+        // 账本有而交易所没有的持仓
+        for held in self.portfolio.positions() {
+            let listed = positions
+                .iter()
+                .any(|p| p.symbol == held.symbol && p.position_side == held.position_side);
+            if !listed {
+                mismatches.push(PositionMismatch {
+                    symbol: held.symbol,
+                    position_side: held.position_side,
+                    exchange: Decimal::ZERO,
+                    ledger: held.size,
+                });
+            }
         }
+        mismatches
+    }
 
-        Ok(true)
+    /// 推进到 `now`：跨过重置时间时开始新的交易日，并更新盈亏高点
+    pub fn update(&self, now: DateTime<Utc>) {
+        let pnl = self.session_pnl();
+        let day = self.trading_day(now);
+
+        let mut state = self.state.lock().expect("risk state lock poisoned");
+        if state.trading_day != Some(day) {
+            state.trading_day = Some(day);
+            state.day_start_pnl = pnl;
+        }
+        state.peak_pnl = state.peak_pnl.max(pnl);
+    }
+
+    /// 自启动以来的累计盈亏
+    pub fn session_pnl(&self) -> Decimal {
+        self.portfolio.realized_pnl() + self.portfolio.unrealized_pnl()
+    }
+
+    /// 当前交易日的盈亏
+    pub fn daily_pnl(&self) -> Decimal {
+        let pnl = self.session_pnl();
+        let state = self.state.lock().expect("risk state lock poisoned");
+        pnl - state.day_start_pnl
+    }
+
+    /// 自累计盈亏高点以来的回撤
+    pub fn drawdown(&self) -> Decimal {
+        let pnl = self.session_pnl();
+        let state = self.state.lock().expect("risk state lock poisoned");
+        (state.peak_pnl.max(pnl) - pnl).max(Decimal::ZERO)
     }
 
-    pub fn calculate_stop_loss(&self, entry_price: Decimal) -> Decimal {
-        entry_price * (Decimal::ONE - self.config.risk_limits.stop_loss_rate)
+    pub fn check_risk(
+        &self,
+        _symbol: &Symbol,
+        size: Decimal,
+        price: Decimal,
+    ) -> Result<RiskDecision, Error> {
+        let limits = &self.config.risk_limits;
+
+        // 检查持仓限制，挂着的开仓买单成交后同样占用额度
+        let exposure = self.portfolio.exposure() + self.orders.pending_notional(Side::Buy);
+        let requested = size * price;
+        if exposure + requested > limits.max_position_size {
+            return Ok(RiskDecision::Rejected(RiskRejection::PositionLimit {
+                exposure,
+                requested,
+                limit: limits.max_position_size,
+            }));
+        }
+
+        // 检查当日亏损限制
+        if let Some(limit) = limits.max_daily_loss {
+            let loss = -self.daily_pnl();
+            if loss >= limit {
+                return Ok(RiskDecision::Rejected(RiskRejection::DailyLoss {
+                    loss,
+                    limit,
+                }));
+            }
+        }
+
+        // 检查自高点的回撤限制
+        let drawdown = self.drawdown();
+        if drawdown >= limits.max_drawdown {
+            return Ok(RiskDecision::Rejected(RiskRejection::MaxDrawdown {
+                drawdown,
+                limit: limits.max_drawdown,
+            }));
+        }
+
+        Ok(RiskDecision::Approved)
+    }
+
+    /// 止损价：`entry` 为开仓方向，多头低于开仓价，空头高于开仓价
+    pub fn calculate_stop_loss(&self, entry_price: Decimal, entry: Side) -> Decimal {
+        let rate = self.config.risk_limits.stop_loss_rate;
+        match entry {
+            Side::Buy => entry_price * (Decimal::ONE - rate),
+            Side::Sell => entry_price * (Decimal::ONE + rate),
+        }
+    }
+
+    /// 重置时间之前的时刻归入前一个交易日
+    fn trading_day(&self, now: DateTime<Utc>) -> NaiveDate {
+        let reset = self.config.risk_limits.daily_reset_time;
+        let since_midnight = reset.signed_duration_since(chrono::NaiveTime::MIN);
+        (now - since_midnight).date_naive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+    use crate::exchange::PaperExchange;
+    use crate::portfolio::CostBasis;
+    use crate::types::test_symbol;
+    use crate::types::{
+        FeeAsset, Fill, Kline, Order, OrderRequest, OrderStatus, PositionSide, Side,
+    };
+    use chrono::{NaiveTime, TimeZone};
+    use rust_decimal_macros::dec;
+
+    fn fill(side: Side, price: Decimal, size: Decimal) -> Fill {
+        Fill {
+            order_id: "1".to_string(),
+            symbol: test_symbol("BTCUSDT"),
+            side,
+            price,
+            size,
+            fee: Decimal::ZERO,
+            fee_asset: FeeAsset::Quote,
+            position_side: PositionSide::Both,
+            timestamp: Utc::now(),
+        }
+    }
+
+    fn risk_manager(max_daily_loss: Option<Decimal>) -> (Arc<Portfolio>, RiskManager) {
+        let mut config = test_config();
+        config.risk_limits.max_daily_loss = max_daily_loss;
+        config.risk_limits.daily_reset_time = NaiveTime::from_hms_opt(8, 0, 0).unwrap();
+        let portfolio = Arc::new(Portfolio::new(CostBasis::AverageCost));
+        let risk_manager = RiskManager::new(Arc::new(config), portfolio.clone());
+        (portfolio, risk_manager)
+    }
+
+    #[test]
+    fn test_position_limit() -> Result<(), Error> {
+        let (portfolio, risk_manager) = risk_manager(None);
+        assert!(risk_manager
+            .check_risk(&test_symbol("BTCUSDT"), dec!(9), dec!(100))?
+            .is_approved());
+
+        portfolio.record_fill(&fill(Side::Buy, dec!(100), dec!(5)));
+        // 已持仓 500，再买 600 超过 1000 的上限
+        assert_eq!(
+            risk_manager.check_risk(&test_symbol("ETHUSDT"), dec!(6), dec!(100))?,
+            RiskDecision::Rejected(RiskRejection::PositionLimit {
+                exposure: dec!(500),
+                requested: dec!(600),
+                limit: dec!(1000),
+            })
+        );
+        assert!(risk_manager
+            .check_risk(&test_symbol("ETHUSDT"), dec!(4), dec!(100))?
+            .is_approved());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_protective_stops_do_not_count_as_exposure() -> Result<(), Error> {
+        let config = Arc::new(test_config());
+        let portfolio = Arc::new(Portfolio::new(CostBasis::AverageCost).with_short_selling(true));
+        let risk_manager = RiskManager::new(config, portfolio.clone());
+        let btc = test_symbol("BTCUSDT");
+
+        // 空头 800 接近 1000 的上限，挂着只减仓的买入止损和双向持仓的止损
+        portfolio.record_fill(&fill(Side::Sell, dec!(100), dec!(8)));
+        let stop = OrderRequest::stop_loss(&btc, Side::Buy, dec!(8), dec!(105));
+        let orders = risk_manager.orders();
+        orders.apply(Order::new(
+            "1",
+            &stop.clone().with_reduce_only(),
+            OrderStatus::New,
+        ))?;
+        orders.apply(Order::new(
+            "2",
+            &stop.with_position_side(PositionSide::Short),
+            OrderStatus::New,
+        ))?;
+        assert!(risk_manager
+            .check_risk(&test_symbol("ETHUSDT"), dec!(2), dec!(100))?
+            .is_approved());
+
+        // 开仓的限价买单仍占用额度
+        let entry = OrderRequest::limit(&test_symbol("ETHUSDT"), Side::Buy, dec!(1), dec!(100));
+        orders.apply(Order::new("3", &entry, OrderStatus::New))?;
+        assert!(!risk_manager
+            .check_risk(&test_symbol("ETHUSDT"), dec!(2), dec!(100))?
+            .is_approved());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_seed_and_mismatch_with_paper_exchange() -> Result<(), Error> {
+        let btc = test_symbol("BTCUSDT");
+        let exchange = PaperExchange::new(dec!(1000));
+        exchange.on_kline(
+            &btc,
+            &Kline {
+                timestamp: Utc::now(),
+                open: dec!(100),
+                high: dec!(100),
+                low: dec!(100),
+                close: dec!(100),
+                volume: dec!(1),
+            },
+        );
+        exchange
+            .place_order(&OrderRequest::market(&btc, Side::Buy, dec!(3)))
+            .await?;
+
+        // 重启后账本为空，从交易所持仓和成交记录还原
+        let (portfolio, risk_manager) = risk_manager(None);
+        let positions = exchange.get_positions().await?;
+        assert_eq!(risk_manager.mismatches(&positions).len(), 1);
+        risk_manager.seed(&exchange).await?;
+        let position = portfolio.position(&btc).unwrap();
+        assert_eq!((position.size, position.entry_price), (dec!(3), dec!(100)));
+        assert!(risk_manager.mismatches(&positions).is_empty());
+
+        // 账本之外的卖出导致数量不一致
+        exchange
+            .place_order(&OrderRequest::market(&btc, Side::Sell, dec!(1)))
+            .await?;
+        let positions = exchange.get_positions().await?;
+        assert_eq!(
+            risk_manager.mismatches(&positions),
+            vec![PositionMismatch {
+                symbol: btc,
+                position_side: PositionSide::Both,
+                exchange: dec!(2),
+                ledger: dec!(3),
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_drawdown_from_peak() -> Result<(), Error> {
+        let (portfolio, risk_manager) = risk_manager(None);
+        let now = Utc.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap();
+
+        portfolio.record_fill(&fill(Side::Buy, dec!(100), dec!(5)));
+        portfolio.mark(&test_symbol("BTCUSDT"), dec!(110));
+        risk_manager.update(now);
+
+        // 盈利 50 回落到 25，自高点回撤 25 超过 20
+        portfolio.mark(&test_symbol("BTCUSDT"), dec!(105));
+        assert_eq!(
+            risk_manager.check_risk(&test_symbol("ETHUSDT"), dec!(1), dec!(100))?,
+            RiskDecision::Rejected(RiskRejection::MaxDrawdown {
+                drawdown: dec!(25),
+                limit: dec!(20),
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_daily_loss_resets_at_configured_time() -> Result<(), Error> {
+        let (portfolio, risk_manager) = risk_manager(Some(dec!(30)));
+
+        portfolio.record_fill(&fill(Side::Buy, dec!(100), dec!(1)));
+        risk_manager.update(Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap());
+        portfolio.record_fill(&fill(Side::Sell, dec!(70), dec!(1)));
+
+        // 次日 07:00 仍属于同一交易日
+        risk_manager.update(Utc.with_ymd_and_hms(2024, 1, 2, 7, 0, 0).unwrap());
+        let decision = risk_manager.check_risk(&test_symbol("BTCUSDT"), dec!(1), dec!(10))?;
+        assert!(matches!(
+            decision,
+            RiskDecision::Rejected(RiskRejection::DailyLoss { .. })
+        ));
+
+        // 08:00 之后开始新的交易日，但回撤仍然生效
+        risk_manager.update(Utc.with_ymd_and_hms(2024, 1, 2, 8, 0, 0).unwrap());
+        assert_eq!(risk_manager.daily_pnl(), Decimal::ZERO);
+        assert_eq!(
+            risk_manager.check_risk(&test_symbol("BTCUSDT"), dec!(1), dec!(10))?,
+            RiskDecision::Rejected(RiskRejection::MaxDrawdown {
+                drawdown: dec!(30),
+                limit: dec!(20),
+            })
+        );
+
+        Ok(())
     }
 }
@@ -0,0 +1,415 @@
+// src/store/mod.rs
+//! 本地K线库：按 (数据源, 交易对, 周期) 存储历史K线，支持增量同步和按时间查询
+
+use chrono::{DateTime, TimeZone, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use rust_decimal::Decimal;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Mutex;
+use tracing::info;
+
+use crate::data::DataFetcher;
+use crate::error::Error;
+use crate::types::{Interval, Kline, Symbol};
+
+/// 单次同步请求的K线数量
+const SYNC_BATCH_SIZE: u32 = 1000;
+
+/// 基于 SQLite 的K线库，价格和成交量以文本保存以保留 `Decimal` 精度
+pub struct KlineStore {
+    conn: Mutex<Connection>,
+}
+
+impl KlineStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, Error> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS klines (
+                source   TEXT    NOT NULL,
+                symbol   TEXT    NOT NULL,
+                interval TEXT    NOT NULL,
+                ts       INTEGER NOT NULL,
+                open     TEXT    NOT NULL,
+                high     TEXT    NOT NULL,
+                low      TEXT    NOT NULL,
+                close    TEXT    NOT NULL,
+                volume   TEXT    NOT NULL,
+                PRIMARY KEY (source, symbol, interval, ts)
+            ) WITHOUT ROWID;",
+        )?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// 写入K线，相同时间戳的记录被覆盖，返回写入条数
+    pub fn insert(
+        &self,
+        source: &str,
+        symbol: &Symbol,
+        interval: Interval,
+        klines: &[Kline],
+    ) -> Result<usize, Error> {
+        let mut conn = self.conn.lock().expect("kline store lock poisoned");
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT OR REPLACE INTO klines
+                 (source, symbol, interval, ts, open, high, low, close, volume)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for k in klines {
+                stmt.execute(params![
+                    source,
+                    symbol.to_string(),
+                    interval.as_str(),
+                    k.timestamp.timestamp_millis(),
+                    k.open.to_string(),
+                    k.high.to_string(),
+                    k.low.to_string(),
+                    k.close.to_string(),
+                    k.volume.to_string(),
+                ])?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(klines.len())
+    }
+
+    /// 最后一根已存储K线的开盘时间
+    pub fn last_timestamp(
+        &self,
+        source: &str,
+        symbol: &Symbol,
+        interval: Interval,
+    ) -> Result<Option<DateTime<Utc>>, Error> {
+        let conn = self.conn.lock().expect("kline store lock poisoned");
+        let ts: Option<i64> = conn
+            .query_row(
+                "SELECT MAX(ts) FROM klines WHERE source = ?1 AND symbol = ?2 AND interval = ?3",
+                params![source, symbol.to_string(), interval.as_str()],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+
+        ts.map(from_millis).transpose()
+    }
+
+    /// 查询 `[start, end)` 区间内的K线，按时间升序；边界为空表示不限
+    pub fn range(
+        &self,
+        source: &str,
+        symbol: &Symbol,
+        interval: Interval,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Result<Vec<Kline>, Error> {
+        let conn = self.conn.lock().expect("kline store lock poisoned");
+        let mut stmt = conn.prepare_cached(
+            "SELECT ts, open, high, low, close, volume FROM klines
+             WHERE source = ?1 AND symbol = ?2 AND interval = ?3 AND ts >= ?4 AND ts < ?5
+             ORDER BY ts",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                source,
+                symbol.to_string(),
+                interval.as_str(),
+                start.map_or(i64::MIN, |t| t.timestamp_millis()),
+                end.map_or(i64::MAX, |t| t.timestamp_millis()),
+            ],
+            read_row,
+        )?;
+
+        rows.map(|row| to_kline(row?)).collect()
+    }
+
+    /// 最近 `limit` 根K线，按时间升序
+    pub fn latest(
+        &self,
+        source: &str,
+        symbol: &Symbol,
+        interval: Interval,
+        limit: usize,
+    ) -> Result<Vec<Kline>, Error> {
+        let conn = self.conn.lock().expect("kline store lock poisoned");
+        let mut stmt = conn.prepare_cached(
+            "SELECT ts, open, high, low, close, volume FROM klines
+             WHERE source = ?1 AND symbol = ?2 AND interval = ?3
+             ORDER BY ts DESC LIMIT ?4",
+        )?;
+        let rows = stmt.query_map(
+            params![source, symbol.to_string(), interval.as_str(), limit as i64],
+            read_row,
+        )?;
+
+        let mut klines = rows
+            .map(|row| to_kline(row?))
+            .collect::<Result<Vec<_>, _>>()?;
+        klines.reverse();
+        Ok(klines)
+    }
+
+    /// 增量同步：只下载最后一根已存储K线之后的数据，库为空时从 `start` 开始
+    ///
+    /// 尚未收盘的K线不会写入，避免之后被跳过。返回新写入的条数。
+    pub async fn sync(
+        &self,
+        fetcher: &dyn DataFetcher,
+        source: &str,
+        symbol: &Symbol,
+        interval: Interval,
+        start: DateTime<Utc>,
+    ) -> Result<usize, Error> {
+        let step = interval.duration();
+        let mut cursor = match self.last_timestamp(source, symbol, interval)? {
+            Some(last) => last + chrono::Duration::milliseconds(1),
+            None => start,
+        };
+
+        let mut synced = 0;
+        loop {
+            let batch = fetcher
+                .fetch_klines_since(symbol, interval, cursor, SYNC_BATCH_SIZE)
+                .await?;
+            let now = Utc::now();
+            let closed: Vec<Kline> = batch
+                .into_iter()
+                .filter(|k| k.timestamp >= cursor && k.timestamp + step <= now)
+                .collect();
+
+            let Some(last) = closed.last() else {
+                break;
+            };
+            cursor = last.timestamp + chrono::Duration::milliseconds(1);
+            synced += self.insert(source, symbol, interval, &closed)?;
+
+            if closed.len() < SYNC_BATCH_SIZE as usize {
+                break;
+            }
+        }
+
+        info!(
+            "Synced {} {} {} klines from {}",
+            synced, symbol, interval, source
+        );
+        Ok(synced)
+    }
+}
+
+fn from_millis(ms: i64) -> Result<DateTime<Utc>, Error> {
+    Utc.timestamp_millis_opt(ms)
+        .single()
+        .ok_or_else(|| Error::ParseError(format!("invalid timestamp {}", ms)))
+}
+
+/// 数据库中的一行：毫秒时间戳及文本形式的开高低收量
+type RawKline = (i64, [String; 5]);
+
+fn read_row(row: &Row<'_>) -> rusqlite::Result<RawKline> {
+    Ok((
+        row.get(0)?,
+        [
+            row.get(1)?,
+            row.get(2)?,
+            row.get(3)?,
+            row.get(4)?,
+            row.get(5)?,
+        ],
+    ))
+}
+
+fn to_kline((ts, fields): RawKline) -> Result<Kline, Error> {
+    let [open, high, low, close, volume] =
+        fields.map(|f| Decimal::from_str(&f).map_err(|e| Error::ParseError(e.to_string())));
+    Ok(Kline {
+        timestamp: from_millis(ts)?,
+        open: open?,
+        high: high?,
+        low: low?,
+        close: close?,
+        volume: volume?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::Duration;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// 返回固定K线序列的数据源，记录请求次数
+    struct StaticFetcher {
+        klines: Vec<Kline>,
+        requests: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl DataFetcher for StaticFetcher {
+        async fn fetch(&self) -> Result<Vec<Kline>, Error> {
+            Ok(self.klines.clone())
+        }
+
+        async fn fetch_klines(
+            &self,
+            _symbol: &Symbol,
+            _interval: Interval,
+            limit: u32,
+        ) -> Result<Vec<Kline>, Error> {
+            let skip = self.klines.len().saturating_sub(limit as usize);
+            Ok(self.klines[skip..].to_vec())
+        }
+
+        async fn fetch_klines_since(
+            &self,
+            _symbol: &Symbol,
+            _interval: Interval,
+            start: DateTime<Utc>,
+            limit: u32,
+        ) -> Result<Vec<Kline>, Error> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            Ok(self
+                .klines
+                .iter()
+                .filter(|k| k.timestamp >= start)
+                .take(limit as usize)
+                .cloned()
+                .collect())
+        }
+
+        async fn fetch_ticker(&self, _symbol: &Symbol) -> Result<Decimal, Error> {
+            Ok(Decimal::ZERO)
+        }
+    }
+
+    fn btcusdt() -> Symbol {
+        Symbol::binance("BTC", "USDT")
+    }
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    fn klines(count: usize) -> Vec<Kline> {
+        (0..count)
+            .map(|i| {
+                let price = Decimal::new(100_000 + i as i64, 3);
+                Kline {
+                    timestamp: start() + Duration::hours(i as i64),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: Decimal::ONE,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_dedup_and_range() -> Result<(), Error> {
+        let store = KlineStore::open_in_memory()?;
+        let data = klines(5);
+        store.insert("binance", &btcusdt(), Interval::Hour1, &data[..3])?;
+        store.insert("binance", &btcusdt(), Interval::Hour1, &data[2..])?;
+        // 其他周期互不影响
+        store.insert("binance", &btcusdt(), Interval::Hour4, &data[..1])?;
+
+        let all = store.range("binance", &btcusdt(), Interval::Hour1, None, None)?;
+        assert_eq!(all.len(), 5);
+        assert_eq!(all[4].close, Decimal::new(100_004, 3));
+
+        let window = store.range(
+            "binance",
+            &btcusdt(),
+            Interval::Hour1,
+            Some(start() + Duration::hours(1)),
+            Some(start() + Duration::hours(3)),
+        )?;
+        let hours: Vec<_> = window.iter().map(|k| k.timestamp).collect();
+        assert_eq!(
+            hours,
+            vec![start() + Duration::hours(1), start() + Duration::hours(2)]
+        );
+
+        let latest = store.latest("binance", &btcusdt(), Interval::Hour1, 2)?;
+        assert_eq!(latest[0].timestamp, start() + Duration::hours(3));
+        assert_eq!(
+            store.last_timestamp("binance", &btcusdt(), Interval::Hour1)?,
+            Some(start() + Duration::hours(4))
+        );
+        assert_eq!(
+            store.last_timestamp("binance", &Symbol::binance("ETH", "USDT"), Interval::Hour1)?,
+            None
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_incremental_sync() -> Result<(), Error> {
+        let store = KlineStore::open_in_memory()?;
+        let fetcher = StaticFetcher {
+            klines: klines(2500),
+            requests: AtomicUsize::new(0),
+        };
+
+        let synced = store
+            .sync(&fetcher, "binance", &btcusdt(), Interval::Hour1, start())
+            .await?;
+        assert_eq!(synced, 2500);
+        assert_eq!(fetcher.requests.load(Ordering::SeqCst), 3);
+
+        // 没有新数据时只发一次请求
+        let synced = store
+            .sync(&fetcher, "binance", &btcusdt(), Interval::Hour1, start())
+            .await?;
+        assert_eq!(synced, 0);
+        assert_eq!(fetcher.requests.load(Ordering::SeqCst), 4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sync_skips_unclosed_bar() -> Result<(), Error> {
+        let store = KlineStore::open_in_memory()?;
+        // 存储精度为毫秒
+        let now = Utc
+            .timestamp_millis_opt(Utc::now().timestamp_millis())
+            .unwrap();
+        let open_time = now - Duration::minutes(30);
+        let mut data = klines(1);
+        data[0].timestamp = open_time - Duration::hours(1);
+        data.push(Kline {
+            timestamp: open_time,
+            ..data[0].clone()
+        });
+        let fetcher = StaticFetcher {
+            klines: data,
+            requests: AtomicUsize::new(0),
+        };
+
+        let synced = store
+            .sync(&fetcher, "binance", &btcusdt(), Interval::Hour1, start())
+            .await?;
+        assert_eq!(synced, 1);
+        assert_eq!(
+            store.last_timestamp("binance", &btcusdt(), Interval::Hour1)?,
+            Some(open_time - Duration::hours(1))
+        );
+
+        Ok(())
+    }
+}
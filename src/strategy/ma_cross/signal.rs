@@ -4,17 +4,7 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use std::sync::Once;
-use tracing_subscriber;
-use tracing::{error, info};
-
-static INIT: Once = Once::new();
-
-fn init_tracing() {
-    INIT.call_once(|| {
-        tracing_subscriber::fmt::init();
-    });
-}
+use tracing::info;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MACrossSignal {
@@ -92,7 +82,7 @@ impl MACrossSignal {
 
     /// 获取格式化的时间
     pub fn formatted_time(&self) -> String {
-        let dt = DateTime::<Utc>::from_timestamp(self.timestamp, 0).unwrap_or_else(|| Utc::now());
+        let dt = DateTime::<Utc>::from_timestamp(self.timestamp, 0).unwrap_or_else(Utc::now);
         dt.format("%Y-%m-%d %H:%M:%S UTC").to_string()
     }
 
@@ -191,11 +181,18 @@ impl NotificationMessage for MACrossSignal {
     }
 }
 
-
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Once;
+
+    static INIT: Once = Once::new();
+
+    fn init_tracing() {
+        INIT.call_once(|| {
+            tracing_subscriber::fmt::init();
+        });
+    }
 
     #[test]
     fn test_signal_creation() -> Result<(), Error> {
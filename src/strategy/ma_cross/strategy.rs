@@ -2,61 +2,141 @@ use super::signal::{Direction, MACrossSignal};
 use async_trait::async_trait;
 use rust_decimal::prelude::ToPrimitive;
 use rust_decimal::Decimal;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::warn;
 
 use crate::config::TradingConfig;
 use crate::error::Error;
+use crate::indicators::{Indicator, Sma};
 use crate::notification::{dingtalk::DingTalkBot, NotificationMessage};
 use crate::strategy::Strategy;
-use crate::types::{Kline, Signal};
+use crate::types::{Kline, Signal, Symbol};
 
 pub struct MACrossStrategy {
-    // symbol: String,
-    // fast_period: usize,
-    // slow_period: usize,
-    dingtalk: DingTalkBot,
-    // last_cross: Option<Direction>,
+    // 为空时不推送信号（回测等场景）
+    dingtalk: Option<DingTalkBot>,
+    // 每个交易对最近一次有效交叉的方向
+    last_cross: Mutex<HashMap<Symbol, Direction>>,
     config: Arc<TradingConfig>,
+    // 每个交易对通过 on_kline 推送的K线窗口
+    windows: Mutex<HashMap<Symbol, VecDeque<Kline>>>,
 }
 
 impl MACrossStrategy {
     pub fn new(dingtalk: DingTalkBot, config: Arc<TradingConfig>) -> Self {
-        Self { dingtalk, config }
-    }
-
-    pub async fn on_kline(&mut self, kline: &Kline) -> Result<Option<MACrossSignal>, Error> {
-        // 策略逻辑实现...
-        Ok(None)
+        Self {
+            dingtalk: Some(dingtalk),
+            last_cross: Mutex::new(HashMap::new()),
+            config,
+            windows: Mutex::new(HashMap::new()),
+        }
     }
 
-    async fn send_signal(&self, signal: MACrossSignal) -> Result<(), Error> {
-        self.dingtalk
-            .send_text(signal.to_notification_message())
-            .await?;
-        Ok(())
+    /// 不发送钉钉通知的策略实例
+    pub fn without_notification(config: Arc<TradingConfig>) -> Self {
+        Self {
+            dingtalk: None,
+            last_cross: Mutex::new(HashMap::new()),
+            config,
+            windows: Mutex::new(HashMap::new()),
+        }
     }
 
     /// 计算给定窗口大小的移动平均值
     pub fn calculate_ma(&self, data: &[Kline], window_size: usize) -> Option<f64> {
-        if data.len() < window_size {
+        Self::sma(data, window_size).and_then(|ma| ma.to_f64())
+    }
+
+    /// 以 `Decimal` 计算最后 `window_size` 根K线的收盘价均线
+    fn sma(data: &[Kline], window_size: usize) -> Option<Decimal> {
+        if window_size == 0 || data.len() < window_size {
             return None;
         }
 
-        // 计算收盘价的总和
-        let sum: Decimal = data[data.len() - window_size..]
-            .iter()
-            .map(|kline| kline.close)
-            .sum();
+        Sma::new(window_size)
+            .batch(&data[data.len() - window_size..])
+            .pop()
+            .flatten()
+    }
+
+    /// 比较上一根与当前K线的均线关系，检测金叉（做多）和死叉（做空）
+    ///
+    /// 与上一次有效交叉方向相同、或均线差值小于 `min_cross_difference` 的交叉会被忽略。
+    pub async fn detect_cross(
+        &self,
+        symbol: &Symbol,
+        data: &[Kline],
+    ) -> Result<Option<MACrossSignal>, Error> {
+        let params = &self.config.strategy_params;
+        if data.len() < params.long_window + 1 {
+            return Err(Error::StrategyError("Insufficient data".to_string()));
+        }
+
+        let previous = &data[..data.len() - 1];
+        let mas = (
+            Self::sma(previous, params.short_window),
+            Self::sma(previous, params.long_window),
+            Self::sma(data, params.short_window),
+            Self::sma(data, params.long_window),
+        );
+        let (prev_short, prev_long, short, long) = match mas {
+            (Some(a), Some(b), Some(c), Some(d)) => (a, b, c, d),
+            _ => return Err(Error::StrategyError("MA calculation failed".to_string())),
+        };
+
+        let direction = if prev_short <= prev_long && short > long {
+            Direction::Long
+        } else if prev_short >= prev_long && short < long {
+            Direction::Short
+        } else {
+            return Ok(None);
+        };
+
+        let last = &data[data.len() - 1];
+        let signal = MACrossSignal::new(
+            symbol.to_string(),
+            direction,
+            last.close.to_f64().unwrap_or_default(),
+            short.to_f64().unwrap_or_default(),
+            long.to_f64().unwrap_or_default(),
+            last.timestamp.timestamp(),
+        )?
+        .with_volume(last.volume.to_f64().unwrap_or_default())?;
+
+        if !signal.is_valid_cross(params.min_cross_difference) {
+            return Ok(None);
+        }
 
-        // 将 Decimal 转换为 f64，并计算平均值
-        sum.to_f64().map(|avg| avg / window_size as f64)
+        let mut last_cross = self.last_cross.lock().await;
+        if last_cross.get(symbol) == Some(&direction) {
+            return Ok(None);
+        }
+        last_cross.insert(symbol.clone(), direction);
+
+        Ok(Some(signal))
+    }
+
+    /// 把K线放入交易对的窗口，窗口已满时返回窗口内的K线
+    async fn push_window(&self, symbol: &Symbol, kline: &Kline) -> Option<Vec<Kline>> {
+        // 交叉检测需要多保留一根K线
+        let capacity = self.config.strategy_params.long_window + 1;
+
+        let mut windows = self.windows.lock().await;
+        let window = windows.entry(symbol.clone()).or_default();
+        window.push_back(kline.clone());
+        while window.len() > capacity {
+            window.pop_front();
+        }
+        (window.len() == capacity).then(|| window.iter().cloned().collect())
     }
 
-    async fn on_signal(&self, signal: MACrossSignal) -> Result<(), Error> {
+    pub async fn on_signal(&self, signal: MACrossSignal) -> Result<(), Error> {
         // 发送信号到钉钉
-        self.dingtalk
-            .send_text(signal.to_notification_message())
-            .await?;
+        if let Some(dingtalk) = &self.dingtalk {
+            dingtalk.send_text(signal.to_notification_message()).await?;
+        }
 
         // 处理其他信号逻辑...
         Ok(())
@@ -65,31 +145,196 @@ impl MACrossStrategy {
 
 #[async_trait]
 impl Strategy for MACrossStrategy {
-    async fn generate_signal(&self, data: &[Kline]) -> Result<Signal, Error> {
-        if data.len() < self.config.strategy_params.long_window as usize {
-            return Err(Error::StrategyError("Insufficient data".to_string()));
-        }
+    async fn generate_signal(&self, symbol: &Symbol, data: &[Kline]) -> Result<Signal, Error> {
+        let signal = match self.detect_cross(symbol, data).await? {
+            Some(signal) => signal,
+            None => return Ok(Signal::Hold),
+        };
+
+        let result = match signal.direction {
+            Direction::Long => Signal::Buy,
+            Direction::Short | Direction::Exit => Signal::Sell,
+        };
 
-        let short_ma = self.calculate_ma(data, self.config.strategy_params.short_window);
-        let long_ma = self.calculate_ma(data, self.config.strategy_params.long_window);
-
-        match (short_ma, long_ma) {
-            (Some(short), Some(long)) => {
-                if short > long {
-                    Ok(Signal::Buy)
-                } else if short < long {
-                    Ok(Signal::Sell)
-                } else {
-                    Ok(Signal::Hold)
-                }
-            }
-            _ => Err(Error::StrategyError("MA calculation failed".to_string())),
+        // 通知失败不影响交易
+        if let Err(e) = self.on_signal(signal).await {
+            warn!("Failed to send MA cross notification: {}", e);
         }
+
+        Ok(result)
     }
 
     fn calculate_position_size(&self, price: Decimal) -> Decimal {
         // 实现仓位计算逻辑
         self.config.risk_limits.max_position_size / price
     }
+
+    async fn on_kline(&self, symbol: &Symbol, kline: &Kline) -> Result<Option<Signal>, Error> {
+        // 窗口未满时只积累数据
+        match self.push_window(symbol, kline).await {
+            Some(window) => self.generate_signal(symbol, &window).await.map(Some),
+            None => Ok(None),
+        }
+    }
+
+    async fn on_replay_kline(&self, symbol: &Symbol, kline: &Kline) -> Result<(), Error> {
+        // 历史交叉只记录方向，避免实时K线重复触发
+        if let Some(window) = self.push_window(symbol, kline).await {
+            self.detect_cross(symbol, &window).await?;
+        }
+        Ok(())
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::test_config;
+    use crate::types::test_symbol;
+    use chrono::{Duration, TimeZone, Utc};
+    use wiremock::matchers::method;
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn strategy(min_cross_difference: f64) -> MACrossStrategy {
+        let mut config = test_config();
+        config.strategy_params.short_window = 2;
+        config.strategy_params.long_window = 3;
+        config.strategy_params.min_cross_difference = min_cross_difference;
+        MACrossStrategy::without_notification(Arc::new(config))
+    }
+
+    fn klines(closes: &[i64]) -> Vec<Kline> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| Kline {
+                timestamp: start + Duration::minutes(i as i64),
+                open: Decimal::from(close),
+                high: Decimal::from(close),
+                low: Decimal::from(close),
+                close: Decimal::from(close),
+                volume: Decimal::ONE,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_golden_and_death_cross() -> Result<(), Error> {
+        let strategy = strategy(0.0);
+
+        // 均线未交叉
+        assert_eq!(
+            strategy
+                .generate_signal(&test_symbol("BTCUSDT"), &klines(&[5, 4, 3, 2, 3]))
+                .await?,
+            Signal::Hold
+        );
+
+        // 短均线上穿长均线
+        let golden = klines(&[5, 4, 3, 2, 3, 4]);
+        let signal = strategy
+            .detect_cross(&test_symbol("ETHUSDT"), &golden)
+            .await?
+            .unwrap();
+        assert_eq!(signal.direction, Direction::Long);
+        assert_eq!(signal.fast_ma, 3.5);
+        assert_eq!(signal.slow_ma, 3.0);
+        assert_eq!(
+            strategy
+                .generate_signal(&test_symbol("BTCUSDT"), &golden)
+                .await?,
+            Signal::Buy
+        );
+
+        // 同一交叉不会重复触发
+        assert_eq!(
+            strategy
+                .generate_signal(&test_symbol("BTCUSDT"), &golden)
+                .await?,
+            Signal::Hold
+        );
+
+        // 持续上涨不再产生信号
+        assert_eq!(
+            strategy
+                .generate_signal(&test_symbol("BTCUSDT"), &klines(&[5, 4, 3, 2, 3, 4, 5]))
+                .await?,
+            Signal::Hold
+        );
+
+        // 短均线下穿长均线
+        assert_eq!(
+            strategy
+                .generate_signal(&test_symbol("BTCUSDT"), &klines(&[5, 4, 3, 2, 3, 4, 2, 1]))
+                .await?,
+            Signal::Sell
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_min_cross_difference_filter() -> Result<(), Error> {
+        let golden = klines(&[5, 4, 3, 2, 3, 4]);
+
+        assert_eq!(
+            strategy(1.0)
+                .generate_signal(&test_symbol("BTCUSDT"), &golden)
+                .await?,
+            Signal::Hold
+        );
+        assert_eq!(
+            strategy(0.5)
+                .generate_signal(&test_symbol("BTCUSDT"), &golden)
+                .await?,
+            Signal::Buy
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replay_does_not_notify() -> Result<(), Error> {
+        let server = MockServer::start().await;
+        // 只有实时K线上的死叉发送通知
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = test_config();
+        config.strategy_params.short_window = 2;
+        config.strategy_params.long_window = 3;
+        config.strategy_params.min_cross_difference = 0.0;
+        let webhook = format!("{}/robot/send?access_token=test", server.uri());
+        let strategy = MACrossStrategy::new(
+            DingTalkBot::new(webhook, "secret".to_string()),
+            Arc::new(config),
+        );
+
+        // 回放中的金叉只更新状态
+        let btc = test_symbol("BTCUSDT");
+        let bars = klines(&[5, 4, 3, 2, 3, 4, 5, 6, 2]);
+        for kline in &bars[..6] {
+            strategy.on_replay_kline(&btc, kline).await?;
+        }
+        assert!(server.received_requests().await.unwrap().is_empty());
+
+        // 实时K线只在新的交叉上产生信号
+        assert_eq!(strategy.on_kline(&btc, &bars[6]).await?, Some(Signal::Hold));
+        assert_eq!(strategy.on_kline(&btc, &bars[7]).await?, Some(Signal::Hold));
+        assert_eq!(strategy.on_kline(&btc, &bars[8]).await?, Some(Signal::Sell));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_insufficient_data() {
+        let result = strategy(0.0)
+            .generate_signal(&test_symbol("BTCUSDT"), &klines(&[1, 2, 3]))
+            .await;
+        assert!(matches!(result, Err(Error::StrategyError(_))));
+    }
+}
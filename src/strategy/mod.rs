@@ -1,14 +1,34 @@
+use crate::data::MultiTimeframe;
 use crate::error::Error;
 use rust_decimal::Decimal;
 
-use crate::types::{Kline, Signal};
+use crate::types::{Kline, Signal, Symbol};
 use async_trait::async_trait;
 
 pub mod ma_cross;
 pub use ma_cross::MACrossStrategy;
 
 #[async_trait]
-pub trait Strategy {
-    async fn generate_signal(&self, data: &[Kline]) -> Result<Signal, Error>;
+pub trait Strategy: Send + Sync {
+    async fn generate_signal(&self, symbol: &Symbol, data: &[Kline]) -> Result<Signal, Error>;
     fn calculate_position_size(&self, price: Decimal) -> Decimal;
+
+    /// 同时参考多个周期生成信号；默认只看基础周期
+    async fn generate_signal_multi(
+        &self,
+        symbol: &Symbol,
+        view: &MultiTimeframe<'_>,
+    ) -> Result<Signal, Error> {
+        self.generate_signal(symbol, view.base()).await
+    }
+
+    /// 推送一根已收盘的K线，由策略自行维护状态；默认不产生信号
+    async fn on_kline(&self, _symbol: &Symbol, _kline: &Kline) -> Result<Option<Signal>, Error> {
+        Ok(None)
+    }
+
+    /// 推送一根回放的历史K线，只更新策略状态，不产生信号也不发送通知
+    async fn on_replay_kline(&self, _symbol: &Symbol, _kline: &Kline) -> Result<(), Error> {
+        Ok(())
+    }
 }
@@ -1,42 +1,62 @@
 // src/trading_system.rs
+use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use tokio;
-use tracing::{error, info};
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
 
+use crate::analytics::{ledger_equity, EquityTracker, PerformanceMetrics, PerformanceReport};
 use crate::config::TradingConfig;
-use crate::data::BinanceDataFetcher;
 use crate::data::DataFetcher;
+use crate::data::{closed_klines, BinanceDataFetcher, MultiTimeframe, Resampler};
+use crate::data::{BinanceMarketStream, StreamKline};
 use crate::error::Error;
-use crate::exchange::BinanceExchange;
-use crate::exchange::Exchange;
-use crate::notification::{dingtalk::DingTalkBot, NotificationMessage};
+use crate::exchange::{create_exchange, Exchange, PaperExchange};
+use crate::execution::SignalExecutor;
+use crate::notification::dingtalk::DingTalkBot;
+use crate::portfolio::Portfolio;
+use crate::rate_limit::{binance_futures_limiter, binance_spot_limiter};
+use crate::retry::RetryDataFetcher;
 use crate::risk::RiskManager;
+use crate::store::KlineStore;
 use crate::strategy::MACrossStrategy;
 use crate::strategy::Strategy;
-use crate::types::{Order, OrderType, Signal};
+use crate::types::{Kline, Signal, Symbol};
 
 pub struct TradingSystem {
     config: Arc<TradingConfig>,
-    exchange: Box<dyn Exchange>,
+    exchange: Arc<dyn Exchange>,
+    // 模拟盘：订单发往本地撮合，由行情推进
+    paper: Option<Arc<PaperExchange>>,
     strategy: Box<dyn Strategy>,
     risk_manager: RiskManager,
-    data_fetcher: Box<dyn DataFetcher>,
+    data_fetcher: Arc<dyn DataFetcher>,
+    market_stream: Option<Arc<BinanceMarketStream>>,
+    store: Option<KlineStore>,
+    resamplers: Vec<Resampler>,
+    notifier: DingTalkBot,
+    equity: EquityTracker,
+    // 实盘启动时的权益减去当时账本上的盈亏，之后的权益按账本盈亏推算
+    equity_base: Mutex<Option<Decimal>>,
 }
 
+// 行情推送通道容量，需容纳预热阶段一次性推送的历史K线
+const STREAM_CHANNEL_SIZE: usize = 4096;
+
+// 轮询模式下每次读取的历史长度
+const HISTORY_LIMIT: u32 = 1000;
+
+// 绩效报告的推送间隔（小时）
+const REPORT_INTERVAL_HOURS: i64 = 24;
+
 impl TradingSystem {
     pub async fn new(config: TradingConfig) -> Result<Self, Error> {
         let config = Arc::new(config);
 
         // 初始化交易所连接
-        let exchange = match config.exchange.as_str() {
-            "binance" => Box::new(BinanceExchange::new(config.clone())) as Box<dyn Exchange>,
-            _ => {
-                error!("Unsupported exchange");
-                return Err(Error::ConfigError("Unsupported exchange".to_string()));
-            }
-        };
+        let exchange = create_exchange(config.clone()).inspect_err(|e| error!("{}", e))?;
 
         // 初始化策略
         let dingtalk = DingTalkBot::new(
@@ -47,26 +67,118 @@ impl TradingSystem {
         let strategy =
             Box::new(MACrossStrategy::new(dingtalk.clone(), config.clone())) as Box<dyn Strategy>;
 
-        // 初始化风险管理器
-        let risk_manager = RiskManager::new(config.clone());
+        // 初始化持仓账本和风险管理器
+        // 合约超出多头的卖出即为开空
+        let portfolio = Arc::new(
+            Portfolio::new(config.cost_basis).with_short_selling(exchange.futures_mode().is_some()),
+        );
+        let risk_manager = RiskManager::new(config.clone(), portfolio);
 
-        // 初始化数据获取器
-        let data_fetcher =
-            Box::new(BinanceDataFetcher::new(config.clone())) as Box<dyn DataFetcher>;
+        // 初始化数据获取器，配置了 stream 时使用 WebSocket 行情
+        let rest_fetcher = BinanceDataFetcher::new(config.clone());
+        let (data_fetcher, market_stream) = match &config.stream {
+            Some(stream_config) => {
+                let stream = Arc::new(
+                    BinanceMarketStream::new(
+                        rest_fetcher,
+                        config.symbols.clone(),
+                        stream_config.interval,
+                        stream_config.window_size,
+                    )
+                    .with_ws_url(config.market_ws_url()),
+                );
+                (stream.clone() as Arc<dyn DataFetcher>, Some(stream))
+            }
+            None => (
+                Arc::new(RetryDataFetcher::new(rest_fetcher)) as Arc<dyn DataFetcher>,
+                None,
+            ),
+        };
+
+        let store = match &config.store {
+            Some(store_config) => Some(KlineStore::open(&store_config.path)?),
+            None => None,
+        };
+
+        let resamplers = config.resamplers();
+        let equity = EquityTracker::new(
+            config.interval.duration(),
+            chrono::Duration::hours(REPORT_INTERVAL_HOURS),
+        );
 
         Ok(Self {
             config,
             exchange,
+            paper: None,
             strategy,
             risk_manager,
             data_fetcher,
+            market_stream,
+            store,
+            resamplers,
+            notifier: dingtalk,
+            equity,
+            equity_base: Mutex::new(None),
         })
     }
 
+    /// 模拟盘运行：行情照常获取，订单改由 `paper` 在本地撮合
+    pub fn with_paper_exchange(mut self, paper: PaperExchange) -> Self {
+        let paper = Arc::new(paper);
+        self.exchange = paper.clone();
+        self.paper = Some(paper);
+        self
+    }
+
     pub async fn run(&self) -> Result<(), Error> {
         info!("Starting trading system...");
+        self.announce_endpoint().await;
+
+        // 接管上次运行留下的挂单
+        match self
+            .risk_manager
+            .orders()
+            .reconcile(self.exchange.as_ref(), &self.config.symbols)
+            .await
+        {
+            Ok(orders) => info!("Loaded {} open orders from exchange", orders.len()),
+            Err(e) => error!("Failed to load open orders: {:?}", e),
+        }
+
+        // 接管交易所上已有的持仓，止损和风控以此为准
+        if let Err(e) = self.risk_manager.seed(self.exchange.as_ref()).await {
+            error!("Failed to seed ledger from exchange positions: {:?}", e);
+        }
+
+        if self.paper.is_none() {
+            match self.starting_equity().await {
+                Ok(equity) => {
+                    let portfolio = self.risk_manager.portfolio();
+                    let base = equity - portfolio.realized_pnl() - portfolio.unrealized_pnl();
+                    *self.equity_base.lock().expect("equity base lock poisoned") = Some(base);
+                }
+                Err(e) => warn!(
+                    "Failed to read starting equity, equity curve disabled: {:?}",
+                    e
+                ),
+            }
+        }
+        self.record_equity().await;
+
+        if let Some(stream) = &self.market_stream {
+            return self.run_stream(stream.clone()).await;
+        }
 
         loop {
+            // 每个周期用交易所持仓刷新风控状态
+            if let Err(e) = self
+                .risk_manager
+                .refresh(self.exchange.as_ref(), Utc::now())
+                .await
+            {
+                error!("Failed to refresh risk state: {:?}", e);
+            }
+
             for symbol in &self.config.symbols {
                 match self.process_symbol(symbol).await {
                     Ok(_) => info!("Successfully processed symbol: {}", symbol),
@@ -74,81 +186,252 @@ impl TradingSystem {
                 }
             }
 
+            self.log_rate_limit_usage();
+            self.record_equity().await;
+
             // 等待下一个交易周期
             tokio::time::sleep(Duration::from_secs(60)).await;
         }
     }
 
-    async fn process_symbol(&self, symbol: &str) -> Result<(), Error> {
-        // 获取市场数据
-        let klines = self.data_fetcher.fetch_klines(symbol, "1m", 1000).await?;
+    /// 记录所交易市场的限速器用量；模拟盘只有行情请求计入
+    fn log_rate_limit_usage(&self) {
+        let (market, limiter) = match self.config.is_futures() {
+            true => ("futures", binance_futures_limiter()),
+            false => ("spot", binance_spot_limiter()),
+        };
+        let scope = match self.paper {
+            Some(_) => " (market data only)",
+            None => "",
+        };
+        let usage: Vec<String> = limiter.usage().iter().map(ToString::to_string).collect();
+        info!(
+            "Binance {} rate limit usage{}: {}",
+            market,
+            scope,
+            usage.join(", ")
+        );
+    }
 
-        // 生成交易信号
-        let signal = self.strategy.generate_signal(&klines).await?;
+    /// 实盘连接 Binance 主网时醒目提示并发送通知
+    async fn announce_endpoint(&self) {
+        let endpoint = &self.config.binance.endpoint;
+        let rest_url = self.config.market_rest_url();
+        let live = self.paper.is_none()
+            && matches!(self.config.exchange.as_str(), "binance" | "binance_futures");
+        if !live || !endpoint.is_mainnet() {
+            info!(
+                "Binance endpoint: {} ({}), paper trading: {}",
+                endpoint,
+                rest_url,
+                self.paper.is_some()
+            );
+            return;
+        }
 
-        // 获取当前价格
-        let current_price = self.data_fetcher.fetch_ticker(symbol).await?;
+        let banner = "!".repeat(72);
+        warn!("{}", banner);
+        warn!(
+            "LIVE TRADING ON BINANCE MAINNET ({}): orders will use real funds",
+            self.config.exchange
+        );
+        warn!(
+            "REST {}, WebSocket {}",
+            rest_url,
+            self.config.market_ws_url()
+        );
+        warn!("{}", banner);
+        let message = format!(
+            "交易系统已启动，连接 Binance 主网（{}），订单将使用真实资金",
+            rest_url
+        );
+        if let Err(e) = self.notifier.send_text(message).await {
+            warn!("Failed to send mainnet notification: {}", e);
+        }
+    }
 
-        // 根据信号执行交易
-        match signal {
-            Signal::Buy => {
-                self.handle_buy_signal(symbol, current_price).await?;
-            }
-            Signal::Sell => {
-                self.handle_sell_signal(symbol, current_price).await?;
-            }
-            Signal::Hold => {
-                info!("Hold position for {}", symbol);
+    /// 由 WebSocket 推送的收盘K线驱动交易
+    async fn run_stream(&self, stream: Arc<BinanceMarketStream>) -> Result<(), Error> {
+        let (tx, mut rx) = mpsc::channel(STREAM_CHANNEL_SIZE);
+        let handle = tokio::spawn(async move { stream.run(tx).await });
+
+        while let Some(event) = rx.recv().await {
+            if let Err(e) = self.process_kline(&event).await {
+                error!("Error processing kline for {}: {:?}", event.symbol, e);
             }
         }
 
-        Ok(())
+        handle
+            .await
+            .map_err(|e| Error::UnknownError(e.to_string()))?
     }
 
-    async fn handle_buy_signal(&self, symbol: &str, price: Decimal) -> Result<(), Error> {
-        // 计算建议仓位大小
-        let size = self.strategy.calculate_position_size(price);
-
-        // 风险检查
-        if self.risk_manager.check_risk(symbol, size, price)? {
-            // 执行买入订单
-            let order = self
-                .exchange
-                .place_order(symbol, OrderType::Buy, size, None)
-                .await?;
+    async fn process_kline(&self, event: &StreamKline) -> Result<(), Error> {
+        if let Some(paper) = &self.paper {
+            paper.on_kline(&event.symbol, &event.kline);
+            self.sync_orders().await?;
+        }
 
-            info!("Buy order executed: {:?}", order);
+        // 历史K线只用于更新策略状态
+        if event.replay {
+            return self
+                .strategy
+                .on_replay_kline(&event.symbol, &event.kline)
+                .await;
+        }
 
-            // 设置止损
-            let stop_loss = self.risk_manager.calculate_stop_loss(price);
-            self.exchange
-                .place_order(symbol, OrderType::StopLoss, size, Some(stop_loss))
+        if let Some(signal) = self.strategy.on_kline(&event.symbol, &event.kline).await? {
+            self.risk_manager
+                .refresh(self.exchange.as_ref(), Utc::now())
+                .await?;
+            let current_price = self.data_fetcher.fetch_ticker(&event.symbol).await?;
+            self.execute_signal(&event.symbol, signal, current_price, event.kline.timestamp)
                 .await?;
-
-            info!("Stop loss order placed at {}", stop_loss);
-        } else {
-            info!("Risk check failed for buy signal on {}", symbol);
         }
 
+        self.record_equity().await;
         Ok(())
     }
 
-    async fn handle_sell_signal(&self, symbol: &str, price: Decimal) -> Result<(), Error> {
-        // 获取当前持仓
-        let positions = self.exchange.get_positions().await?;
+    /// 实盘启动时的权益：所交易计价资产的余额加上账本持仓的市值
+    ///
+    /// 合约余额取可用余额，不含已占用的保证金，只作近似。
+    async fn starting_equity(&self) -> Result<Decimal, Error> {
+        let balances = self.exchange.get_balance().await?;
+        let quotes: HashSet<&str> = self.config.symbols.iter().map(|s| s.quote()).collect();
+        let cash: Decimal = quotes.iter().filter_map(|asset| balances.get(*asset)).sum();
+        let position_value: Decimal = self
+            .risk_manager
+            .portfolio()
+            .positions()
+            .iter()
+            .map(|p| p.size * p.current_price)
+            .sum();
+        Ok(cash + position_value)
+    }
 
-        if let Some(position) = positions.iter().find(|p| p.symbol == symbol) {
-            // 执行卖出订单
-            let order = self
-                .exchange
-                .place_order(symbol, OrderType::Sell, position.size, None)
+    /// 记录一个权益快照，满推送间隔时发送绩效报告
+    async fn record_equity(&self) {
+        let now = Utc::now();
+        let portfolio = self.risk_manager.portfolio();
+        let (title, point) = match &self.paper {
+            Some(paper) => ("模拟盘绩效", paper.equity_point(now)),
+            None => {
+                let base = *self.equity_base.lock().expect("equity base lock poisoned");
+                let Some(base) = base else {
+                    return;
+                };
+                ("实盘绩效", ledger_equity(portfolio, base, now))
+            }
+        };
+        self.equity.record(point);
+
+        if !self.equity.take_report_due(now) {
+            return;
+        }
+        let fills = match &self.paper {
+            Some(paper) => paper.fills(),
+            None => portfolio.fills(),
+        };
+        let metrics = PerformanceMetrics::compute(&fills, &self.equity.curve());
+        if let Err(e) = PerformanceReport::new(title, metrics)
+            .send(&self.notifier)
+            .await
+        {
+            warn!("Failed to send performance report: {}", e);
+        }
+    }
+
+    async fn process_symbol(&self, symbol: &Symbol) -> Result<(), Error> {
+        // 获取市场数据
+        let klines = self.fetch_history(symbol).await?;
+
+        // 聚合出配置的长周期，生成交易信号
+        let higher: Vec<Vec<Kline>> = self
+            .resamplers
+            .iter()
+            .map(|resampler| resampler.resample(&klines))
+            .collect();
+        let mut view = MultiTimeframe::new(self.config.interval, &klines);
+        for (resampler, series) in self.resamplers.iter().zip(&higher) {
+            view = view.with_series(resampler.interval(), series);
+        }
+        let signal = self.strategy.generate_signal_multi(symbol, &view).await?;
+
+        // 获取当前价格
+        let current_price = self.data_fetcher.fetch_ticker(symbol).await?;
+
+        // 客户端订单号按信号所在的K线生成
+        let signal_time = klines.last().map_or_else(Utc::now, |k| k.timestamp);
+        self.execute_signal(symbol, signal, current_price, signal_time)
+            .await
+    }
+
+    /// 配置了K线库时增量同步后从库中读取，否则直接请求最近的K线
+    async fn fetch_history(&self, symbol: &Symbol) -> Result<Vec<Kline>, Error> {
+        let interval = self.config.interval;
+        let (Some(store), Some(store_config)) = (&self.store, &self.config.store) else {
+            // 未收盘的K线不参与信号计算
+            let klines = self
+                .data_fetcher
+                .fetch_klines(symbol, interval, HISTORY_LIMIT)
                 .await?;
+            return Ok(closed_klines(klines, interval, Utc::now()));
+        };
 
-            info!("Sell order executed: {:?}", order);
-        } else {
-            info!("No position to sell for {}", symbol);
+        let start = Utc::now() - interval.duration() * HISTORY_LIMIT as i32;
+        store
+            .sync(
+                self.data_fetcher.as_ref(),
+                &store_config.source,
+                symbol,
+                interval,
+                start,
+            )
+            .await?;
+        store.latest(
+            &store_config.source,
+            symbol,
+            interval,
+            HISTORY_LIMIT as usize,
+        )
+    }
+
+    async fn execute_signal(
+        &self,
+        symbol: &Symbol,
+        signal: Signal,
+        current_price: Decimal,
+        signal_time: DateTime<Utc>,
+    ) -> Result<(), Error> {
+        if let Some(paper) = &self.paper {
+            paper.on_ticker(symbol, current_price, Utc::now());
         }
+        // 下单前同步挂单状态，账本和风控以最新成交为准
+        self.sync_orders().await?;
+
+        SignalExecutor::new(
+            self.exchange.as_ref(),
+            self.strategy.as_ref(),
+            &self.risk_manager,
+        )
+        .with_notifier(self.notifier.clone())
+        .with_signal_time(signal_time)
+        .execute(symbol, signal, current_price)
+        .await
+    }
 
+    /// 轮询未完结订单，挂单的新增成交计入持仓账本
+    async fn sync_orders(&self) -> Result<(), Error> {
+        let fills = self
+            .risk_manager
+            .orders()
+            .sync(self.exchange.as_ref())
+            .await?;
+        for fill in fills {
+            info!("Order filled: {:?}", fill);
+            self.risk_manager.portfolio().record_fill(&fill);
+        }
         Ok(())
     }
 }
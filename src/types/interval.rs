@@ -0,0 +1,197 @@
+// src/types/interval.rs
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::Error;
+
+/// 周线按周一对齐，与 Binance 一致
+pub(crate) const WEEK_ANCHOR: NaiveDate = match NaiveDate::from_ymd_opt(1970, 1, 5) {
+    Some(date) => date,
+    None => panic!("invalid week anchor"),
+};
+
+/// K线周期，取值与 Binance 的周期代码一致
+///
+/// 不支持月线：自然月长度不固定，无法按固定时长切分。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Interval {
+    #[serde(rename = "1s")]
+    Second1,
+    #[serde(rename = "1m")]
+    Minute1,
+    #[serde(rename = "3m")]
+    Minute3,
+    #[serde(rename = "5m")]
+    Minute5,
+    #[serde(rename = "15m")]
+    Minute15,
+    #[serde(rename = "30m")]
+    Minute30,
+    #[serde(rename = "1h")]
+    Hour1,
+    #[serde(rename = "2h")]
+    Hour2,
+    #[serde(rename = "4h")]
+    Hour4,
+    #[serde(rename = "6h")]
+    Hour6,
+    #[serde(rename = "8h")]
+    Hour8,
+    #[serde(rename = "12h")]
+    Hour12,
+    #[serde(rename = "1d")]
+    Day1,
+    #[serde(rename = "3d")]
+    Day3,
+    #[serde(rename = "1w")]
+    Week1,
+}
+
+impl Interval {
+    pub const ALL: [Interval; 15] = [
+        Interval::Second1,
+        Interval::Minute1,
+        Interval::Minute3,
+        Interval::Minute5,
+        Interval::Minute15,
+        Interval::Minute30,
+        Interval::Hour1,
+        Interval::Hour2,
+        Interval::Hour4,
+        Interval::Hour6,
+        Interval::Hour8,
+        Interval::Hour12,
+        Interval::Day1,
+        Interval::Day3,
+        Interval::Week1,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Interval::Second1 => "1s",
+            Interval::Minute1 => "1m",
+            Interval::Minute3 => "3m",
+            Interval::Minute5 => "5m",
+            Interval::Minute15 => "15m",
+            Interval::Minute30 => "30m",
+            Interval::Hour1 => "1h",
+            Interval::Hour2 => "2h",
+            Interval::Hour4 => "4h",
+            Interval::Hour6 => "6h",
+            Interval::Hour8 => "8h",
+            Interval::Hour12 => "12h",
+            Interval::Day1 => "1d",
+            Interval::Day3 => "3d",
+            Interval::Week1 => "1w",
+        }
+    }
+
+    pub fn duration(&self) -> Duration {
+        match self {
+            Interval::Second1 => Duration::seconds(1),
+            Interval::Minute1 => Duration::minutes(1),
+            Interval::Minute3 => Duration::minutes(3),
+            Interval::Minute5 => Duration::minutes(5),
+            Interval::Minute15 => Duration::minutes(15),
+            Interval::Minute30 => Duration::minutes(30),
+            Interval::Hour1 => Duration::hours(1),
+            Interval::Hour2 => Duration::hours(2),
+            Interval::Hour4 => Duration::hours(4),
+            Interval::Hour6 => Duration::hours(6),
+            Interval::Hour8 => Duration::hours(8),
+            Interval::Hour12 => Duration::hours(12),
+            Interval::Day1 => Duration::days(1),
+            Interval::Day3 => Duration::days(3),
+            Interval::Week1 => Duration::weeks(1),
+        }
+    }
+
+    /// 是否能由 `source` 周期的K线无缝聚合而来
+    pub fn is_multiple_of(&self, source: Interval) -> bool {
+        let (step, source) = (
+            self.duration().num_milliseconds(),
+            source.duration().num_milliseconds(),
+        );
+        step > source && step % source == 0
+    }
+
+    /// `timestamp` 所在K线的开盘时间（UTC 对齐，周线从周一开始）
+    pub fn floor(&self, timestamp: DateTime<Utc>) -> DateTime<Utc> {
+        let step = self.duration().num_milliseconds();
+        let anchor = match self {
+            Interval::Week1 => WEEK_ANCHOR.and_time(NaiveTime::MIN).and_utc(),
+            _ => DateTime::UNIX_EPOCH,
+        };
+        let offset = (timestamp - anchor).num_milliseconds();
+        anchor + Duration::milliseconds(offset.div_euclid(step) * step)
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Interval {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Interval::ALL
+            .into_iter()
+            .find(|interval| interval.as_str() == s)
+            .ok_or_else(|| Error::ParseError(format!("invalid interval: {}", s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_parse_and_duration() -> Result<(), Error> {
+        for interval in Interval::ALL {
+            assert_eq!(interval.as_str().parse::<Interval>()?, interval);
+        }
+        assert_eq!("4h".parse::<Interval>()?.duration(), Duration::hours(4));
+        assert!("7m".parse::<Interval>().is_err());
+        assert!("1M".parse::<Interval>().is_err());
+
+        assert_eq!(
+            serde_json::to_string(&Interval::Minute15).unwrap(),
+            "\"15m\""
+        );
+        assert_eq!(
+            serde_json::from_str::<Interval>("\"1d\"").unwrap(),
+            Interval::Day1
+        );
+
+        assert!(Interval::Hour1.is_multiple_of(Interval::Minute15));
+        assert!(!Interval::Hour1.is_multiple_of(Interval::Hour1));
+        assert!(Interval::Day3.is_multiple_of(Interval::Hour8));
+        assert!(!Interval::Week1.is_multiple_of(Interval::Day3));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_floor() {
+        let t = Utc.with_ymd_and_hms(2024, 1, 3, 10, 47, 12).unwrap();
+        assert_eq!(
+            Interval::Minute15.floor(t),
+            Utc.with_ymd_and_hms(2024, 1, 3, 10, 45, 0).unwrap()
+        );
+        assert_eq!(
+            Interval::Hour4.floor(t),
+            Utc.with_ymd_and_hms(2024, 1, 3, 8, 0, 0).unwrap()
+        );
+        // 2024-01-03 是周三
+        assert_eq!(
+            Interval::Week1.floor(t),
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+        );
+    }
+}
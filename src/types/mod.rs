@@ -0,0 +1,214 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+mod interval;
+pub use interval::Interval;
+pub(crate) use interval::WEEK_ANCHOR;
+
+mod order;
+pub use order::{OrderKind, OrderRequest, OrderStatus, PositionSide, Side, TimeInForce};
+
+mod symbol;
+pub use symbol::{Symbol, SymbolInfo, Venue};
+
+/// 测试用交易对，如 `test_symbol("BTCUSDT")`
+#[cfg(test)]
+pub(crate) fn test_symbol(s: &str) -> Symbol {
+    s.parse().expect("invalid test symbol")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Kline {
+    pub timestamp: DateTime<Utc>,
+    pub open: Decimal,
+    pub high: Decimal,
+    pub low: Decimal,
+    pub close: Decimal,
+    pub volume: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Position {
+    pub symbol: Symbol,
+    /// 合约空头持仓为负数
+    pub size: Decimal,
+    pub entry_price: Decimal,
+    pub current_price: Decimal,
+    pub unrealized_pnl: Decimal,
+    /// 现货和单向持仓模式下为 `Both`
+    pub position_side: PositionSide,
+    /// 合约的强平价格，现货为 `None`
+    pub liquidation_price: Option<Decimal>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Signal {
+    Buy,
+    Sell,
+    Hold,
+}
+
+#[derive(Debug, Clone)]
+pub struct Order {
+    pub order_id: String,
+    pub client_order_id: Option<String>,
+    pub symbol: Symbol,
+    pub side: Side,
+    pub kind: OrderKind,
+    pub size: Decimal,
+    /// 市价单为成交价，限价类订单为限价
+    pub price: Option<Decimal>,
+    pub trigger_price: Option<Decimal>,
+    pub time_in_force: Option<TimeInForce>,
+    pub status: OrderStatus,
+    /// 累计成交数量
+    pub filled_size: Decimal,
+    /// 成交均价，尚未成交时为空
+    pub average_price: Option<Decimal>,
+    /// 已知的逐笔成交，查询订单的接口可能不返回明细
+    pub fills: Vec<Fill>,
+    /// 合约的只减仓或平仓单，成交后不会增加持仓
+    pub reduce_only: bool,
+    /// 合约双向持仓模式下开平的持仓方向，其余为 `Both`
+    pub position_side: PositionSide,
+}
+
+impl Order {
+    /// 按下单请求创建尚无成交的订单
+    pub fn new(order_id: impl Into<String>, request: &OrderRequest, status: OrderStatus) -> Self {
+        Self {
+            order_id: order_id.into(),
+            client_order_id: request.client_order_id.clone(),
+            symbol: request.symbol.clone(),
+            side: request.side,
+            kind: request.kind,
+            size: request.size,
+            price: request.price,
+            trigger_price: request.trigger_price,
+            time_in_force: request.effective_time_in_force(),
+            status,
+            filled_size: Decimal::ZERO,
+            average_price: None,
+            fills: Vec::new(),
+            reduce_only: request.reduce_only || request.close_position,
+            position_side: request.position_side.unwrap_or_default(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.status.is_open()
+    }
+
+    /// 是否为开仓单；只减仓单和止损、止盈等保护单不增加敞口
+    pub fn is_entry(&self) -> bool {
+        !self.reduce_only && !self.kind.has_trigger_price()
+    }
+
+    /// 尚未成交的数量
+    pub fn remaining_size(&self) -> Decimal {
+        (self.size - self.filled_size).max(Decimal::ZERO)
+    }
+
+    /// 累计成交额
+    pub fn filled_notional(&self) -> Decimal {
+        self.average_price.unwrap_or_default() * self.filled_size
+    }
+
+    /// 实际到账的成交数量，扣除买入时以基础资产收取的手续费
+    pub fn net_filled_size(&self) -> Decimal {
+        let base_fees: Decimal = self.fills.iter().map(|f| f.size - f.net_size()).sum();
+        self.filled_size - base_fees
+    }
+
+    /// 按累计成交数量和成交额更新成交进度
+    pub fn set_filled(&mut self, filled_size: Decimal, filled_notional: Decimal) {
+        self.filled_size = filled_size;
+        self.average_price = (filled_size > Decimal::ZERO).then(|| filled_notional / filled_size);
+    }
+
+    /// 记录一笔成交，并按累计数量推进为部分成交或完全成交
+    pub fn record_fill(&mut self, fill: Fill) {
+        let notional = self.filled_notional() + fill.price * fill.size;
+        self.set_filled(self.filled_size + fill.size, notional);
+        self.status = if self.filled_size >= self.size {
+            OrderStatus::Filled
+        } else {
+            OrderStatus::PartiallyFilled
+        };
+        self.fills.push(fill);
+    }
+}
+
+/// 手续费的计费资产
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeAsset {
+    /// 计价资产，如 BTCUSDT 的 USDT
+    #[default]
+    Quote,
+    /// 基础资产，买入时直接从到账数量中扣除
+    Base,
+    /// 第三方资产，如用 BNB 抵扣手续费
+    Other(String),
+}
+
+impl FeeAsset {
+    /// 按交易对识别交易所返回的手续费资产
+    pub fn of(symbol: &Symbol, asset: &str) -> Self {
+        if symbol.base() == asset {
+            Self::Base
+        } else if symbol.quote() == asset {
+            Self::Quote
+        } else {
+            Self::Other(asset.to_string())
+        }
+    }
+}
+
+/// 成交记录
+///
+/// `size` 为成交数量，`fee` 以 `fee_asset` 计价。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fill {
+    pub order_id: String,
+    pub symbol: Symbol,
+    pub side: Side,
+    pub price: Decimal,
+    pub size: Decimal,
+    pub fee: Decimal,
+    #[serde(default)]
+    pub fee_asset: FeeAsset,
+    /// 合约双向持仓模式下成交所属的持仓方向，其余为 `Both`
+    #[serde(default)]
+    pub position_side: PositionSide,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Fill {
+    /// 实际到账的数量：买入时扣除以基础资产收取的手续费
+    pub fn net_size(&self) -> Decimal {
+        match (self.side, &self.fee_asset) {
+            (Side::Buy, FeeAsset::Base) => self.size - self.fee,
+            _ => self.size,
+        }
+    }
+
+    /// 折算为计价资产的手续费；第三方资产无法按本交易对折算，记为零
+    pub fn quote_fee(&self) -> Decimal {
+        match self.fee_asset {
+            FeeAsset::Quote => self.fee,
+            FeeAsset::Base => self.fee * self.price,
+            FeeAsset::Other(_) => Decimal::ZERO,
+        }
+    }
+}
+
+/// 账户权益快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub timestamp: DateTime<Utc>,
+    pub equity: Decimal,
+    pub cash: Decimal,
+    pub position_value: Decimal,
+}
@@ -0,0 +1,576 @@
+// src/types/order.rs
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+use super::Symbol;
+use crate::error::Error;
+
+/// 买卖方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Side::Buy => write!(f, "buy"),
+            Side::Sell => write!(f, "sell"),
+        }
+    }
+}
+
+impl Side {
+    /// 反方向：平仓单和止损单与开仓方向相反
+    pub fn opposite(&self) -> Self {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+}
+
+/// 合约持仓方向，单向持仓模式下为 `Both`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PositionSide {
+    #[default]
+    Both,
+    Long,
+    Short,
+}
+
+impl fmt::Display for PositionSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PositionSide::Both => write!(f, "BOTH"),
+            PositionSide::Long => write!(f, "LONG"),
+            PositionSide::Short => write!(f, "SHORT"),
+        }
+    }
+}
+
+/// 订单类型，与 Binance 现货的订单类型一一对应
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OrderKind {
+    Market,
+    Limit,
+    /// 触发后以市价成交
+    StopLoss,
+    /// 触发后以限价挂单
+    StopLossLimit,
+    TakeProfit,
+    TakeProfitLimit,
+    /// 只做 maker，会立即成交时拒绝
+    LimitMaker,
+}
+
+impl OrderKind {
+    pub const ALL: [OrderKind; 7] = [
+        OrderKind::Market,
+        OrderKind::Limit,
+        OrderKind::StopLoss,
+        OrderKind::StopLossLimit,
+        OrderKind::TakeProfit,
+        OrderKind::TakeProfitLimit,
+        OrderKind::LimitMaker,
+    ];
+
+    /// 是否需要限价
+    pub fn has_limit_price(&self) -> bool {
+        matches!(
+            self,
+            OrderKind::Limit
+                | OrderKind::StopLossLimit
+                | OrderKind::TakeProfitLimit
+                | OrderKind::LimitMaker
+        )
+    }
+
+    /// 是否需要触发价
+    pub fn has_trigger_price(&self) -> bool {
+        matches!(
+            self,
+            OrderKind::StopLoss
+                | OrderKind::StopLossLimit
+                | OrderKind::TakeProfit
+                | OrderKind::TakeProfitLimit
+        )
+    }
+
+    /// 是否接受有效期；`LimitMaker` 只挂单，不需要有效期
+    pub fn accepts_time_in_force(&self) -> bool {
+        matches!(
+            self,
+            OrderKind::Limit | OrderKind::StopLossLimit | OrderKind::TakeProfitLimit
+        )
+    }
+}
+
+impl fmt::Display for OrderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderKind::Market => write!(f, "market"),
+            OrderKind::Limit => write!(f, "limit"),
+            OrderKind::StopLoss => write!(f, "stop_loss"),
+            OrderKind::StopLossLimit => write!(f, "stop_loss_limit"),
+            OrderKind::TakeProfit => write!(f, "take_profit"),
+            OrderKind::TakeProfitLimit => write!(f, "take_profit_limit"),
+            OrderKind::LimitMaker => write!(f, "limit_maker"),
+        }
+    }
+}
+
+/// 限价单的有效期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimeInForce {
+    /// 成交或撤销前一直有效
+    Gtc,
+    /// 立即成交，剩余部分撤销
+    Ioc,
+    /// 全部立即成交，否则撤销
+    Fok,
+}
+
+impl fmt::Display for TimeInForce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeInForce::Gtc => write!(f, "GTC"),
+            TimeInForce::Ioc => write!(f, "IOC"),
+            TimeInForce::Fok => write!(f, "FOK"),
+        }
+    }
+}
+
+/// 订单状态，取值与 Binance 一致
+///
+/// `New` 和 `PartiallyFilled` 为未完结状态，其余为终态，终态不再变化。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderStatus {
+    New,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    Rejected,
+    /// IOC、FOK 未能成交或被撮合引擎过期
+    #[serde(alias = "EXPIRED_IN_MATCH")]
+    Expired,
+}
+
+impl OrderStatus {
+    /// 仍在挂单簿上，可能继续成交
+    pub fn is_open(&self) -> bool {
+        matches!(self, OrderStatus::New | OrderStatus::PartiallyFilled)
+    }
+
+    /// 状态只能向前推进；重复收到同一状态视为合法
+    pub fn can_transition_to(&self, next: OrderStatus) -> bool {
+        if *self == next {
+            return true;
+        }
+        match self {
+            OrderStatus::New => true,
+            // 部分成交后不会再被拒绝
+            OrderStatus::PartiallyFilled => {
+                next != OrderStatus::New && next != OrderStatus::Rejected
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for OrderStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrderStatus::New => write!(f, "NEW"),
+            OrderStatus::PartiallyFilled => write!(f, "PARTIALLY_FILLED"),
+            OrderStatus::Filled => write!(f, "FILLED"),
+            OrderStatus::Canceled => write!(f, "CANCELED"),
+            OrderStatus::Rejected => write!(f, "REJECTED"),
+            OrderStatus::Expired => write!(f, "EXPIRED"),
+        }
+    }
+}
+
+/// 下单请求
+///
+/// 用 `market`、`limit`、`stop_loss` 等构造函数创建，保证价格字段与订单类型匹配；
+/// 各交易所适配器再拒绝自身不支持的组合。
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderRequest {
+    pub symbol: Symbol,
+    pub side: Side,
+    pub kind: OrderKind,
+    pub size: Decimal,
+    /// 限价
+    pub price: Option<Decimal>,
+    /// 止损、止盈的触发价
+    pub trigger_price: Option<Decimal>,
+    /// 未指定时限价类订单按 GTC 处理
+    pub time_in_force: Option<TimeInForce>,
+    /// 客户端订单号，重发同一请求时用于在交易所查重
+    pub client_order_id: Option<String>,
+    /// 合约双向持仓模式下开平的持仓方向
+    pub position_side: Option<PositionSide>,
+    /// 只减少持仓，不会开出反向仓位（合约）
+    pub reduce_only: bool,
+    /// 触发后平掉全部持仓，不带数量（合约）
+    pub close_position: bool,
+}
+
+impl OrderRequest {
+    fn new(symbol: &Symbol, side: Side, kind: OrderKind, size: Decimal) -> Self {
+        Self {
+            symbol: symbol.clone(),
+            side,
+            kind,
+            size,
+            price: None,
+            trigger_price: None,
+            time_in_force: None,
+            client_order_id: None,
+            position_side: None,
+            reduce_only: false,
+            close_position: false,
+        }
+    }
+
+    pub fn market(symbol: &Symbol, side: Side, size: Decimal) -> Self {
+        Self::new(symbol, side, OrderKind::Market, size)
+    }
+
+    pub fn limit(symbol: &Symbol, side: Side, size: Decimal, price: Decimal) -> Self {
+        Self {
+            price: Some(price),
+            ..Self::new(symbol, side, OrderKind::Limit, size)
+        }
+    }
+
+    pub fn limit_maker(symbol: &Symbol, side: Side, size: Decimal, price: Decimal) -> Self {
+        Self {
+            price: Some(price),
+            ..Self::new(symbol, side, OrderKind::LimitMaker, size)
+        }
+    }
+
+    pub fn stop_loss(symbol: &Symbol, side: Side, size: Decimal, trigger_price: Decimal) -> Self {
+        Self {
+            trigger_price: Some(trigger_price),
+            ..Self::new(symbol, side, OrderKind::StopLoss, size)
+        }
+    }
+
+    pub fn stop_loss_limit(
+        symbol: &Symbol,
+        side: Side,
+        size: Decimal,
+        trigger_price: Decimal,
+        price: Decimal,
+    ) -> Self {
+        Self {
+            price: Some(price),
+            trigger_price: Some(trigger_price),
+            ..Self::new(symbol, side, OrderKind::StopLossLimit, size)
+        }
+    }
+
+    pub fn take_profit(symbol: &Symbol, side: Side, size: Decimal, trigger_price: Decimal) -> Self {
+        Self {
+            trigger_price: Some(trigger_price),
+            ..Self::new(symbol, side, OrderKind::TakeProfit, size)
+        }
+    }
+
+    pub fn take_profit_limit(
+        symbol: &Symbol,
+        side: Side,
+        size: Decimal,
+        trigger_price: Decimal,
+        price: Decimal,
+    ) -> Self {
+        Self {
+            price: Some(price),
+            trigger_price: Some(trigger_price),
+            ..Self::new(symbol, side, OrderKind::TakeProfitLimit, size)
+        }
+    }
+
+    /// 触发后以市价平掉全部持仓的止损或止盈单，`kind` 为 `StopLoss` 或 `TakeProfit`
+    pub fn close_position(
+        symbol: &Symbol,
+        side: Side,
+        kind: OrderKind,
+        trigger_price: Decimal,
+    ) -> Self {
+        Self {
+            trigger_price: Some(trigger_price),
+            close_position: true,
+            ..Self::new(symbol, side, kind, Decimal::ZERO)
+        }
+    }
+
+    pub fn with_time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = Some(time_in_force);
+        self
+    }
+
+    pub fn with_client_order_id(mut self, client_order_id: impl Into<String>) -> Self {
+        self.client_order_id = Some(client_order_id.into());
+        self
+    }
+
+    /// 按下单意图生成客户端订单号：交易对、方向、触发信号的K线时间和用途相同的请求
+    /// 得到同一个订单号，重启后重发也能在交易所查到之前的订单
+    pub fn with_intent(self, signal_time: DateTime<Utc>, purpose: &str) -> Self {
+        let intent = format!(
+            "{}:{}:{}:{}",
+            self.symbol,
+            self.side,
+            signal_time.timestamp_millis(),
+            purpose
+        );
+        let digest = Sha256::digest(intent.as_bytes());
+        // Binance 限制 36 位
+        self.with_client_order_id(format!("rqt-{}", hex::encode(&digest[..16])))
+    }
+
+    pub fn with_position_side(mut self, position_side: PositionSide) -> Self {
+        self.position_side = Some(position_side);
+        self
+    }
+
+    pub fn with_reduce_only(mut self) -> Self {
+        self.reduce_only = true;
+        self
+    }
+
+    /// 是否使用了只有合约支持的持仓方向、只减仓或平仓参数
+    pub fn has_futures_options(&self) -> bool {
+        self.position_side.is_some() || self.reduce_only || self.close_position
+    }
+
+    /// 实际生效的有效期，限价类订单默认 GTC
+    pub fn effective_time_in_force(&self) -> Option<TimeInForce> {
+        match self.time_in_force {
+            Some(tif) => Some(tif),
+            None if self.kind.accepts_time_in_force() => Some(TimeInForce::Gtc),
+            None => None,
+        }
+    }
+
+    /// 检查数量、价格字段和有效期是否与订单类型匹配
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.close_position {
+            if !matches!(self.kind, OrderKind::StopLoss | OrderKind::TakeProfit) {
+                return Err(Error::invalid_order(format!(
+                    "{} order cannot close the position",
+                    self.kind
+                )));
+            }
+            if !self.size.is_zero() || self.reduce_only {
+                return Err(Error::invalid_order(
+                    "close position order does not take a size or reduce only",
+                ));
+            }
+        } else if self.size <= Decimal::ZERO {
+            return Err(Error::invalid_order(format!(
+                "invalid order size {}",
+                self.size
+            )));
+        }
+
+        let check = |name: &str, value: Option<Decimal>, required: bool| match (value, required) {
+            (Some(v), true) if v <= Decimal::ZERO => Err(Error::invalid_order(format!(
+                "invalid {} {} for {} order",
+                name, v, self.kind
+            ))),
+            (None, true) => Err(Error::invalid_order(format!(
+                "{} order requires a {}",
+                self.kind, name
+            ))),
+            (Some(_), false) => Err(Error::invalid_order(format!(
+                "{} order does not take a {}",
+                self.kind, name
+            ))),
+            _ => Ok(()),
+        };
+        check("price", self.price, self.kind.has_limit_price())?;
+        check(
+            "trigger price",
+            self.trigger_price,
+            self.kind.has_trigger_price(),
+        )?;
+
+        if let Some(tif) = self.time_in_force {
+            if !self.kind.accepts_time_in_force() {
+                return Err(Error::invalid_order(format!(
+                    "{} order does not take time in force {}",
+                    self.kind, tif
+                )));
+            }
+        }
+
+        // Binance 要求 1 到 36 位的字母、数字和 `.:/_-`
+        if let Some(id) = &self.client_order_id {
+            let valid_char = |c: char| c.is_ascii_alphanumeric() || ".:/_-".contains(c);
+            if id.is_empty() || id.len() > 36 || !id.chars().all(valid_char) {
+                return Err(Error::invalid_order(format!(
+                    "invalid client order id {:?}",
+                    id
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::test_symbol;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_validate() {
+        let btc = test_symbol("BTCUSDT");
+
+        assert!(OrderRequest::market(&btc, Side::Buy, dec!(1))
+            .validate()
+            .is_ok());
+        assert!(OrderRequest::market(&btc, Side::Buy, dec!(0))
+            .validate()
+            .is_err());
+        assert!(
+            OrderRequest::stop_loss_limit(&btc, Side::Sell, dec!(1), dec!(90), dec!(89))
+                .with_time_in_force(TimeInForce::Ioc)
+                .validate()
+                .is_ok()
+        );
+        assert!(OrderRequest::limit(&btc, Side::Buy, dec!(1), dec!(-1))
+            .validate()
+            .is_err());
+
+        // 市价单和 LIMIT_MAKER 不接受有效期
+        assert!(OrderRequest::market(&btc, Side::Buy, dec!(1))
+            .with_time_in_force(TimeInForce::Gtc)
+            .validate()
+            .is_err());
+        assert!(
+            OrderRequest::limit_maker(&btc, Side::Buy, dec!(1), dec!(95))
+                .with_time_in_force(TimeInForce::Gtc)
+                .validate()
+                .is_err()
+        );
+
+        // 手动拼出的请求缺少触发价或多带限价
+        let mut stop = OrderRequest::stop_loss(&btc, Side::Sell, dec!(1), dec!(90));
+        stop.price = Some(dec!(89));
+        assert!(stop.validate().is_err());
+        stop.price = None;
+        stop.trigger_price = None;
+        assert!(stop.validate().is_err());
+
+        let market = OrderRequest::market(&btc, Side::Buy, dec!(1));
+        assert!(market
+            .clone()
+            .with_client_order_id("rqt-1700000000000-1")
+            .validate()
+            .is_ok());
+        assert!(market
+            .clone()
+            .with_client_order_id("has space")
+            .validate()
+            .is_err());
+        assert!(market
+            .with_client_order_id("x".repeat(37))
+            .validate()
+            .is_err());
+
+        // 平仓单不带数量，只能是市价触发的止损止盈
+        let close = OrderRequest::close_position(&btc, Side::Sell, OrderKind::StopLoss, dec!(90));
+        assert!(close.validate().is_ok());
+        assert!(close.has_futures_options());
+        assert!(close.clone().with_reduce_only().validate().is_err());
+        assert!(
+            OrderRequest::close_position(&btc, Side::Sell, OrderKind::Limit, dec!(90))
+                .validate()
+                .is_err()
+        );
+        let mut sized = close;
+        sized.size = dec!(1);
+        assert!(sized.validate().is_err());
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        use OrderStatus::*;
+
+        assert!(New.can_transition_to(PartiallyFilled));
+        assert!(New.can_transition_to(Rejected));
+        assert!(PartiallyFilled.can_transition_to(PartiallyFilled));
+        assert!(PartiallyFilled.can_transition_to(Canceled));
+        assert!(!PartiallyFilled.can_transition_to(New));
+        assert!(!PartiallyFilled.can_transition_to(Rejected));
+        assert!(Filled.can_transition_to(Filled));
+        assert!(!Filled.can_transition_to(Canceled));
+        assert!(!Canceled.can_transition_to(New));
+
+        assert!(PartiallyFilled.is_open());
+        assert!(!Expired.is_open());
+        assert_eq!(
+            serde_json::from_str::<OrderStatus>(r#""EXPIRED_IN_MATCH""#).unwrap(),
+            Expired
+        );
+        assert_eq!(PartiallyFilled.to_string(), "PARTIALLY_FILLED");
+    }
+
+    #[test]
+    fn test_intent_client_order_id() {
+        let btc = test_symbol("BTCUSDT");
+        let bar = chrono::TimeZone::with_ymd_and_hms(&Utc, 2024, 1, 1, 0, 0, 0).unwrap();
+        let id = |request: OrderRequest| request.client_order_id.unwrap();
+
+        // 同一意图每次生成相同的订单号，与数量和进程无关
+        let open = id(OrderRequest::market(&btc, Side::Buy, dec!(1)).with_intent(bar, "open"));
+        assert_eq!(
+            open,
+            id(OrderRequest::market(&btc, Side::Buy, dec!(2)).with_intent(bar, "open"))
+        );
+        assert_eq!(open.len(), 36);
+        assert!(OrderRequest::market(&btc, Side::Buy, dec!(1))
+            .with_intent(bar, "open")
+            .validate()
+            .is_ok());
+
+        // 用途、方向或信号K线不同时订单号不同
+        let others = [
+            OrderRequest::market(&btc, Side::Buy, dec!(1)).with_intent(bar, "close"),
+            OrderRequest::market(&btc, Side::Sell, dec!(1)).with_intent(bar, "open"),
+            OrderRequest::market(&btc, Side::Buy, dec!(1))
+                .with_intent(bar + chrono::Duration::minutes(1), "open"),
+        ];
+        for other in others {
+            assert_ne!(open, id(other));
+        }
+    }
+
+    #[test]
+    fn test_default_time_in_force() {
+        let btc = test_symbol("BTCUSDT");
+        assert_eq!(
+            OrderRequest::limit(&btc, Side::Buy, dec!(1), dec!(95)).effective_time_in_force(),
+            Some(TimeInForce::Gtc)
+        );
+        assert_eq!(
+            OrderRequest::take_profit(&btc, Side::Sell, dec!(1), dec!(120))
+                .effective_time_in_force(),
+            None
+        );
+    }
+}
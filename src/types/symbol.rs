@@ -0,0 +1,335 @@
+// src/types/symbol.rs
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+use super::{OrderKind, OrderRequest};
+use crate::error::Error;
+
+/// Binance 现货常见的计价资产，按长度从长到短匹配交易对后缀
+const BINANCE_QUOTES: [&str; 13] = [
+    "FDUSD", "USDT", "USDC", "TUSD", "BUSD", "BTC", "ETH", "BNB", "EUR", "TRY", "BRL", "JPY", "DAI",
+];
+
+/// A 股以人民币计价
+const A_SHARE_QUOTE: &str = "CNY";
+
+/// 交易场所
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Venue {
+    Binance,
+    /// 上海证券交易所（`.SH`）
+    Sse,
+    /// 深圳证券交易所（`.SZ`）
+    Szse,
+    /// 北京证券交易所（`.BJ`）
+    Bse,
+}
+
+impl Venue {
+    /// Tushare 代码的交易所后缀
+    fn suffix(&self) -> Option<&'static str> {
+        match self {
+            Venue::Binance => None,
+            Venue::Sse => Some("SH"),
+            Venue::Szse => Some("SZ"),
+            Venue::Bse => Some("BJ"),
+        }
+    }
+}
+
+/// 交易对：场所、基础资产和计价资产
+///
+/// `Display` 和 `FromStr` 使用各场所的原生写法，如 `BTCUSDT`、`600000.SH`。
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol {
+    venue: Venue,
+    base: String,
+    quote: String,
+}
+
+impl Symbol {
+    pub fn new(venue: Venue, base: impl Into<String>, quote: impl Into<String>) -> Self {
+        Self {
+            venue,
+            base: base.into().to_uppercase(),
+            quote: quote.into().to_uppercase(),
+        }
+    }
+
+    pub fn binance(base: impl Into<String>, quote: impl Into<String>) -> Self {
+        Self::new(Venue::Binance, base, quote)
+    }
+
+    pub fn venue(&self) -> Venue {
+        self.venue
+    }
+
+    /// 基础资产，A 股为证券代码
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    pub fn quote(&self) -> &str {
+        &self.quote
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.venue.suffix() {
+            Some(suffix) => write!(f, "{}.{}", self.base, suffix),
+            None => write!(f, "{}{}", self.base, self.quote),
+        }
+    }
+}
+
+impl FromStr for Symbol {
+    type Err = Error;
+
+    /// 支持 `600000.SH`、`BTC/USDT` 和 `BTCUSDT` 三种写法
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::ParseError(format!("invalid symbol: {}", s));
+        let s_upper = s.trim().to_uppercase();
+
+        if let Some((code, suffix)) = s_upper.split_once('.') {
+            let venue = [Venue::Sse, Venue::Szse, Venue::Bse]
+                .into_iter()
+                .find(|venue| venue.suffix() == Some(suffix))
+                .ok_or_else(invalid)?;
+            if code.is_empty() || !code.chars().all(|c| c.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            return Ok(Symbol::new(venue, code, A_SHARE_QUOTE));
+        }
+
+        if let Some((base, quote)) = s_upper.split_once('/') {
+            if base.is_empty() || quote.is_empty() {
+                return Err(invalid());
+            }
+            return Ok(Symbol::binance(base, quote));
+        }
+
+        BINANCE_QUOTES
+            .iter()
+            .find_map(|quote| {
+                s_upper
+                    .strip_suffix(quote)
+                    .filter(|base| !base.is_empty())
+                    .map(|base| Symbol::binance(base, *quote))
+            })
+            .ok_or_else(invalid)
+    }
+}
+
+impl Serialize for Symbol {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Symbol {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// 交易所给出的交易对规则，未设置的限制为 `None`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolInfo {
+    pub symbol: Symbol,
+    /// 当前是否可交易
+    pub trading: bool,
+    /// 价格最小变动单位
+    pub tick_size: Option<Decimal>,
+    /// 数量最小变动单位
+    pub step_size: Option<Decimal>,
+    pub min_size: Option<Decimal>,
+    pub max_size: Option<Decimal>,
+    /// 最小成交额
+    pub min_notional: Option<Decimal>,
+    /// 支持的订单类型
+    pub order_kinds: Vec<OrderKind>,
+}
+
+impl SymbolInfo {
+    /// 可交易、没有任何数量价格限制的交易对
+    pub fn new(symbol: Symbol) -> Self {
+        Self {
+            symbol,
+            trading: true,
+            tick_size: None,
+            step_size: None,
+            min_size: None,
+            max_size: None,
+            min_notional: None,
+            order_kinds: OrderKind::ALL.to_vec(),
+        }
+    }
+
+    /// 数量按步长向下取整
+    pub fn round_size(&self, size: Decimal) -> Decimal {
+        match self.step_size {
+            Some(step) => ((size / step).floor() * step).normalize(),
+            None => size,
+        }
+    }
+
+    /// 价格按最小变动价位取最接近的档位
+    pub fn round_price(&self, price: Decimal) -> Decimal {
+        match self.tick_size {
+            Some(tick) => ((price / tick).round() * tick).normalize(),
+            None => price,
+        }
+    }
+
+    /// 按交易规则取整数量和价格，不满足数量或成交额下限时拒绝
+    ///
+    /// 市价单没有价格，成交额按 `reference_price`（通常为最新价）估算；
+    /// 未提供时跳过成交额检查。
+    pub fn normalize(
+        &self,
+        request: &OrderRequest,
+        reference_price: Option<Decimal>,
+    ) -> Result<OrderRequest, Error> {
+        if !self.trading {
+            return Err(Error::invalid_order(format!(
+                "{} is not trading",
+                self.symbol
+            )));
+        }
+        if !self.order_kinds.contains(&request.kind) {
+            return Err(Error::invalid_order(format!(
+                "{} does not support {} orders",
+                self.symbol, request.kind
+            )));
+        }
+
+        let rounded = OrderRequest {
+            size: self.round_size(request.size),
+            price: request.price.map(|p| self.round_price(p)),
+            trigger_price: request.trigger_price.map(|p| self.round_price(p)),
+            ..request.clone()
+        };
+        // 平仓单的数量由交易所按持仓计算
+        if rounded.close_position {
+            rounded.validate()?;
+            return Ok(rounded);
+        }
+
+        if let Some(min_size) = self.min_size {
+            if rounded.size < min_size {
+                return Err(Error::invalid_order(format!(
+                    "{} quantity {} (rounded to {}) is below the minimum {}",
+                    self.symbol, request.size, rounded.size, min_size
+                )));
+            }
+        }
+        if let Some(max_size) = self.max_size {
+            if rounded.size > max_size {
+                return Err(Error::invalid_order(format!(
+                    "{} quantity {} exceeds the maximum {}",
+                    self.symbol, rounded.size, max_size
+                )));
+            }
+        }
+
+        let price = rounded.price.or(rounded.trigger_price).or(reference_price);
+        if let (Some(min_notional), Some(price)) = (self.min_notional, price) {
+            let notional = rounded.size * price;
+            if notional < min_notional {
+                return Err(Error::invalid_order(format!(
+                    "{} order value {} is below the minimum notional {}",
+                    self.symbol, notional, min_notional
+                )));
+            }
+        }
+
+        rounded.validate()?;
+        Ok(rounded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format() -> Result<(), Error> {
+        let btc: Symbol = "BTCUSDT".parse()?;
+        assert_eq!(btc, Symbol::binance("BTC", "USDT"));
+        assert_eq!(btc.to_string(), "BTCUSDT");
+        assert_eq!("eth/btc".parse::<Symbol>()?, Symbol::binance("ETH", "BTC"));
+        assert_eq!("ETHFDUSD".parse::<Symbol>()?.quote(), "FDUSD");
+
+        let stock: Symbol = "600000.SH".parse()?;
+        assert_eq!(stock.venue(), Venue::Sse);
+        assert_eq!(stock.base(), "600000");
+        assert_eq!(stock.quote(), "CNY");
+        assert_eq!(stock.to_string(), "600000.SH");
+        assert_eq!("000001.sz".parse::<Symbol>()?.venue(), Venue::Szse);
+
+        assert!("USDT".parse::<Symbol>().is_err());
+        assert!("600000.HK".parse::<Symbol>().is_err());
+        assert!("FOOBAR".parse::<Symbol>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_order() -> Result<(), Error> {
+        use crate::types::Side;
+        use rust_decimal_macros::dec;
+
+        let btc = Symbol::binance("BTC", "USDT");
+        let info = SymbolInfo {
+            tick_size: Some(dec!(0.01)),
+            step_size: Some(dec!(0.001)),
+            min_size: Some(dec!(0.001)),
+            max_size: Some(dec!(100)),
+            min_notional: Some(dec!(10)),
+            order_kinds: vec![OrderKind::Market, OrderKind::Limit],
+            ..SymbolInfo::new(btc.clone())
+        };
+
+        let limit = info.normalize(
+            &OrderRequest::limit(&btc, Side::Buy, dec!(0.12345678), dec!(43210.126)),
+            None,
+        )?;
+        assert_eq!(limit.size, dec!(0.123));
+        assert_eq!(limit.price, Some(dec!(43210.13)));
+        assert_eq!(limit.size.to_string(), "0.123");
+
+        // 取整后低于最小数量
+        assert!(matches!(
+            info.normalize(&OrderRequest::market(&btc, Side::Buy, dec!(0.0009)), None),
+            Err(Error::InvalidOrder(_))
+        ));
+        // 市价单按参考价估算成交额
+        let small = OrderRequest::market(&btc, Side::Buy, dec!(0.002));
+        assert!(info.normalize(&small, Some(dec!(4000))).is_err());
+        assert!(info.normalize(&small, Some(dec!(6000))).is_ok());
+        // 未开放的订单类型
+        assert!(info
+            .normalize(
+                &OrderRequest::stop_loss(&btc, Side::Sell, dec!(1), dec!(40000)),
+                None
+            )
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_serde_as_string() {
+        let symbols: Vec<Symbol> = serde_json::from_str(r#"["BTCUSDT", "600000.SH"]"#).unwrap();
+        assert_eq!(symbols[0], Symbol::binance("BTC", "USDT"));
+        assert_eq!(
+            serde_json::to_string(&symbols).unwrap(),
+            r#"["BTCUSDT","600000.SH"]"#
+        );
+        assert!(serde_json::from_str::<Symbol>(r#""BTC""#).is_err());
+    }
+}